@@ -26,6 +26,7 @@ fn build_protos() {
         .build_client(false)
         .out_dir(PROTO_GEN_PATH)
         .file_descriptor_set_path(FILE_DESCRIPTOR_PATH)
+        .boxed(".rustic_poker.GameStateUpdate.update.snapshot")
         .compile_protos(&[PROTO_PATH], &[PROTO_WATCH])
         .unwrap();
 }
@@ -0,0 +1,139 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::history::{HandHistory, HandHistoryAction};
+use crate::types::hand::HandRank;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum Achievement {
+    FirstPotWon,
+    FirstRoyalFlush, // shown at showdown
+    WinWithHighCard,
+    HandsPlayed100,
+    KnockOutThree, // in a single hand
+}
+
+/// A player's progress towards a single achievement.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct AchievementProgress {
+    pub progress: u32,
+    pub unlocked_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct AchievementUnlock {
+    pub player_id: Uuid,
+    pub achievement: Achievement,
+    pub unlocked_at: DateTime<Utc>,
+}
+
+impl Achievement {
+    pub const ALL: [Self; 5] = [
+        Self::FirstPotWon,
+        Self::FirstRoyalFlush,
+        Self::WinWithHighCard,
+        Self::HandsPlayed100,
+        Self::KnockOutThree,
+    ];
+
+    pub fn get_target(&self) -> u32 {
+        match self {
+            Achievement::HandsPlayed100 => 100,
+            Achievement::KnockOutThree => 3,
+            _ => 1,
+        }
+    }
+
+    /// Cumulative achievements add up across hands, the others only count their best single hand.
+    fn is_cumulative(&self) -> bool {
+        matches!(self, Achievement::HandsPlayed100)
+    }
+
+    fn measure(&self, facts: &HandFacts) -> u32 {
+        match self {
+            Achievement::FirstPotWon => u32::from(facts.won_pot),
+            Achievement::FirstRoyalFlush => u32::from(facts.shown_rank == Some(HandRank::RoyalFlush)),
+            Achievement::WinWithHighCard => u32::from(facts.won_pot && facts.shown_rank == Some(HandRank::HighCard)),
+            Achievement::HandsPlayed100 => 1,
+            Achievement::KnockOutThree => facts.knockouts,
+        }
+    }
+}
+
+impl AchievementProgress {
+    /// Returns whether the hand unlocked the achievement.
+    pub fn record(&mut self, achievement: Achievement, progress: u32, timestamp: DateTime<Utc>) -> bool {
+        if self.unlocked_at.is_some() {
+            return false;
+        }
+        self.progress = match achievement.is_cumulative() {
+            true => self.progress + progress,
+            false => self.progress.max(progress),
+        };
+        if self.progress < achievement.get_target() {
+            return false;
+        }
+        self.progress = achievement.get_target();
+        self.unlocked_at = Some(timestamp);
+        true
+    }
+}
+
+/// What a single hand amounted to for one of its players.
+pub(super) struct HandFacts {
+    won_pot: bool,
+    shown_rank: Option<HandRank>,
+    knockouts: u32, // players busting in a hand the player won a pot in
+}
+
+impl HandFacts {
+    pub fn from_history(player_id: &Uuid, history: &HandHistory) -> Self {
+        let mut won_pot = false;
+        let mut shown_rank = None;
+        for event in &history.events {
+            match &event.action {
+                HandHistoryAction::HandShown { player_id: showing_player_id, rank, .. } if showing_player_id == player_id => {
+                    shown_rank = Some(rank.clone());
+                },
+                HandHistoryAction::PotAwarded { player_id: winner_id, .. } if winner_id == player_id => {
+                    won_pot = true;
+                },
+                _ => {},
+            }
+        }
+
+        let net_results = history.get_net_results();
+        let knockouts = match won_pot {
+            true => history.seats
+                .iter()
+                .filter(|seat| seat.player_id != *player_id)
+                .filter(|seat| seat.starting_credits as i64 + net_results[&seat.player_id] <= 0)
+                .count() as u32,
+            false => 0,
+        };
+        HandFacts { won_pot, shown_rank, knockouts }
+    }
+
+    pub fn measure(&self, achievement: Achievement) -> u32 {
+        achievement.measure(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn record() {
+        let now = Utc::now();
+        let mut progress = AchievementProgress::default();
+        assert!(!progress.record(Achievement::HandsPlayed100, 99, now));
+        assert!(progress.record(Achievement::HandsPlayed100, 1, now));
+        assert!(!progress.record(Achievement::HandsPlayed100, 1, now));
+        assert_eq!(progress.progress, 100);
+        let mut progress = AchievementProgress::default();
+        assert!(!progress.record(Achievement::KnockOutThree, 2, now));
+        assert!(!progress.record(Achievement::KnockOutThree, 1, now));
+        assert_eq!(progress.progress, 2);
+        assert!(progress.record(Achievement::KnockOutThree, 4, now));
+    }
+}
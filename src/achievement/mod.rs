@@ -0,0 +1,6 @@
+mod achievement;
+mod proto;
+mod registry;
+
+pub(crate) use achievement::{Achievement, AchievementProgress, AchievementUnlock};
+pub(crate) use registry::AchievementRegistry;
@@ -0,0 +1,35 @@
+use crate::common::time::chrono_to_prost_timestamp;
+use crate::service::proto;
+use super::{Achievement, AchievementProgress, AchievementUnlock};
+
+impl From<Achievement> for proto::Achievement {
+    fn from(achievement: Achievement) -> Self {
+        match achievement {
+            Achievement::FirstPotWon => proto::Achievement::FirstPotWon,
+            Achievement::FirstRoyalFlush => proto::Achievement::FirstRoyalFlush,
+            Achievement::WinWithHighCard => proto::Achievement::WinWithHighCard,
+            Achievement::HandsPlayed100 => proto::Achievement::HandsPlayed100,
+            Achievement::KnockOutThree => proto::Achievement::KnockOutThree,
+        }
+    }
+}
+
+impl From<(Achievement, AchievementProgress)> for proto::AchievementProgress {
+    fn from((achievement, progress): (Achievement, AchievementProgress)) -> Self {
+        proto::AchievementProgress {
+            achievement: proto::Achievement::from(achievement) as i32,
+            progress: progress.progress,
+            target: achievement.get_target(),
+            unlocked_at: progress.unlocked_at.map(chrono_to_prost_timestamp),
+        }
+    }
+}
+
+impl From<AchievementUnlock> for proto::game_event::AchievementUnlocked {
+    fn from(unlock: AchievementUnlock) -> Self {
+        proto::game_event::AchievementUnlocked {
+            player_id: unlock.player_id.to_string(),
+            achievement: proto::Achievement::from(unlock.achievement) as i32,
+        }
+    }
+}
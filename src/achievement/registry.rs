@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::history::HandHistory;
+use super::achievement::HandFacts;
+use super::{Achievement, AchievementProgress, AchievementUnlock};
+
+/// Achievement progress of every player, derived from the recorded hand histories rather than persisted on its own.
+#[derive(Debug, Default)]
+pub(crate) struct AchievementRegistry {
+    player_achievements: HashMap<Uuid, HashMap<Achievement, AchievementProgress>>,
+}
+
+impl AchievementRegistry {
+    /// Achievements unlocked in the past don't get announced again.
+    pub fn restore(hand_histories: &[HandHistory]) -> Self {
+        let mut hand_histories: Vec<&HandHistory> = hand_histories.iter().collect();
+        hand_histories.sort_by_key(|history| history.started_at);
+        let mut registry = AchievementRegistry::default();
+        for history in hand_histories {
+            registry.record_hand(history);
+        }
+        registry
+    }
+
+    pub fn record_hand(&mut self, history: &HandHistory) -> Vec<AchievementUnlock> {
+        let unlocked_at = history.ended_at.unwrap_or(history.started_at);
+        let mut unlocks = Vec::new();
+        for seat in &history.seats {
+            let facts = HandFacts::from_history(&seat.player_id, history);
+            let achievements = self.player_achievements.entry(seat.player_id).or_default();
+            for achievement in Achievement::ALL {
+                let progress = facts.measure(achievement);
+                if progress == 0 {
+                    continue;
+                }
+                if achievements.entry(achievement).or_default().record(achievement, progress, unlocked_at) {
+                    unlocks.push(AchievementUnlock {
+                        player_id: seat.player_id,
+                        achievement,
+                        unlocked_at,
+                    });
+                }
+            }
+        }
+        unlocks
+    }
+
+    /// Every achievement, including the ones not worked towards yet.
+    pub fn get_achievements(&self, player_id: &Uuid) -> Vec<(Achievement, AchievementProgress)> {
+        let achievements = self.player_achievements.get(player_id);
+        Achievement::ALL
+            .into_iter()
+            .map(|achievement| {
+                let progress = achievements
+                    .and_then(|achievements| achievements.get(&achievement))
+                    .cloned()
+                    .unwrap_or_default();
+                (achievement, progress)
+            })
+            .collect()
+    }
+}
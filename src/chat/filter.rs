@@ -0,0 +1,61 @@
+use std::collections::HashSet;
+use std::fmt::Debug;
+
+/// Cleans up chat messages before they're delivered.
+pub(crate) trait ProfanityFilter: Debug + Send + Sync {
+    fn censor(&self, text: &str) -> String;
+}
+
+/// Masks listed words, matching whole words case-insensitively.
+#[derive(Debug, Default)]
+pub(crate) struct WordListProfanityFilter {
+    words: HashSet<String>,
+}
+
+impl WordListProfanityFilter {
+    pub fn new<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let words = words
+            .into_iter()
+            .map(|word| word.as_ref().trim().to_lowercase())
+            .filter(|word| !word.is_empty())
+            .collect();
+        WordListProfanityFilter { words }
+    }
+}
+
+impl ProfanityFilter for WordListProfanityFilter {
+    fn censor(&self, text: &str) -> String {
+        let mut censored = String::with_capacity(text.len());
+        let mut word = String::new();
+        for char in text.chars().chain(std::iter::once(' ')) {
+            if char.is_alphanumeric() {
+                word.push(char);
+                continue;
+            }
+            match self.words.contains(&word.to_lowercase()) {
+                true => censored.extend(word.chars().map(|_| '*')),
+                false => censored.push_str(&word),
+            }
+            word.clear();
+            censored.push(char);
+        }
+        censored.pop();
+        censored
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn censor() {
+        let filter = WordListProfanityFilter::new(["darn", " Heck "]);
+        assert_eq!(filter.censor("Darn it, what the heck!"), "**** it, what the ****!");
+        assert_eq!(filter.censor("darnation"), "darnation");
+        assert_eq!(filter.censor(""), "");
+    }
+}
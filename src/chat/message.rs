@@ -0,0 +1,13 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+#[derive(Clone, Debug)]
+pub(crate) struct ChatMessage {
+    pub message_id: Uuid,
+    pub lobby_id: Uuid,
+    pub sender_id: Uuid,
+    pub sender_name: String,
+    pub is_spectator: bool,
+    pub text: String,
+    pub timestamp: DateTime<Utc>,
+}
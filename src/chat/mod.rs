@@ -0,0 +1,8 @@
+mod filter;
+mod message;
+mod proto;
+mod registry;
+
+pub(crate) use filter::{ProfanityFilter, WordListProfanityFilter};
+pub(crate) use message::ChatMessage;
+pub(crate) use registry::ChatRegistry;
@@ -0,0 +1,17 @@
+use crate::common::time::chrono_to_prost_timestamp;
+use crate::service::proto;
+use super::ChatMessage;
+
+impl From<ChatMessage> for proto::ChatMessage {
+    fn from(message: ChatMessage) -> Self {
+        proto::ChatMessage {
+            message_id: message.message_id.to_string(),
+            lobby_id: message.lobby_id.to_string(),
+            sender_id: message.sender_id.to_string(),
+            sender_name: message.sender_name,
+            is_spectator: message.is_spectator,
+            text: message.text,
+            timestamp: Some(chrono_to_prost_timestamp(message.timestamp)),
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex, RwLock};
+use chrono::{TimeDelta, Utc};
+use uuid::Uuid;
+
+use crate::common::error::AppError;
+use super::{ChatMessage, ProfanityFilter, WordListProfanityFilter};
+
+/// Chat state of every lobby, plus each player's personal mute list.<br />
+/// Guarded by std locks, as mute lists get checked synchronously while filtering event streams.
+#[derive(Debug)]
+pub(crate) struct ChatRegistry {
+    lobby_chats: Mutex<HashMap<Uuid, LobbyChat>>,
+    player_mutes: RwLock<HashMap<Uuid, HashSet<Uuid>>>,
+    profanity_filter: Arc<dyn ProfanityFilter>,
+}
+
+#[derive(Debug, Default)]
+struct LobbyChat {
+    history: VecDeque<ChatMessage>,
+    muted_player_ids: HashSet<Uuid>, // by the host
+    recent_messages: HashMap<Uuid, VecDeque<ChatMessage>>, // per sender, for rate limiting
+}
+
+impl Default for ChatRegistry {
+    fn default() -> Self {
+        Self::new(Arc::new(WordListProfanityFilter::default()))
+    }
+}
+
+impl ChatRegistry {
+    pub const MAX_MESSAGE_LENGTH: usize = 280;
+    const HISTORY_SIZE: usize = 50;
+    const RATE_LIMIT_MESSAGES: usize = 5;
+    const RATE_LIMIT_WINDOW_SECONDS: i64 = 10;
+
+    pub fn new(profanity_filter: Arc<dyn ProfanityFilter>) -> Self {
+        ChatRegistry {
+            lobby_chats: Mutex::default(),
+            player_mutes: RwLock::default(),
+            profanity_filter,
+        }
+    }
+
+    pub fn post_message(
+        &self,
+        lobby_id: Uuid,
+        sender_id: Uuid,
+        sender_name: String,
+        is_spectator: bool,
+        text: &str,
+    ) -> Result<ChatMessage, AppError> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err(AppError::invalid_request("Chat messages may not be empty!"));
+        }
+        if text.chars().count() > Self::MAX_MESSAGE_LENGTH {
+            return Err(
+                AppError::invalid_request(
+                    format!("Chat messages may not exceed {} characters!", Self::MAX_MESSAGE_LENGTH)
+                )
+            );
+        }
+
+        let mut lobby_chats = self.lobby_chats
+            .lock()
+            .map_err(|_| AppError::internal("Chat registry poisoned!"))?;
+        let lobby_chat = lobby_chats.entry(lobby_id).or_default();
+        if lobby_chat.muted_player_ids.contains(&sender_id) {
+            return Err(AppError::unauthorized("You've been muted by the host!"));
+        }
+
+        let now = Utc::now();
+        let recent_messages = lobby_chat.recent_messages.entry(sender_id).or_default();
+        recent_messages.retain(|message| {
+            now - message.timestamp < TimeDelta::seconds(Self::RATE_LIMIT_WINDOW_SECONDS)
+        });
+        if recent_messages.len() >= Self::RATE_LIMIT_MESSAGES {
+            return Err(
+                AppError::rate_limited(
+                    format!(
+                        "Chat rate limit ({} messages per {}s) exceeded!",
+                        Self::RATE_LIMIT_MESSAGES,
+                        Self::RATE_LIMIT_WINDOW_SECONDS,
+                    )
+                )
+            );
+        }
+
+        let message = ChatMessage {
+            message_id: Uuid::new_v4(),
+            lobby_id,
+            sender_id,
+            sender_name,
+            is_spectator,
+            text: self.profanity_filter.censor(text),
+            timestamp: now,
+        };
+        recent_messages.push_back(message.clone());
+        lobby_chat.history.push_back(message.clone());
+        if lobby_chat.history.len() > Self::HISTORY_SIZE {
+            lobby_chat.history.pop_front();
+        }
+        Ok(message)
+    }
+
+    /// Most recent messages, oldest first, minus the ones by players the viewer muted.
+    pub fn get_history(&self, lobby_id: &Uuid, viewer_id: &Uuid) -> Vec<ChatMessage> {
+        let Ok(lobby_chats) = self.lobby_chats.lock() else {
+            return Vec::new();
+        };
+        lobby_chats
+            .get(lobby_id)
+            .map(|lobby_chat| {
+                lobby_chat.history
+                    .iter()
+                    .filter(|message| !self.is_muted_by(viewer_id, &message.sender_id))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Host-level mutes keep players from chatting in the lobby altogether.
+    pub fn set_host_mute(&self, lobby_id: Uuid, player_id: Uuid, muted: bool) -> Result<(), AppError> {
+        let mut lobby_chats = self.lobby_chats
+            .lock()
+            .map_err(|_| AppError::internal("Chat registry poisoned!"))?;
+        let lobby_chat = lobby_chats.entry(lobby_id).or_default();
+        match muted {
+            true => lobby_chat.muted_player_ids.insert(player_id),
+            false => lobby_chat.muted_player_ids.remove(&player_id),
+        };
+        Ok(())
+    }
+
+    /// Personal mutes only hide the muted player's messages from the muting one.
+    pub fn set_player_mute(&self, player_id: Uuid, muted_player_id: Uuid, muted: bool) -> Result<(), AppError> {
+        if player_id == muted_player_id {
+            return Err(AppError::invalid_request("Players can't mute themselves!"));
+        }
+        let mut player_mutes = self.player_mutes
+            .write()
+            .map_err(|_| AppError::internal("Chat registry poisoned!"))?;
+        let muted_player_ids = player_mutes.entry(player_id).or_default();
+        match muted {
+            true => muted_player_ids.insert(muted_player_id),
+            false => muted_player_ids.remove(&muted_player_id),
+        };
+        Ok(())
+    }
+
+    /// Drops a removed lobby's chat history, host mutes and rate limiting state.
+    pub fn remove_lobby(&self, lobby_id: &Uuid) {
+        if let Ok(mut lobby_chats) = self.lobby_chats.lock() {
+            lobby_chats.remove(lobby_id);
+        }
+    }
+
+    pub fn is_muted_by(&self, player_id: &Uuid, sender_id: &Uuid) -> bool {
+        self.player_mutes
+            .read()
+            .map(|player_mutes| {
+                player_mutes
+                    .get(player_id)
+                    .is_some_and(|muted_player_ids| muted_player_ids.contains(sender_id))
+            })
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn post_message() {
+        let registry = ChatRegistry::default();
+        let (lobby_id, sender_id, viewer_id) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let too_long = "a".repeat(ChatRegistry::MAX_MESSAGE_LENGTH + 1);
+        assert!(registry.post_message(lobby_id, sender_id, String::from("Sender"), false, &too_long).is_err());
+        for _ in 0..ChatRegistry::RATE_LIMIT_MESSAGES {
+            registry.post_message(lobby_id, sender_id, String::from("Sender"), false, " hi ").unwrap();
+        }
+        let err = registry.post_message(lobby_id, sender_id, String::from("Sender"), false, "hi");
+        assert!(matches!(err, Err(AppError::RateLimited(_))));
+        assert_eq!(registry.get_history(&lobby_id, &viewer_id)[0].text, "hi");
+        registry.set_player_mute(viewer_id, sender_id, true).unwrap();
+        assert!(registry.get_history(&lobby_id, &viewer_id).is_empty());
+        registry.set_host_mute(lobby_id, viewer_id, true).unwrap();
+        assert!(registry.post_message(lobby_id, viewer_id, String::from("Viewer"), false, "hi").is_err());
+        registry.remove_lobby(&lobby_id);
+        assert!(registry.lobby_chats.lock().unwrap().is_empty());
+    }
+}
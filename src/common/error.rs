@@ -14,6 +14,10 @@ pub enum AppError {
     Unauthorized(String),
     #[error("Invalid Request: {0}")]
     InvalidRequest(String),
+    #[error("Data Loss: {0}")]
+    DataLoss(String),
+    #[error("Rate Limited: {0}")]
+    RateLimited(String),
 }
 
 impl AppError {
@@ -48,6 +52,20 @@ impl AppError {
     {
         Self::InvalidRequest(info.to_string())
     }
+
+    pub fn data_loss<S>(info: S) -> Self
+    where
+        S: ToString,
+    {
+        Self::DataLoss(info.to_string())
+    }
+
+    pub fn rate_limited<S>(info: S) -> Self
+    where
+        S: ToString,
+    {
+        Self::RateLimited(info.to_string())
+    }
 }
 
 impl From<AppError> for Status {
@@ -58,6 +76,8 @@ impl From<AppError> for Status {
             AppError::PreconditionFailed(err) => Status::failed_precondition(err),
             AppError::Unauthorized(err) => Status::permission_denied(err),
             AppError::InvalidRequest(err) => Status::invalid_argument(err),
+            AppError::DataLoss(err) => Status::data_loss(err),
+            AppError::RateLimited(err) => Status::resource_exhausted(err),
         }
     }
 }
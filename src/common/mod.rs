@@ -1 +1,3 @@
 pub mod error;
+pub(crate) mod stream;
+pub(crate) mod time;
@@ -0,0 +1,69 @@
+use std::collections::VecDeque;
+use std::future::Future;
+use chrono::{DateTime, TimeDelta, Utc};
+use futures::Stream;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::common::error::AppError;
+
+/// Yields broadcast items once they're `delay` old, without pausing the receiver in the meantime.<br />
+/// Items the viewer isn't meant to see get skipped. The stream ends along with the channel.<br />
+/// Whenever the receiver lags behind, `resync` is handed the number of skipped items and may
+/// provide a fresh item to catch up with; failing that, the stream drains and ends with its error.
+pub(crate) fn delayed_broadcast_stream<T, U, F, R, Fut>(
+    mut receiver: broadcast::Receiver<Option<T>>,
+    mut pending: VecDeque<U>,
+    delay: TimeDelta,
+    view: F,
+    get_timestamp: fn(&U) -> DateTime<Utc>,
+    mut resync: R,
+) -> impl Stream<Item = Result<U, AppError>> + Send
+where
+    T: Clone + Send + 'static,
+    U: Send + 'static,
+    F: Fn(&T) -> Option<U> + Send + 'static,
+    R: FnMut(u64) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<T, AppError>> + Send,
+{
+    async_stream::stream! {
+        let mut is_receiving = true;
+        let mut failure = None;
+        while is_receiving || !pending.is_empty() {
+            let release_in = pending
+                .front()
+                .map(|item| (get_timestamp(item) + delay - Utc::now()).to_std().unwrap_or_default());
+            let mut skipped = None;
+            let released = tokio::select! {
+                received = receiver.recv(), if is_receiving => {
+                    match received {
+                        Ok(Some(item)) => pending.extend(view(&item)),
+                        Err(RecvError::Lagged(count)) => skipped = Some(count),
+                        Ok(None) | Err(RecvError::Closed) => is_receiving = false,
+                    }
+                    None
+                },
+                _ = tokio::time::sleep(release_in.unwrap_or_default()), if release_in.is_some() => {
+                    pending.pop_front()
+                },
+            };
+            if let Some(item) = released {
+                yield Ok(item);
+            }
+            if let Some(count) = skipped {
+                // Items still queued up are older than the resynced one...
+                receiver = receiver.resubscribe();
+                match resync(count).await {
+                    Ok(item) => pending.extend(view(&item)),
+                    Err(err) => {
+                        failure = Some(err);
+                        is_receiving = false;
+                    },
+                }
+            }
+        }
+        if let Some(err) = failure {
+            yield Err(err);
+        }
+    }
+}
@@ -0,0 +1,8 @@
+use chrono::{DateTime, Utc};
+
+pub(crate) fn chrono_to_prost_timestamp(dt: DateTime<Utc>) -> prost_types::Timestamp {
+    prost_types::Timestamp {
+        seconds: dt.timestamp(),
+        nanos: dt.timestamp_subsec_nanos() as i32,
+    }
+}
@@ -0,0 +1,73 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Pending friend request, or an accepted friendship once the addressee agreed to it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Friendship {
+    pub requester_id: Uuid,
+    pub addressee_id: Uuid,
+    pub is_accepted: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum FriendshipStatus {
+    Friend,
+    IncomingRequest,
+    OutgoingRequest,
+}
+
+/// Lobby ids are left out for private lobbies, which require an invite to join.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum FriendPresence {
+    Offline,
+    Online,
+    InLobby(Option<Uuid>),
+    InGame(Option<Uuid>),
+}
+
+/// A friendship as seen by one of its players. Presence is only shared between accepted friends.
+#[derive(Clone, Debug)]
+pub(crate) struct FriendInfo {
+    pub player_id: Uuid,
+    pub player_name: String,
+    pub status: FriendshipStatus,
+    pub since: DateTime<Utc>,
+    pub presence: FriendPresence,
+}
+
+impl Friendship {
+    pub fn new(requester_id: Uuid, addressee_id: Uuid) -> Self {
+        Friendship {
+            requester_id,
+            addressee_id,
+            is_accepted: false,
+            updated_at: Utc::now(),
+        }
+    }
+
+    /// Order-independent key of the two players.
+    pub fn get_key(&self) -> (Uuid, Uuid) {
+        Self::key(self.requester_id, self.addressee_id)
+    }
+
+    pub fn key(player_id: Uuid, other_player_id: Uuid) -> (Uuid, Uuid) {
+        (player_id.min(other_player_id), player_id.max(other_player_id))
+    }
+
+    pub fn get_other_player_id(&self, player_id: &Uuid) -> Uuid {
+        match self.requester_id == *player_id {
+            true => self.addressee_id,
+            false => self.requester_id,
+        }
+    }
+
+    pub fn get_status(&self, player_id: &Uuid) -> FriendshipStatus {
+        match (self.is_accepted, self.requester_id == *player_id) {
+            (true, _) => FriendshipStatus::Friend,
+            (false, true) => FriendshipStatus::OutgoingRequest,
+            (false, false) => FriendshipStatus::IncomingRequest,
+        }
+    }
+}
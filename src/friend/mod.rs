@@ -0,0 +1,6 @@
+mod friendship;
+mod proto;
+mod registry;
+
+pub(crate) use friendship::{FriendInfo, FriendPresence, Friendship, FriendshipStatus};
+pub(crate) use registry::FriendRegistry;
@@ -0,0 +1,38 @@
+use uuid::Uuid;
+
+use crate::common::time::chrono_to_prost_timestamp;
+use crate::service::proto;
+use super::{FriendInfo, FriendPresence, FriendshipStatus};
+
+impl From<FriendInfo> for proto::Friend {
+    fn from(friend: FriendInfo) -> Self {
+        let status = match friend.status {
+            FriendshipStatus::Friend => proto::friend::FriendshipStatus::Friend,
+            FriendshipStatus::IncomingRequest => proto::friend::FriendshipStatus::IncomingRequest,
+            FriendshipStatus::OutgoingRequest => proto::friend::FriendshipStatus::OutgoingRequest,
+        };
+        let (presence, lobby_id) = friend.presence.into();
+        proto::Friend {
+            player: Some(proto::PlayerPublicInfo {
+                player_id: friend.player_id.to_string(),
+                player_name: friend.player_name,
+            }),
+            status: status as i32,
+            since: Some(chrono_to_prost_timestamp(friend.since)),
+            presence: presence as i32,
+            lobby_id: lobby_id.map(|lobby_id| lobby_id.to_string()),
+        }
+    }
+}
+
+/// Presence along with the lobby it refers to, if shared.
+impl From<FriendPresence> for (proto::friend::FriendPresence, Option<Uuid>) {
+    fn from(presence: FriendPresence) -> Self {
+        match presence {
+            FriendPresence::Offline => (proto::friend::FriendPresence::Offline, None),
+            FriendPresence::Online => (proto::friend::FriendPresence::Online, None),
+            FriendPresence::InLobby(lobby_id) => (proto::friend::FriendPresence::InLobby, lobby_id),
+            FriendPresence::InGame(lobby_id) => (proto::friend::FriendPresence::InGame, lobby_id),
+        }
+    }
+}
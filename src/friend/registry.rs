@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::common::error::AppError;
+use crate::storage::{self, InMemoryStorage, Storage};
+use super::Friendship;
+
+/// Friendships and pending friend requests, persisted as they change.
+#[derive(Debug)]
+pub(crate) struct FriendRegistry {
+    friendships: HashMap<(Uuid, Uuid), Friendship>,
+    storage: Arc<dyn Storage>,
+}
+
+impl Default for FriendRegistry {
+    fn default() -> Self {
+        Self::restore(Arc::new(InMemoryStorage::default()), Vec::new())
+    }
+}
+
+impl FriendRegistry {
+    const MAX_FRIENDSHIPS: usize = 200; // outgoing requests included
+    const MAX_INCOMING_REQUESTS: usize = 50;
+
+    pub fn restore(storage: Arc<dyn Storage>, friendships: Vec<Friendship>) -> Self {
+        let friendships = friendships
+            .into_iter()
+            .map(|friendship| (friendship.get_key(), friendship))
+            .collect();
+        FriendRegistry { friendships, storage }
+    }
+
+    /// Requesting a player who already sent a request accepts theirs instead.<br />
+    /// Incoming requests don't count towards the friendship limit, the oldest one being evicted once too many pile up.
+    pub async fn send_request(&mut self, player_id: Uuid, addressee_id: Uuid) -> Result<Friendship, AppError> {
+        if player_id == addressee_id {
+            return Err(AppError::invalid_request("Players can't befriend themselves!"));
+        }
+        match self.friendships.get(&Friendship::key(player_id, addressee_id)) {
+            Some(friendship) if friendship.is_accepted => {
+                return Err(AppError::precondition_failed("Players are already friends!"));
+            },
+            Some(friendship) if friendship.requester_id == player_id => {
+                return Err(AppError::precondition_failed("Friend request already sent!"));
+            },
+            Some(_) => return self.accept_request(player_id, addressee_id).await,
+            None => {},
+        }
+        self.limit_validation(&player_id)?;
+        if self.count_friendships(&addressee_id) >= Self::MAX_FRIENDSHIPS {
+            return Err(AppError::precondition_failed(format!("Player ({addressee_id}) can't take any more friends!")));
+        }
+        let incoming_requests: Vec<&Friendship> = self.get_friendships(&addressee_id)
+            .into_iter()
+            .filter(|friendship| !friendship.is_accepted && friendship.addressee_id == addressee_id)
+            .collect();
+        if incoming_requests.len() >= Self::MAX_INCOMING_REQUESTS {
+            if let Some(oldest_request) = incoming_requests.into_iter().min_by_key(|friendship| friendship.updated_at) {
+                let key = oldest_request.get_key();
+                storage::run_blocking(&self.storage, move |storage| storage.remove_friendship(key)).await?;
+                self.friendships.remove(&key);
+            }
+        }
+
+        let friendship = Friendship::new(player_id, addressee_id);
+        self.store(friendship.clone()).await?;
+        Ok(friendship)
+    }
+
+    pub async fn accept_request(&mut self, player_id: Uuid, requester_id: Uuid) -> Result<Friendship, AppError> {
+        let mut friendship = match self.friendships.get(&Friendship::key(player_id, requester_id)) {
+            Some(friendship) if !friendship.is_accepted && friendship.addressee_id == player_id => friendship.clone(),
+            _ => return Err(AppError::precondition_failed("No pending friend request from this player!")),
+        };
+        self.limit_validation(&player_id)?;
+        friendship.is_accepted = true;
+        friendship.updated_at = Utc::now();
+        self.store(friendship.clone()).await?;
+        Ok(friendship)
+    }
+
+    /// Also declines incoming requests and withdraws outgoing ones.
+    pub async fn remove(&mut self, player_id: Uuid, other_player_id: Uuid) -> Result<(), AppError> {
+        let key = Friendship::key(player_id, other_player_id);
+        if !self.friendships.contains_key(&key) {
+            return Err(AppError::precondition_failed("No friendship or friend request with this player!"));
+        }
+        storage::run_blocking(&self.storage, move |storage| storage.remove_friendship(key)).await?;
+        self.friendships.remove(&key);
+        Ok(())
+    }
+
+    pub fn is_friend(&self, player_id: Uuid, other_player_id: Uuid) -> bool {
+        self.friendships
+            .get(&Friendship::key(player_id, other_player_id))
+            .is_some_and(|friendship| friendship.is_accepted)
+    }
+
+    pub fn get_friendships(&self, player_id: &Uuid) -> Vec<&Friendship> {
+        self.friendships
+            .values()
+            .filter(|friendship| friendship.requester_id == *player_id || friendship.addressee_id == *player_id)
+            .collect()
+    }
+
+    /// Accepted friendships only, leaving out pending requests.
+    pub fn get_friend_ids(&self, player_id: &Uuid) -> Vec<Uuid> {
+        self.get_friendships(player_id)
+            .into_iter()
+            .filter(|friendship| friendship.is_accepted)
+            .map(|friendship| friendship.get_other_player_id(player_id))
+            .collect()
+    }
+
+    fn limit_validation(&self, player_id: &Uuid) -> Result<(), AppError> {
+        if self.count_friendships(player_id) >= Self::MAX_FRIENDSHIPS {
+            return Err(
+                AppError::precondition_failed(
+                    format!("Players may have up to {} friends and outgoing requests!", Self::MAX_FRIENDSHIPS)
+                )
+            );
+        }
+        Ok(())
+    }
+
+    /// Friends and outgoing requests, which count towards the friendship limit.
+    fn count_friendships(&self, player_id: &Uuid) -> usize {
+        self.get_friendships(player_id)
+            .into_iter()
+            .filter(|friendship| friendship.is_accepted || friendship.requester_id == *player_id)
+            .count()
+    }
+
+    async fn store(&mut self, friendship: Friendship) -> Result<(), AppError> {
+        let stored_friendship = friendship.clone();
+        storage::run_blocking(&self.storage, move |storage| storage.store_friendship(&stored_friendship)).await?;
+        self.friendships.insert(friendship.get_key(), friendship);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::FriendshipStatus;
+    #[tokio::test]
+    async fn send_request() {
+        let mut registry = FriendRegistry::default();
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        assert!(registry.send_request(a, a).await.is_err());
+        registry.send_request(a, b).await.unwrap();
+        assert!(registry.send_request(a, b).await.is_err());
+        assert!(registry.accept_request(a, b).await.is_err());
+        assert_eq!(registry.get_friendships(&b)[0].get_status(&b), FriendshipStatus::IncomingRequest);
+        assert!(registry.send_request(b, a).await.unwrap().is_accepted); // mutual requests
+        assert!(registry.is_friend(a, b));
+        registry.remove(b, a).await.unwrap();
+        assert!(registry.get_friendships(&a).is_empty());
+        assert!(registry.remove(a, b).await.is_err());
+
+        for _ in 0..FriendRegistry::MAX_FRIENDSHIPS {
+            registry.send_request(Uuid::new_v4(), b).await.unwrap();
+        }
+        registry.send_request(a, b).await.unwrap(); // evicts the oldest incoming request
+        registry.send_request(b, Uuid::new_v4()).await.unwrap();
+        assert_eq!(registry.get_friendships(&b).len(), FriendRegistry::MAX_INCOMING_REQUESTS + 1);
+        assert!(registry.accept_request(b, a).await.unwrap().is_accepted);
+    }
+}
@@ -2,6 +2,6 @@ mod phase;
 pub(crate) mod table;
 mod service;
 
-pub(crate) use phase::{PokerPhase, GamePhase, DiscardedCards};
+pub(crate) use phase::{PokerPhase, PokerPhaseKind, GamePhase, BettingRoundAction, DiscardedCards};
 pub(crate) use table::GameTable;
 pub(crate) use service::GameService;
@@ -1,55 +1,97 @@
 mod poker;
 mod progression;
 
-pub(crate) use poker::{PokerPhase, BettingRoundAction, DiscardedCards};
+pub(crate) use poker::{PokerPhase, PokerPhaseKind, BettingRoundAction, DiscardedCards};
 
-use std::collections::{HashMap, VecDeque};
+use std::collections::HashMap;
 use std::sync::Arc;
 use chrono::{DateTime, Utc};
 use tokio::sync::{broadcast, RwLock};
 use uuid::Uuid;
 
+use crate::lobby::LobbySettings;
 use crate::r#match::MatchStartPlayers;
 use crate::types::stateful::StatefulCard;
 use crate::types::deck::CardDeck;
 use crate::game::GameTable;
-use crate::output::GameStateBroadcaster;
+use crate::output::{GameEvent, GameStateBroadcaster};
 use crate::common::error::AppError;
 use crate::output::MatchStatePhaseSpecifics;
+use crate::history::HandHistory;
+use crate::storage::{self, MatchSnapshot, Storage};
 use poker::*;
 
 #[derive(Clone, Debug)]
 pub struct GamePhase {
+    lobby_id: Uuid,
     poker_phase: PokerPhase,
     state_time: DateTime<Utc>,
-    state_broadcaster: GameStateBroadcaster
+    state_broadcaster: GameStateBroadcaster,
+    storage: Arc<dyn Storage>,
+    published_event_count: usize, // hand history events already published
 }
 
 impl GamePhase {
     pub fn new(
-        match_id: Uuid,
+        history: HandHistory,
         state_broadcaster: GameStateBroadcaster,
         rpc_action_broadcaster: broadcast::Sender<()>,
+        storage: Arc<dyn Storage>,
         players: MatchStartPlayers,
-        ante_amount: u64,
+        settings: &LobbySettings,
     ) -> Self {
-        let game_table = GameTable::new(match_id, players);
+        let lobby_id = history.lobby_id;
+        let game_table = GameTable::new(history.match_id, players, settings.rake.clone(), history);
         let card_deck = CardDeck::default();
         let poker_phase = PokerPhase::new(
             rpc_action_broadcaster,
             game_table,
             card_deck,
-            ante_amount);
+            settings.ante_amount);
 
         GamePhase {
+            lobby_id,
             poker_phase,
             state_time: Utc::now(),
             state_broadcaster,
+            storage,
+            published_event_count: 0,
         }
     }
 
-    pub async fn progress(phase_arc: Arc<RwLock<GamePhase>>, mut rpc_action_receiver: broadcast::Receiver<()>) {
-        let mut first_run = true;
+    /// Restores an interrupted match from its latest snapshot.
+    pub fn restore(
+        state_broadcaster: GameStateBroadcaster,
+        storage: Arc<dyn Storage>,
+        snapshot: MatchSnapshot,
+    ) -> Self {
+        let published_event_count = snapshot.poker_phase.get_table().history.events.len();
+        GamePhase {
+            lobby_id: snapshot.lobby_id,
+            poker_phase: snapshot.poker_phase,
+            state_time: snapshot.timestamp,
+            state_broadcaster,
+            storage,
+            published_event_count,
+        }
+    }
+
+    pub fn subscribe_rpc_actions(&self) -> broadcast::Receiver<()> {
+        self.poker_phase.subscribe_rpc_actions()
+    }
+
+    /// Drives the match until it's over.<br />
+    /// Resumed matches skip straight to awaiting their snapshot phase's next action.<br />
+    /// Failing phase actions abort the match, refunding every pot contribution.
+    pub async fn progress(
+        phase_arc: Arc<RwLock<GamePhase>>,
+        mut rpc_action_receiver: broadcast::Receiver<()>,
+        resumed: bool,
+    ) {
+        let mut first_run = !resumed;
+        if first_run {
+            phase_arc.read().await.publish_phase_change();
+        }
         loop {
             // Contemplate Life Choices
             if first_run {
@@ -71,30 +113,81 @@ impl GamePhase {
             let state_broadcaster = {
                 let mut phase_w = phase_arc.write().await;
                 phase_w.state_time = Utc::now();
-                phase_w.poker_phase.act();
+                if let Err(err) = phase_w.poker_phase.act() {
+                    let match_id = phase_w.get_table().get_match_id();
+                    eprintln!("Match ({match_id}) aborted, refunding its pots: {err}");
+                    phase_w.poker_phase.get_table_mut().refund_pot_contributions();
+                    return;
+                }
+                phase_w.publish_events();
 
                 phase_w.state_broadcaster.clone()
             };
 
             // Build & Publish State
-            state_broadcaster.publish().await;
+            let state = state_broadcaster.publish().await;
 
             // Handle State Progression
-            {
+            let (storage, snapshot) = {
                 let mut phase_w = phase_arc.write().await;
+                if let Some(state) = state {
+                    phase_w.poker_phase.get_table_mut().history.record_frame(state);
+                }
                 if phase_w.poker_phase.is_phase_completed() {
                     if let Some(next_phase) = phase_w.poker_phase.clone().next_phase() {
-                        phase_w.poker_phase = next_phase
+                        phase_w.poker_phase = next_phase;
+                        phase_w.publish_phase_change();
                     } else {
                         // TODO: Game Over - Cleanup
                         // TODO: handle this via ActionProgression or sth.
                         return;
                     }
                 }
+                phase_w.take_snapshot()
+            };
+            if let Err(err) = storage::run_blocking(&storage, move |storage| storage.store_match_snapshot(snapshot)).await {
+                eprintln!("Storing match snapshot failed, a restart would resume from an earlier one: {err}");
             }
         }
     }
 
+    /// Publishes any hand history events recorded since the last call as game events.
+    fn publish_events(&mut self) {
+        let history = &self.get_table().history;
+        for event in history.events.iter().skip(self.published_event_count) {
+            let event = GameEvent::from_history_event(history.match_id, event.clone());
+            self.state_broadcaster.publish_event(event);
+        }
+        self.published_event_count = history.events.len();
+    }
+
+    fn publish_phase_change(&self) {
+        let event = GameEvent::phase_changed(self.get_table().get_match_id(), self.poker_phase.get_kind());
+        self.state_broadcaster.publish_event(event);
+    }
+
+    pub fn is_played_out(&self) -> bool {
+        self.poker_phase.is_played_out()
+    }
+
+    pub fn get_storage(&self) -> Arc<dyn Storage> {
+        self.storage.clone()
+    }
+
+    /// Replay frames are left out, keeping snapshots from growing with every action.<br />
+    /// Resumed matches can only be replayed from the point they got resumed at.
+    fn take_snapshot(&mut self) -> (Arc<dyn Storage>, MatchSnapshot) {
+        let frames = std::mem::take(&mut self.poker_phase.get_table_mut().history.frames);
+        let snapshot = MatchSnapshot {
+            match_id: self.get_table().get_match_id(),
+            lobby_id: self.lobby_id,
+            poker_phase: self.poker_phase.clone(),
+            timestamp: self.state_time,
+        };
+        self.poker_phase.get_table_mut().history.frames = frames;
+        (self.storage.clone(), snapshot)
+    }
+
     pub async fn handle_betting_action(
         &mut self,
         player_id: Uuid,
@@ -131,8 +224,57 @@ impl GamePhase {
     pub fn can_player_act(&self) -> HashMap<Uuid, bool> {
         self.poker_phase.can_player_act()
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::GameService;
+    use crate::lobby::Lobby;
+    use crate::player::{Player, PlayerRegistry};
+    use crate::storage::InMemoryStorage;
+    #[tokio::test]
+    async fn restore_played_out_snapshot() {
+        let storage: Arc<dyn Storage> = Arc::new(InMemoryStorage::default());
+        let player_registry = Arc::new(RwLock::new(PlayerRegistry::restore(storage.clone(), HashMap::new(), Vec::new())));
+        let players: Vec<Player> = (0..2).map(|_| Player::register().0).collect();
+        for player in &players {
+            player_registry.write().await.add_player(player.clone()).await.unwrap();
+        }
+        let mut lobby = Lobby::new(1, player_registry, String::from("Table"), players[0].player_id, players[0].player_name.clone());
+        lobby.seats.insert(1, players[1].player_id);
+        storage.store_lobby(lobby.to_record()).unwrap();
+
+        let settings = LobbySettings::default();
+        let start_players = MatchStartPlayers::new(players.iter().cloned().collect(), &lobby.seats, 0, HashMap::new());
+        let match_id = Uuid::new_v4();
+        let history = HandHistory::new(match_id, lobby.lobby_id, lobby.name.clone(), &settings, &start_players);
+        let game_table = GameTable::new(match_id, start_players, settings.rake.clone(), history);
+        let mut card_deck = serde_json::to_value(CardDeck::default()).unwrap();
+        card_deck["deck"].as_array_mut().unwrap().sort_by_key(|card| (card["suit"].to_string(), card["rank"].to_string())); // unpaired hands
+        let card_deck = serde_json::from_value(card_deck).unwrap();
+        let (rpc_action_broadcaster, _rpc_action_receiver) = broadcast::channel(1);
+        let mut poker_phase = PokerPhase::new(rpc_action_broadcaster, game_table, card_deck, settings.ante_amount);
+        while !poker_phase.is_played_out() {
+            if poker_phase.is_phase_completed() {
+                poker_phase = poker_phase.next_phase().unwrap();
+            } else if let (PokerPhaseKind::FirstBetting, Some(player_id)) = (poker_phase.get_kind(), poker_phase.get_active_player_id()) {
+                poker_phase.handle_betting_action(player_id, BettingRoundAction::Fold).await.unwrap();
+            } else {
+                poker_phase.act().unwrap();
+            }
+        }
+        let snapshot = MatchSnapshot { match_id, lobby_id: lobby.lobby_id, poker_phase, timestamp: Utc::now() };
+        storage.store_match_snapshot(snapshot).unwrap();
 
-    pub fn get_table_players_order(&self) -> VecDeque<Uuid> {
-        self.poker_phase.get_table_players_order()
+        let game_service = GameService::restore(storage.clone()).await.unwrap();
+        let mut balances = Vec::new();
+        for player in &players {
+            balances.push(game_service.get_balance_rpc(player.player_id).await.unwrap().balance);
+        }
+        balances.sort();
+        assert_eq!(balances, [500 - settings.ante_amount, 500 + settings.ante_amount]);
+        assert!(game_service.leave_lobby_rpc(players[1].player_id).await.is_ok()); // not resumed
+        assert!(storage.load().unwrap().match_snapshots.is_empty());
     }
 }
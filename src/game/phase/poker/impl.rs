@@ -6,9 +6,11 @@ use crate::common::error::AppError;
 use crate::game::GameTable;
 use crate::game::phase::BettingRoundAction;
 use crate::game::phase::progression::ActionProgression;
+use crate::history::HandHistoryAction;
+use crate::ledger::LedgerTransactionKind;
 use crate::types::card::Card;
 use crate::types::deck::CardDeck;
-use crate::types::hand::{Hand, RateHands};
+use crate::types::hand::Hand;
 use crate::output::MatchStatePhaseSpecifics;
 use super::{PokerPhase, PokerPhaseBehavior, PokerPhaseAnte, PokerPhaseBetting, PokerPhaseDealing, PokerPhaseDrawingDiscarding, PokerPhaseDrawingDealing, PokerPhaseFirstBetting, PokerPhaseSecondBetting, PokerPhaseShowdown};
 
@@ -32,8 +34,9 @@ impl PokerPhaseAnte {
 
 impl PokerPhaseBehavior for PokerPhaseAnte {
     /// Handles the placement of forced initial bets.<br />
-    /// Phase actions are automatically scheduled without any player interaction.
-    fn act(&mut self) {
+    /// Phase actions are automatically scheduled without any player interaction.<br />
+    /// Players unable to cover the ante fail the phase, see `Lobby::ante_validation`.
+    fn act(&mut self) -> Result<(), AppError> {
         let Some(player_id) = self.phase_player_queue.front().cloned() else {
             unreachable!()
         };
@@ -42,9 +45,12 @@ impl PokerPhaseBehavior for PokerPhaseAnte {
         };
 
         let credit_pot = self.game_table.credit_pots.values_mut().next().unwrap(); // first pot should exist
-        credits.use_credits(self.ante_amount, credit_pot).unwrap();
+        credits.use_credits(self.ante_amount, credit_pot)?;
+        self.game_table.record_transaction(player_id, LedgerTransactionKind::Ante, self.ante_amount);
+        self.game_table.record_history(HandHistoryAction::Ante { player_id, amount: self.ante_amount });
 
         let _ = shift_queue(&mut self.phase_player_queue); // TODO
+        Ok(())
     }
 
     fn is_phase_completed(&self) -> bool {
@@ -108,7 +114,7 @@ impl PokerPhaseDealing {
 impl PokerPhaseBehavior for PokerPhaseDealing {
     /// Handles card dealing.<br />
     /// Phase actions are automatically scheduled without any player interaction.
-    fn act(&mut self) {
+    fn act(&mut self) -> Result<(), AppError> {
         let Some(player_id) = self.phase_player_queue.front().cloned() else {
             unreachable!()
         };
@@ -120,9 +126,15 @@ impl PokerPhaseBehavior for PokerPhaseDealing {
             .try_into()
             .unwrap(); // fresh deck can't underflow for max players
         let hand: Hand = cards.try_into().unwrap(); // no dupes
+        self.game_table.record_history(HandHistoryAction::CardsDealt {
+            player_id,
+            card_count: hand.cards.len(),
+            cards: Some(hand.cards.to_vec()),
+        });
         self.player_hands.insert(player_id, hand);
 
         let _ = shift_queue(&mut self.phase_player_queue); // TODO
+        Ok(())
     }
 
     fn is_phase_completed(&self) -> bool {
@@ -199,7 +211,7 @@ impl PokerPhaseFirstBetting {
 }
 
 impl PokerPhaseBehavior for PokerPhaseFirstBetting {
-    fn act(&mut self) {
+    fn act(&mut self) -> Result<(), AppError> {
         self.0.act()
     }
 
@@ -209,7 +221,7 @@ impl PokerPhaseBehavior for PokerPhaseFirstBetting {
 
     fn next_phase(self) -> Option<PokerPhase> {
         if self.0.last_man_standing() {
-            Some(PokerPhase::Showdown(PokerPhaseShowdown::from_betting(self.0)))
+            Some(PokerPhase::Showdown(PokerPhaseShowdown::from_betting(self.0, false)))
         } else {
             Some(PokerPhase::DrawingDiscarding(PokerPhaseDrawingDiscarding::from_first_betting(self)))
         }
@@ -264,7 +276,7 @@ impl PokerPhaseSecondBetting {
 }
 
 impl PokerPhaseBehavior for PokerPhaseSecondBetting {
-    fn act(&mut self) {
+    fn act(&mut self) -> Result<(), AppError> {
         self.0.act()
     }
 
@@ -273,13 +285,17 @@ impl PokerPhaseBehavior for PokerPhaseSecondBetting {
     }
 
     fn next_phase(self) -> Option<PokerPhase> {
-        Some(PokerPhase::Showdown(PokerPhaseShowdown::from_betting(self.0)))
+        Some(PokerPhase::Showdown(PokerPhaseShowdown::from_betting(self.0, true)))
     }
 
     fn get_active_player_id(&self) -> Option<Uuid> {
         self.0.phase_player_queue.front().cloned()
     }
 
+    fn get_action_progression(&self) -> Option<ActionProgression> {
+        self.0.get_action_progression()
+    }
+
     fn get_player_bet_amounts(&self) -> Option<HashMap<Uuid, u64>> {
         self.0.get_player_bet_amounts()
     }
@@ -295,56 +311,6 @@ impl PokerPhaseBehavior for PokerPhaseSecondBetting {
     }
 }
 
-impl PokerPhaseShowdown {
-    fn from_betting(betting_phase: PokerPhaseBetting) -> Self {
-        let phase_player_queue = betting_phase.game_table.clone_player_queue();
-        PokerPhaseShowdown {
-            game_table: betting_phase.game_table,
-            card_deck: betting_phase.card_deck,
-            phase_player_queue,
-            player_hands: betting_phase.player_hands,
-        }
-    }
-}
-
-impl PokerPhaseBehavior for PokerPhaseShowdown {
-    /// Handles the calculation of player hand rankings.<br />
-    /// A single phase action is automatically scheduled without any player interaction.
-    fn act(&mut self) {
-        let hands: Vec<Hand> = self.player_hands.values().cloned().collect();
-        let winners = hands.determine_winners();
-        // TODO: refactor determine_winners(), returning:
-        // - winner ids
-        // - best rank
-        // TODO: propagate these fields outwards
-    }
-
-    fn is_phase_completed(&self) -> bool {
-        // todo!()
-        true
-    }
-
-    fn get_phase_specifics(&self) -> MatchStatePhaseSpecifics {
-        MatchStatePhaseSpecifics::Showdown(
-            todo!()
-            // MatchStatePhaseSpecificsShowdown {
-            //     winning_rank: ,
-            //     winner_ids: ,
-            //     pot_distribution: ,
-            // }
-        )
-    }
-
-    fn can_player_act(&self) -> HashMap<Uuid, bool> {
-        self.game_table
-            .player_ids
-            .iter()
-            .cloned()
-            .map(|player_id| (player_id, false))
-            .collect()
-    }
-}
-
 pub fn shift_queue(queue: &mut VecDeque<Uuid>) -> Result<Uuid, AppError> {
     let active_player = queue
         .pop_front()
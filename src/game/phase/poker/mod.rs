@@ -3,7 +3,8 @@ mod r#impl;
 
 pub(crate) use phase::{BettingRoundAction, DiscardedCards};
 
-use std::collections::{HashMap, VecDeque};
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
 use tokio::sync::broadcast;
 use uuid::Uuid;
 
@@ -15,7 +16,7 @@ use crate::common::error::AppError;
 use crate::output::MatchStatePhaseSpecifics;
 use phase::*;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(crate) enum PokerPhase {
     Ante(PokerPhaseAnte),
     Dealing(PokerPhaseDealing),
@@ -26,8 +27,25 @@ pub(crate) enum PokerPhase {
     Showdown(PokerPhaseShowdown),
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum PokerPhaseKind {
+    Ante,
+    Dealing,
+    FirstBetting,
+    DrawingDiscarding,
+    DrawingDealing,
+    SecondBetting,
+    Showdown,
+}
+
+/// Phases get restored with a fresh RPC action channel, as subscriptions don't outlive the process.
+fn new_rpc_action_broadcaster() -> broadcast::Sender<()> {
+    let (rpc_action_broadcaster, _) = broadcast::channel(PokerPhase::RPC_ACTION_EVENT_CHANNEL_CAPACITY);
+    rpc_action_broadcaster
+}
+
 pub(super) trait PokerPhaseBehavior {
-    fn act(&mut self);
+    fn act(&mut self) -> Result<(), AppError>;
 
     fn is_phase_completed(&self) -> bool;
 
@@ -53,7 +71,7 @@ pub(super) trait PokerPhaseBehavior {
 }
 
 impl PokerPhaseBehavior for PokerPhase {
-    fn act(&mut self) {
+    fn act(&mut self) -> Result<(), AppError> {
         match self {
             PokerPhase::Ante(phase) => phase.act(),
             PokerPhase::Dealing(phase) => phase.act(),
@@ -196,13 +214,37 @@ impl PokerPhase {
         }
     }
 
-    pub fn get_table_players_order(&self) -> VecDeque<Uuid> {
-        let table = self.get_table();
-        table.player_queue_immut.clone()
+    pub(super) fn subscribe_rpc_actions(&self) -> broadcast::Receiver<()> {
+        match self {
+            PokerPhase::Ante(phase) => phase._rpc_action_broadcaster.subscribe(),
+            PokerPhase::Dealing(phase) => phase._rpc_action_broadcaster.subscribe(),
+            PokerPhase::FirstBetting(phase) => phase.rpc_action_broadcaster.subscribe(),
+            PokerPhase::DrawingDiscarding(phase) => phase.rpc_action_broadcaster.subscribe(),
+            PokerPhase::DrawingDealing(phase) => phase.rpc_action_broadcaster.subscribe(),
+            PokerPhase::SecondBetting(phase) => phase.rpc_action_broadcaster.subscribe(),
+            PokerPhase::Showdown(_) => new_rpc_action_broadcaster().subscribe(), // no player actions
+        }
     }
 }
 
 impl PokerPhase {
+    /// Whether the showdown already paid out the pots, leaving nothing to be played.
+    pub fn is_played_out(&self) -> bool {
+        matches!(self, PokerPhase::Showdown(phase) if phase.pots_distributed)
+    }
+
+    pub fn get_kind(&self) -> PokerPhaseKind {
+        match self {
+            PokerPhase::Ante(_) => PokerPhaseKind::Ante,
+            PokerPhase::Dealing(_) => PokerPhaseKind::Dealing,
+            PokerPhase::FirstBetting(_) => PokerPhaseKind::FirstBetting,
+            PokerPhase::DrawingDiscarding(_) => PokerPhaseKind::DrawingDiscarding,
+            PokerPhase::DrawingDealing(_) => PokerPhaseKind::DrawingDealing,
+            PokerPhase::SecondBetting(_) => PokerPhaseKind::SecondBetting,
+            PokerPhase::Showdown(_) => PokerPhaseKind::Showdown,
+        }
+    }
+
     // TODO: tmp-only exposure
     pub fn get_table(&self) -> &GameTable {
         match self {
@@ -216,6 +258,18 @@ impl PokerPhase {
         }
     }
 
+    pub fn get_table_mut(&mut self) -> &mut GameTable {
+        match self {
+            PokerPhase::Ante(phase) => &mut phase.game_table,
+            PokerPhase::Dealing(phase) => &mut phase.game_table,
+            PokerPhase::FirstBetting(phase) => &mut phase.0.game_table,
+            PokerPhase::DrawingDiscarding(phase) => &mut phase.game_table,
+            PokerPhase::DrawingDealing(phase) => &mut phase.game_table,
+            PokerPhase::SecondBetting(phase) => &mut phase.0.game_table,
+            PokerPhase::Showdown(phase) => &mut phase.game_table,
+        }
+    }
+
     pub fn get_player_cards(&self) -> Option<HashMap<Uuid, Option<Vec<StatefulCard>>>> {
         match self {
             PokerPhase::Ante(_) => None,
@@ -3,6 +3,7 @@ use std::future::Future;
 use std::ops::Deref;
 use std::pin::Pin;
 use std::sync::Arc;
+use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
@@ -12,12 +13,14 @@ use crate::game::GamePhase;
 use crate::game::phase::poker::{PokerPhase, PokerPhaseBehavior};
 use crate::game::phase::poker::r#impl::shift_queue;
 use crate::game::phase::progression::ActionProgression;
+use crate::history::HandHistoryAction;
+use crate::ledger::LedgerTransactionKind;
 use crate::output::{MatchStatePhaseSpecifics, MatchStatePhaseSpecificsBetting};
 use super::{PokerPhaseBetting, PokerPhaseFirstBetting, PokerPhaseSecondBetting};
 
 // TODO: second betting phase, lurking .unwrap() panic!
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(crate) enum BettingRoundAction {
     Bet(u64),
     Call,
@@ -29,11 +32,12 @@ impl PokerPhaseBehavior for PokerPhaseBetting {
     /// Handles betting phase gameplay actions.<br />
     /// Phase actions are primarily initiated by players.<br />
     /// Players not responding in time fold their hands.
-    fn act(&mut self) {
+    fn act(&mut self) -> Result<(), AppError> {
         // Player actions handled via RPC calls.
         // Timeout actions handled via a callback.
         self.first_round_action = false;
         let _ = shift_queue(&mut self.phase_player_queue); // TODO
+        Ok(())
     }
 
     /// Checks whether everyone has either folded or matched the highest bet.
@@ -142,6 +146,8 @@ impl PokerPhaseBetting {
 
         self.card_deck.discard_cards(player_hand.cards.into());
         self.player_bets.remove(&player_id);
+        let bet_amount = self.get_player_bet_credits(player_id);
+        self.record_betting_action(player_id, BettingRoundAction::Fold, bet_amount, 0);
 
         self.rpc_action_broadcaster.send(()).unwrap(); // TODO: handle dropped receiver
         Ok(())
@@ -155,7 +161,8 @@ impl PokerPhaseBetting {
         let highest_bet = self.get_highest_bet()
             .ok_or(AppError::invalid_request("No bet to call against!"))?;
 
-        self.set_player_bet(player_id, highest_bet)?;
+        let added_credits = self.set_player_bet(player_id, highest_bet)?;
+        self.record_betting_action(player_id, BettingRoundAction::Call, highest_bet, added_credits);
         self.rpc_action_broadcaster.send(()).unwrap(); // TODO: handle dropped receiver
         Ok(())
     }
@@ -171,7 +178,12 @@ impl PokerPhaseBetting {
             return Err(AppError::invalid_request("Player can't act out of turn!"));
         }
 
-        self.set_player_bet(player_id, bet_credits)?;
+        let action = match self.get_highest_bet() {
+            Some(_) => BettingRoundAction::Raise(bet_credits),
+            None => BettingRoundAction::Bet(bet_credits),
+        };
+        let added_credits = self.set_player_bet(player_id, bet_credits)?;
+        self.record_betting_action(player_id, action, bet_credits, added_credits);
         self.rpc_action_broadcaster.send(()).unwrap(); // TODO: handle dropped receiver
         Ok(())
     }
@@ -191,7 +203,8 @@ impl PokerPhaseBetting {
         }
     }
 
-    fn set_player_bet(&mut self, player_id: Uuid, bet_credits: u64) -> Result<(), AppError> {
+    /// Returns the amount of credits added to the pot by the updated bet.
+    fn set_player_bet(&mut self, player_id: Uuid, bet_credits: u64) -> Result<u64, AppError> {
         self.check_min_bet(bet_credits)?;
 
         let mut player_credits = self.game_table
@@ -209,11 +222,34 @@ impl PokerPhaseBetting {
             // TODO: currently assuming a single pot
             let credit_pot = self.game_table.credit_pots.values_mut().next().unwrap();
             player_credits.use_credits(added_credits, credit_pot)?;
+            self.game_table.record_transaction(player_id, LedgerTransactionKind::Bet, added_credits);
 
             self.player_bets.insert(player_id, bet_credits);
         };
 
-        Ok(())
+        Ok(added_credits)
+    }
+
+    fn get_player_bet_credits(&self, player_id: Uuid) -> u64 {
+        self.game_table
+            .player_credits
+            .get(&player_id)
+            .map_or(0, |credits| credits.starting_credits - credits.remaining_credits)
+    }
+
+    fn record_betting_action(
+        &mut self,
+        player_id: Uuid,
+        action: BettingRoundAction,
+        bet_amount: u64,
+        added_credits: u64,
+    ) {
+        self.game_table.record_history(HandHistoryAction::BettingAction {
+            player_id,
+            action,
+            bet_amount,
+            added_credits,
+        });
     }
 
     fn can_player_act(&self, player_id: Uuid) -> bool {
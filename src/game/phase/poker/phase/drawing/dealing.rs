@@ -2,10 +2,12 @@ use std::array;
 use std::collections::HashMap;
 use uuid::Uuid;
 
+use crate::common::error::AppError;
 use crate::game::phase::poker::phase::PokerPhaseSecondBetting;
 use crate::game::phase::poker::{PokerPhase, PokerPhaseBehavior};
 use crate::game::phase::poker::r#impl::shift_queue;
 use crate::game::phase::progression::ActionProgression;
+use crate::history::HandHistoryAction;
 use crate::types::card::Card;
 use crate::output::{MatchStatePhaseSpecifics, MatchStatePhaseSpecificsDrawing};
 use super::{PokerPhaseDrawingDealing, PokerPhaseDrawingDiscarding};
@@ -14,7 +16,7 @@ impl PokerPhaseBehavior for PokerPhaseDrawingDealing {
     /// Handles dealing replacement cards back to the players.<br />
     /// Phase actions are automatically scheduled on a loop until replacement cards are received.<br />
     /// Players receive all their replacement cards at once, in player order.
-    fn act(&mut self) {
+    fn act(&mut self) -> Result<(), AppError> {
         self.card_deck.handle_discard_end();
 
         let Some(player_id) = self.get_active_player_id() else {
@@ -23,6 +25,7 @@ impl PokerPhaseBehavior for PokerPhaseDrawingDealing {
         self.replenish_player_cards(player_id);
 
         let _ = shift_queue(&mut self.phase_player_queue); // TODO
+        Ok(())
     }
 
     fn is_phase_completed(&self) -> bool {
@@ -87,6 +90,11 @@ impl PokerPhaseDrawingDealing {
         let mut new_cards: Vec<Card> = (0..count)
             .map(|_| self.card_deck.draw().unwrap())
             .collect();
+        self.game_table.record_history(HandHistoryAction::CardsDrawn {
+            player_id,
+            card_count: count,
+            cards: Some(new_cards.clone()),
+        });
         let next_cards: [Card; 5] = array::from_fn(|i| {
             if !discarded_cards.0.contains(&old_cards[i]) {
                 old_cards[i].clone()
@@ -11,6 +11,7 @@ use crate::game::phase::poker::phase::{PokerPhaseDrawingDealing, PokerPhaseFirst
 use crate::game::phase::poker::{PokerPhase, PokerPhaseBehavior};
 use crate::game::phase::poker::r#impl::shift_queue;
 use crate::game::phase::progression::ActionProgression;
+use crate::history::HandHistoryAction;
 use crate::types::card::Card;
 use crate::output::{DrawingStageDiscarding, MatchStatePhaseSpecifics, MatchStatePhaseSpecificsDrawing};
 use super::PokerPhaseDrawingDiscarding;
@@ -20,8 +21,9 @@ impl PokerPhaseBehavior for PokerPhaseDrawingDiscarding {
     /// Discarded cards are initially declared by everyone (via player action).<br />
     /// Player actions do initiate phase actions, but the former aren't really required.<br />
     /// Any players failing to decide within a fixed amount of time get to discard no cards.
-    fn act(&mut self) {
+    fn act(&mut self) -> Result<(), AppError> {
         let _ = shift_queue(&mut self.phase_player_queue); // TODO
+        Ok(())
     }
 
     fn is_phase_completed(&self) -> bool {
@@ -117,6 +119,15 @@ impl PokerPhaseDrawingDiscarding {
             self.player_owns_cards_validation(player_id, &cards.0)?;
             self.card_deck.discard_cards(cards.0);
         };
+        let discarded_cards: Vec<Card> = cards
+            .as_ref()
+            .map(|cards| cards.iter().cloned().collect())
+            .unwrap_or_default();
+        self.game_table.record_history(HandHistoryAction::CardsDiscarded {
+            player_id,
+            card_count: discarded_cards.len(),
+            cards: Some(discarded_cards),
+        });
         self.player_discarded_cards.insert(player_id, cards);
 
         self.rpc_action_broadcaster.send(()).unwrap(); // TODO: handle dropped receiver
@@ -3,18 +3,21 @@ mod discarding;
 
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::ops::Deref;
+use serde::{Deserialize, Serialize};
 use tokio::sync::broadcast;
 use uuid::Uuid;
 
 use crate::common::error::AppError;
 use crate::game::GameTable;
+use crate::game::phase::poker::new_rpc_action_broadcaster;
 use crate::service::proto;
 use crate::types::card::Card;
 use crate::types::deck::CardDeck;
 use crate::types::hand::Hand;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(crate) struct PokerPhaseDrawingDealing {
+    #[serde(skip, default = "new_rpc_action_broadcaster")]
     pub(crate) rpc_action_broadcaster: broadcast::Sender<()>,
     pub(crate) game_table: GameTable,
     pub(crate) card_deck: CardDeck,
@@ -24,8 +27,9 @@ pub(crate) struct PokerPhaseDrawingDealing {
     pub(crate) player_discarded_cards: HashMap<Uuid, Option<DiscardedCards>>, // TODO: display (output) own discarded cards, foreign discarded count
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(crate) struct PokerPhaseDrawingDiscarding {
+    #[serde(skip, default = "new_rpc_action_broadcaster")]
     pub(crate) rpc_action_broadcaster: broadcast::Sender<()>,
     pub(crate) game_table: GameTable,
     pub(crate) card_deck: CardDeck,
@@ -35,7 +39,7 @@ pub(crate) struct PokerPhaseDrawingDiscarding {
     pub(crate) player_discarded_cards: HashMap<Uuid, Option<DiscardedCards>>, // TODO: display (output) own discarded cards, foreign discarded count
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(crate) struct DiscardedCards(HashSet<Card>); // TODO: consider Vec<Card> (but discard in hand order, not discard input order)
 
 impl DiscardedCards {
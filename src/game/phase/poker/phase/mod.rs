@@ -1,19 +1,24 @@
 mod betting;
 mod drawing;
+mod showdown;
 
 pub(crate) use betting::BettingRoundAction;
 pub(crate) use drawing::{PokerPhaseDrawingDiscarding, PokerPhaseDrawingDealing, DiscardedCards};
 
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
+use serde::{Deserialize, Serialize};
 use tokio::sync::broadcast;
 use uuid::Uuid;
 
 use crate::game::GameTable;
+use crate::game::phase::poker::new_rpc_action_broadcaster;
+use crate::output::ShowdownPotDistribution;
 use crate::types::deck::CardDeck;
-use crate::types::hand::Hand;
+use crate::types::hand::{Hand, HandRank};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(super) struct PokerPhaseBetting {
+    #[serde(skip, default = "new_rpc_action_broadcaster")]
     pub(super) rpc_action_broadcaster: broadcast::Sender<()>,
     pub(super) game_table: GameTable,
     pub(super) card_deck: CardDeck,
@@ -26,8 +31,9 @@ pub(super) struct PokerPhaseBetting {
     //       maybe use HashSet<Uuid> ? that way unordered round phases can omit past players...
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(super) struct PokerPhaseAnte {
+    #[serde(skip, default = "new_rpc_action_broadcaster")]
     pub(super) _rpc_action_broadcaster: broadcast::Sender<()>,
     pub(super) game_table: GameTable,
     pub(super) card_deck: CardDeck,
@@ -35,8 +41,9 @@ pub(super) struct PokerPhaseAnte {
     pub(super) ante_amount: u64,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(super) struct PokerPhaseDealing {
+    #[serde(skip, default = "new_rpc_action_broadcaster")]
     pub(super) _rpc_action_broadcaster: broadcast::Sender<()>,
     pub(super) game_table: GameTable,
     pub(super) card_deck: CardDeck,
@@ -46,16 +53,22 @@ pub(super) struct PokerPhaseDealing {
     //pub(super) player_cards: HashMap<Uuid, HashSet<Card>>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(super) struct PokerPhaseFirstBetting(pub(super) PokerPhaseBetting);
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(super) struct PokerPhaseSecondBetting(pub(super) PokerPhaseBetting);
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(super) struct PokerPhaseShowdown {
     pub(super) game_table: GameTable,
     pub(super) card_deck: CardDeck,
     pub(super) phase_player_queue: VecDeque<Uuid>,
     pub(super) player_hands: HashMap<Uuid, Hand>, // folded hands omitted, all hands revealed at the same time
+    pub(super) winning_rank: HandRank,
+    pub(super) winner_ids: HashSet<Uuid>,
+    pub(super) pot_distribution: HashMap<Uuid, ShowdownPotDistribution>,
+    pub(super) player_payouts: HashMap<Uuid, u64>,
+    pub(super) player_refunds: HashMap<Uuid, u64>, // uncalled bets
+    pub(super) pots_distributed: bool,
 }
@@ -0,0 +1,190 @@
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+use crate::common::error::AppError;
+use crate::game::table::CreditPot;
+use crate::game::phase::poker::PokerPhaseBehavior;
+use crate::game::phase::progression::ActionProgression;
+use crate::history::HandHistoryAction;
+use crate::ledger::{CreditLedger, LedgerTransactionKind};
+use crate::output::{MatchStatePhaseSpecifics, MatchStatePhaseSpecificsShowdown, ShowdownPotDistribution};
+use crate::types::hand::{Hand, RateHands};
+use super::{PokerPhaseBetting, PokerPhaseShowdown};
+
+impl PokerPhaseBehavior for PokerPhaseShowdown {
+    /// Handles the distribution of pot credits to the showdown winners.<br />
+    /// A single phase action is automatically scheduled without any player interaction.
+    fn act(&mut self) -> Result<(), AppError> {
+        let player_payouts = self.player_payouts.clone();
+        let player_refunds = self.player_refunds.clone();
+        for (player_id, amount) in player_refunds {
+            self.pay_out(player_id, LedgerTransactionKind::Refund, amount);
+        }
+        for (player_id, amount) in player_payouts {
+            self.pay_out(player_id, LedgerTransactionKind::PotWin, amount);
+        }
+        self.pots_distributed = true;
+        Ok(())
+    }
+
+    fn is_phase_completed(&self) -> bool {
+        self.pots_distributed
+    }
+
+    fn get_action_progression(&self) -> Option<ActionProgression> {
+        Some(ActionProgression::delay(500))
+    }
+
+    fn get_phase_specifics(&self) -> MatchStatePhaseSpecifics {
+        MatchStatePhaseSpecifics::Showdown(
+            MatchStatePhaseSpecificsShowdown {
+                winning_rank: self.winning_rank.clone(),
+                winner_ids: self.winner_ids.clone(),
+                pot_distribution: self.pot_distribution.clone(),
+            }
+        )
+    }
+
+    fn can_player_act(&self) -> HashMap<Uuid, bool> {
+        self.game_table
+            .player_ids
+            .iter()
+            .cloned()
+            .map(|player_id| (player_id, false))
+            .collect()
+    }
+}
+
+impl PokerPhaseShowdown {
+    pub(crate) fn from_betting(betting_phase: PokerPhaseBetting, reached_drawing: bool) -> Self {
+        let phase_player_queue = betting_phase.game_table.clone_player_queue();
+        let winning_rank = betting_phase.player_hands
+            .values()
+            .max()
+            .unwrap() // last man standing
+            .rank
+            .clone();
+        let mut showdown = PokerPhaseShowdown {
+            game_table: betting_phase.game_table,
+            card_deck: betting_phase.card_deck,
+            phase_player_queue,
+            player_hands: betting_phase.player_hands,
+            winning_rank,
+            winner_ids: HashSet::new(),
+            pot_distribution: HashMap::new(),
+            player_payouts: HashMap::new(),
+            player_refunds: HashMap::new(),
+            pots_distributed: false,
+        };
+        showdown.show_hands();
+        showdown.rank_pots(reached_drawing);
+        showdown
+    }
+
+    /// Reveals every remaining hand, unless everyone else folded.
+    fn show_hands(&mut self) {
+        if self.player_hands.len() < 2 {
+            return;
+        }
+        for player_id in self.game_table.clone_player_queue() {
+            if let Some(hand) = self.player_hands.get(&player_id) {
+                self.game_table.record_history(HandHistoryAction::HandShown {
+                    player_id,
+                    cards: hand.cards.to_vec(),
+                    rank: hand.rank.clone(),
+                });
+            }
+        }
+    }
+
+    /// Determines the winners of each pot among the players still holding a hand.<br />
+    /// Tied winners split the pot, with any odd credits going out in table order.<br />
+    /// Uncalled bets are returned to their bettors instead of being awarded.<br />
+    /// The house's rake is taken out of each pot before the winnings get split.
+    fn rank_pots(&mut self, reached_drawing: bool) {
+        let table_order = self.game_table.clone_player_queue();
+        let mut total_rake = 0;
+        for pot in self.game_table.credit_pots.values_mut() {
+            let contender_hands: Vec<Hand> = self.player_hands
+                .iter()
+                .filter(|(player_id, _)| pot.player_credits.contains_key(player_id))
+                .map(|(_, hand)| hand.clone())
+                .collect();
+            let Some(best_hand) = contender_hands.determine_winners().into_iter().next() else {
+                continue;
+            };
+            let winner_ids: HashSet<Uuid> = self.player_hands
+                .iter()
+                .filter(|(player_id, hand)| pot.player_credits.contains_key(player_id) && **hand == best_hand)
+                .map(|(player_id, _)| *player_id)
+                .collect();
+
+            let mut awarded_credits = pot.total_credits;
+            if let Some((player_id, refund)) = Self::get_uncalled_bet(pot, &winner_ids) {
+                *self.player_refunds.entry(player_id).or_insert(0) += refund;
+                awarded_credits -= refund;
+            }
+
+            let rake = self.game_table.rake_settings.calculate_rake(awarded_credits, reached_drawing);
+            pot.take_rake(rake);
+            awarded_credits -= rake;
+            total_rake += rake;
+
+            let credits_per_winner = awarded_credits / winner_ids.len() as u64;
+            let mut odd_credits = awarded_credits % winner_ids.len() as u64;
+            for player_id in table_order.iter().filter(|player_id| winner_ids.contains(player_id)) {
+                let mut payout = credits_per_winner;
+                if odd_credits > 0 {
+                    payout += 1;
+                    odd_credits -= 1;
+                }
+                *self.player_payouts.entry(*player_id).or_insert(0) += payout;
+            }
+
+            if pot.is_main_pot {
+                self.winner_ids = winner_ids.clone();
+            }
+            let distribution = ShowdownPotDistribution {
+                pot_id: pot.pot_id,
+                player_ids: winner_ids,
+                total_credits: awarded_credits,
+                credits_per_winner,
+                raked_credits: rake,
+            };
+            self.pot_distribution.insert(pot.pot_id, distribution);
+        }
+        self.game_table.record_transaction(CreditLedger::HOUSE_ACCOUNT_ID, LedgerTransactionKind::Rake, total_rake);
+        if total_rake > 0 {
+            self.game_table.record_history(HandHistoryAction::Rake { amount: total_rake });
+        }
+    }
+
+    /// Returns the part of the highest pot contribution nobody else matched, if any.
+    fn get_uncalled_bet(pot: &CreditPot, winner_ids: &HashSet<Uuid>) -> Option<(Uuid, u64)> {
+        let mut contributions: Vec<(Uuid, u64)> = pot.player_credits
+            .iter()
+            .map(|(player_id, credits)| (*player_id, *credits))
+            .collect();
+        contributions.sort_by(|(_, a), (_, b)| b.cmp(a));
+        let (top_player_id, top_credits) = *contributions.first()?;
+        let runner_up_credits = contributions.get(1).map_or(0, |(_, credits)| *credits);
+        if top_credits > runner_up_credits && winner_ids.contains(&top_player_id) {
+            Some((top_player_id, top_credits - runner_up_credits))
+        } else {
+            None
+        }
+    }
+
+    fn pay_out(&mut self, player_id: Uuid, kind: LedgerTransactionKind, amount: u64) {
+        let Some(credits) = self.game_table.player_credits.get_mut(&player_id) else {
+            unreachable!()
+        };
+        credits.receive_credits(amount);
+        let history_action = match kind {
+            LedgerTransactionKind::Refund => HandHistoryAction::UncalledBetReturned { player_id, amount },
+            _ => HandHistoryAction::PotAwarded { player_id, amount },
+        };
+        self.game_table.record_transaction(player_id, kind, amount);
+        self.game_table.record_history(history_action);
+    }
+}
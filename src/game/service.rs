@@ -1,34 +1,345 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::future::Future;
 use std::sync::Arc;
 use async_stream::__private::AsyncStream;
+use futures::Stream;
+use chrono::{DateTime, TimeDelta, Utc};
 use tokio::sync::RwLock;
+use tokio::sync::broadcast::error::RecvError;
 use uuid::Uuid;
 
+use crate::achievement::{Achievement, AchievementProgress, AchievementRegistry, AchievementUnlock};
+use crate::chat::{ChatMessage, ChatRegistry, ProfanityFilter};
 use crate::common::error::AppError;
+use crate::common::stream::delayed_broadcast_stream;
+use crate::friend::{FriendInfo, FriendPresence, FriendRegistry, Friendship, FriendshipStatus};
 use crate::game::DiscardedCards;
 use crate::game::phase::BettingRoundAction;
-use crate::lobby::{Lobby, LobbyRegistry};
-use crate::player::{Player, PlayerRegistry};
-use crate::output::{GameStateAsPlayer, LobbyInfoPublic};
+use crate::history::{HandHistory, HandHistoryRegistry};
+use crate::leaderboard::{LeaderboardPage, LeaderboardQuery, LeaderboardRegistry};
+use crate::ledger::PlayerBalance;
+use crate::notification::{Notification, NotificationHub, NotificationKind};
+use crate::lobby::{GameMode, Lobby, LobbyInvitation, LobbyInvitationRegistry, LobbyInvite, LobbyInviteRegistry, LobbyRegistry, LobbySettings, LobbyWaitlistPosition};
+use crate::player::{Player, PlayerProfile, PlayerRegistry};
+use crate::quickplay::{QuickPlayCriteria, QuickPlayQueue, QuickPlayStatus, QuickPlayTicket};
+use crate::rating::RatingRegistry;
+use crate::stats::{PlayerStats, StatsRegistry};
+use crate::output::{GameEvent, GameState, GameStateAsPlayer, LobbyFilter, LobbyInfoPublic, LobbyListEvent, LobbyListView, LobbyPage, LobbyQuery, SubscriberLag};
+use crate::r#match::Match;
+use crate::storage::{self, InMemoryStorage, Storage, StorageRecords};
 
-#[derive(Default)]
+#[derive(Clone)] // shares its registries, for the sake of background tasks
 pub struct GameService {
     lobby_registry: Arc<RwLock<LobbyRegistry>>,
     player_registry: Arc<RwLock<PlayerRegistry>>,
     player_lobby_map: Arc<RwLock<HashMap<Uuid, Uuid>>>,
+    spectator_lobby_map: Arc<RwLock<HashMap<Uuid, Uuid>>>, // not persisted
+    waitlist_lobby_map: Arc<RwLock<HashMap<Uuid, Uuid>>>, // not persisted
+    hand_history_registry: Arc<RwLock<HandHistoryRegistry>>,
+    rating_registry: Arc<RwLock<RatingRegistry>>, // derived from hand histories
+    stats_registry: Arc<RwLock<StatsRegistry>>, // derived from hand histories
+    leaderboard_registry: Arc<RwLock<LeaderboardRegistry>>, // derived from hand histories
+    achievement_registry: Arc<RwLock<AchievementRegistry>>, // derived from hand histories
+    friend_registry: Arc<RwLock<FriendRegistry>>,
+    lobby_invite_registry: Arc<RwLock<LobbyInviteRegistry>>, // not persisted
+    lobby_invitation_registry: Arc<RwLock<LobbyInvitationRegistry>>, // not persisted
+    chat_registry: Arc<ChatRegistry>, // not persisted
+    notification_hub: Arc<NotificationHub>, // not persisted
+    quick_play_queue: Arc<RwLock<QuickPlayQueue>>, // not persisted
+    storage: Arc<dyn Storage>,
+}
+
+impl Default for GameService {
+    fn default() -> Self {
+        let storage: Arc<dyn Storage> = Arc::new(InMemoryStorage::default());
+        GameService {
+            lobby_registry: Arc::new(RwLock::new(LobbyRegistry::new(storage.clone()))),
+            player_registry: Arc::new(RwLock::new(PlayerRegistry::restore(storage.clone(), HashMap::new(), Vec::new()))),
+            player_lobby_map: Arc::default(),
+            spectator_lobby_map: Arc::default(),
+            waitlist_lobby_map: Arc::default(),
+            hand_history_registry: Arc::new(RwLock::new(HandHistoryRegistry::restore(storage.clone(), Vec::new()))),
+            rating_registry: Arc::default(),
+            stats_registry: Arc::default(),
+            leaderboard_registry: Arc::default(),
+            achievement_registry: Arc::default(),
+            friend_registry: Arc::new(RwLock::new(FriendRegistry::restore(storage.clone(), Vec::new()))),
+            lobby_invite_registry: Arc::default(),
+            lobby_invitation_registry: Arc::default(),
+            chat_registry: Arc::default(),
+            notification_hub: Arc::default(),
+            quick_play_queue: Arc::default(),
+            storage,
+        }
+    }
+}
+
+impl GameService {
+    /// Restores any persisted players, balances, lobbies and in-progress matches.<br />
+    /// Matches that can't be resumed get their pot contributions refunded instead.<br />
+    /// Played out matches whose settlement failed get settled as they are, without being played again.
+    pub async fn restore(storage: Arc<dyn Storage>) -> Result<Self, AppError> {
+        let records = storage::run_blocking(&storage, |storage| storage.load()).await?;
+        let StorageRecords {
+            players,
+            transactions,
+            lobbies,
+            match_snapshots,
+            mut hand_histories,
+            friendships,
+            pending_settlements,
+        } = records;
+        let player_registry = PlayerRegistry::restore(storage.clone(), players, transactions);
+        let player_registry = Arc::new(RwLock::new(player_registry));
+        for settlement in pending_settlements.into_values() {
+            let match_id = settlement.match_id;
+            let mut player_registry_w = player_registry.write().await;
+            let history = match Match::settle_pending(settlement, &mut player_registry_w).await {
+                Ok(Some(history)) => history,
+                Ok(None) => continue,
+                Err(err) => {
+                    eprintln!("Settling pending match ({match_id}) failed, retrying on the next boot: {err}");
+                    continue;
+                },
+            };
+            let stored_history = history.clone();
+            if let Err(err) = storage::run_blocking(&storage, move |storage| storage.store_hand_history(&stored_history)).await {
+                eprintln!("Recording match ({match_id}) hand history failed: {err}");
+            }
+            hand_histories.push(history);
+        }
+        hand_histories.sort_by_key(|history| (history.ended_at, history.started_at));
+        let rating_registry = RatingRegistry::restore(&hand_histories);
+        let stats_registry = StatsRegistry::restore(&hand_histories);
+        let leaderboard_registry = LeaderboardRegistry::restore(&hand_histories);
+        let achievement_registry = AchievementRegistry::restore(&hand_histories);
+        let hand_history_registry = HandHistoryRegistry::restore(storage.clone(), hand_histories);
+        let friend_registry = FriendRegistry::restore(storage.clone(), friendships);
+        let hand_history_registry = Arc::new(RwLock::new(hand_history_registry));
+
+        let mut lobby_registry = LobbyRegistry::new(storage.clone());
+        let mut player_lobby_map = HashMap::new();
+        for record in lobbies.into_values() {
+            let has_plaintext_password = record.password.is_some();
+            let lobby = Lobby::from_record(
+                Self::LOBBY_BROADCAST_CHANNEL_CAPACITY,
+                player_registry.clone(),
+                record,
+            );
+            if has_plaintext_password {
+                let record = lobby.to_record(); // hashed from now on
+                storage::run_blocking(&storage, move |storage| storage.store_lobby(record)).await?;
+            }
+            let lobby_id = lobby.lobby_id;
+            for player_id in lobby.seats.values() {
+                player_lobby_map.insert(*player_id, lobby_id);
+            }
+            let lobby_arc = Arc::new(RwLock::new(lobby));
+            lobby_arc.write().await.state_broadcaster.set_lobby(lobby_arc.clone());
+            lobby_registry.restore_lobby(lobby_id, lobby_arc).await;
+        }
+
+        let mut resumed_lobby_arcs = Vec::new();
+        for snapshot in match_snapshots.into_values() {
+            let lobby_arc = lobby_registry.get_lobby_arc(&snapshot.lobby_id).await;
+            let player_ids = &snapshot.poker_phase.get_table().player_ids;
+            let resumable = match &lobby_arc {
+                _ if snapshot.poker_phase.is_played_out() => false,
+                Some(lobby_arc) => {
+                    let lobby_r = lobby_arc.read().await;
+                    !lobby_r.is_in_game() && player_ids.is_subset(&lobby_r.get_player_ids())
+                },
+                None => false,
+            };
+            match lobby_arc {
+                Some(lobby_arc) if resumable => {
+                    let mut lobby_w = lobby_arc.write().await;
+                    lobby_w.restore_match(
+                        snapshot,
+                        player_registry.clone(),
+                        hand_history_registry.clone(),
+                        storage.clone(),
+                    ).await;
+                    resumed_lobby_arcs.push(lobby_arc.clone());
+                },
+                _ => {
+                    let mut player_registry_w = player_registry.write().await;
+                    Match::settle_interrupted(snapshot, &mut player_registry_w).await?;
+                },
+            }
+        }
+
+        let game_service = GameService {
+            lobby_registry: Arc::new(RwLock::new(lobby_registry)),
+            player_registry,
+            player_lobby_map: Arc::new(RwLock::new(player_lobby_map)),
+            spectator_lobby_map: Arc::default(),
+            waitlist_lobby_map: Arc::default(),
+            hand_history_registry,
+            rating_registry: Arc::new(RwLock::new(rating_registry)),
+            stats_registry: Arc::new(RwLock::new(stats_registry)),
+            leaderboard_registry: Arc::new(RwLock::new(leaderboard_registry)),
+            achievement_registry: Arc::new(RwLock::new(achievement_registry)),
+            friend_registry: Arc::new(RwLock::new(friend_registry)),
+            lobby_invite_registry: Arc::default(),
+            lobby_invitation_registry: Arc::default(),
+            chat_registry: Arc::default(),
+            notification_hub: Arc::default(),
+            quick_play_queue: Arc::default(),
+            storage,
+        };
+        for lobby_arc in resumed_lobby_arcs {
+            game_service.spawn_match_cleanup(lobby_arc.clone(), &*lobby_arc.read().await);
+        }
+        Ok(game_service)
+    }
+
+    /// Sent to every player watching notifications, later subscribers receiving the latest one too.
+    pub fn announce(&self, text: String) {
+        self.notification_hub.announce(text);
+    }
+
+    /// Swaps out the default (no-op) chat profanity filter.
+    pub fn with_profanity_filter(mut self, profanity_filter: Arc<dyn ProfanityFilter>) -> Self {
+        self.chat_registry = Arc::new(ChatRegistry::new(profanity_filter));
+        self
+    }
 }
 
 // RPCs
 impl GameService {
     const LOBBY_BROADCAST_CHANNEL_CAPACITY: usize = 10;
+    const MIN_REPLAY_SPEED: f32 = 0.25;
+    const MAX_REPLAY_SPEED: f32 = 16.0;
 
-    pub async fn connect_rpc(&self) -> Result<Uuid, AppError> {
+    /// Registers a new player or resumes an existing one given its credentials.<br />
+    /// Returns the player along with its access token, which only ever gets stored hashed.
+    pub async fn connect_rpc(&self, credentials: Option<(Uuid, String)>) -> Result<(Player, String), AppError> {
         let mut player_registry_w = self.player_registry.write().await;
-        let player = Player::register();
-        let player_id = player.player_id.clone();
-        player_registry_w.add_player(player).await?;
-        Ok(player_id)
+        if let Some((player_id, access_token)) = credentials {
+            let player = player_registry_w
+                .get_player(&player_id)
+                .await
+                .ok_or(AppError::not_found(player_id))?;
+            if !player.verify_access_token(&access_token) {
+                return Err(AppError::unauthorized("Invalid player credentials!"));
+            }
+            return Ok((player, access_token));
+        }
+        let (player, access_token) = Player::register();
+        player_registry_w.add_player(player.clone()).await?;
+        Ok((player, access_token))
+    }
+
+    pub async fn get_player_rpc(&self, player_id: &Uuid) -> Result<Player, AppError> {
+        let player_registry_r = self.player_registry.read().await;
+        player_registry_r
+            .get_player(player_id)
+            .await
+            .ok_or(AppError::not_found(*player_id))
+    }
+
+    pub async fn get_player_profile_rpc(&self, player_id: &Uuid) -> Result<PlayerProfile, AppError> {
+        let player = self.get_player_rpc(player_id).await?;
+        let ratings = self.rating_registry.read().await.get_player_ratings(player_id);
+        Ok(PlayerProfile {
+            player_id: player.player_id,
+            player_name: player.player_name,
+            ratings,
+        })
+    }
+
+    pub async fn get_player_stats_rpc(&self, player_id: &Uuid) -> Result<PlayerStats, AppError> {
+        self.get_player_rpc(player_id).await?;
+        let stats_registry_r = self.stats_registry.read().await;
+        Ok(stats_registry_r.get_stats(player_id))
+    }
+
+    pub async fn get_player_achievements_rpc(&self, player_id: &Uuid) -> Result<Vec<(Achievement, AchievementProgress)>, AppError> {
+        self.get_player_rpc(player_id).await?;
+        let achievement_registry_r = self.achievement_registry.read().await;
+        Ok(achievement_registry_r.get_achievements(player_id))
+    }
+
+    /// Also looks up the caller's own rank.
+    pub async fn get_leaderboard_rpc(&self, query: LeaderboardQuery, player_id: &Uuid) -> LeaderboardPage {
+        let scores = {
+            let leaderboard_registry_r = self.leaderboard_registry.read().await;
+            let rating_registry_r = self.rating_registry.read().await;
+            leaderboard_registry_r.get_scores(
+                query.metric,
+                query.period,
+                query.game_mode,
+                &rating_registry_r,
+                Utc::now(),
+            )
+        };
+        query.paginate(scores, player_id)
+    }
+
+    pub async fn send_friend_request_rpc(&self, player_id: Uuid, addressee_id: Uuid) -> Result<Friendship, AppError> {
+        self.get_player_rpc(&addressee_id).await?;
+        let mut friend_registry_w = self.friend_registry.write().await;
+        friend_registry_w.send_request(player_id, addressee_id).await
+    }
+
+    pub async fn accept_friend_request_rpc(&self, player_id: Uuid, requester_id: Uuid) -> Result<Friendship, AppError> {
+        let mut friend_registry_w = self.friend_registry.write().await;
+        friend_registry_w.accept_request(player_id, requester_id).await
+    }
+
+    pub async fn remove_friend_rpc(&self, player_id: Uuid, other_player_id: Uuid) -> Result<(), AppError> {
+        let mut friend_registry_w = self.friend_registry.write().await;
+        friend_registry_w.remove(player_id, other_player_id).await
+    }
+
+    /// Friends first, then incoming and outgoing requests, each by name.<br />
+    /// Connections are tracked by the gRPC layer, hence the online players being passed in.
+    pub async fn get_friends_rpc(&self, player_id: Uuid, online_player_ids: &HashSet<Uuid>) -> Result<Vec<FriendInfo>, AppError> {
+        let friendships: Vec<Friendship> = self.friend_registry
+            .read()
+            .await
+            .get_friendships(&player_id)
+            .into_iter()
+            .cloned()
+            .collect();
+        let friend_ids = friendships
+            .iter()
+            .map(|friendship| friendship.get_other_player_id(&player_id))
+            .collect();
+        let players = self.player_registry.read().await.get_players(&friend_ids).await?;
+
+        let mut friends = Vec::with_capacity(friendships.len());
+        for friendship in friendships {
+            let friend_id = friendship.get_other_player_id(&player_id);
+            let status = friendship.get_status(&player_id);
+            let presence = match status {
+                FriendshipStatus::Friend => self.get_presence(&friend_id, online_player_ids).await,
+                _ => FriendPresence::Offline,
+            };
+            friends.push(FriendInfo {
+                player_id: friend_id,
+                player_name: players.get(&friend_id).map(|player| player.player_name.clone()).unwrap_or_default(),
+                status,
+                since: friendship.updated_at,
+                presence,
+            });
+        }
+        friends.sort_by(|a, b| {
+            (a.status as u8, &a.player_name, a.player_id).cmp(&(b.status as u8, &b.player_name, b.player_id))
+        });
+        Ok(friends)
+    }
+
+    /// Goes through the same checks as joining by lobby id.
+    pub async fn join_friend_lobby_rpc(&self, player_id: Uuid, friend_id: Uuid, password: Option<String>, seat: Option<u8>) -> Result<(), AppError> {
+        if !self.friend_registry.read().await.is_friend(player_id, friend_id) {
+            return Err(AppError::unauthorized("Only lobbies of friends may be joined this way!"));
+        }
+        let lobby = self.get_player_lobby(&friend_id)
+            .await
+            .ok_or(AppError::precondition_failed(format!("Player ({friend_id}) not participating in any lobbies!")))?;
+        self.join_lobby_rpc(lobby.lobby_id, player_id, password, false, seat).await
     }
 
     pub async fn disconnect_rpc(&self, player_id: &Uuid) -> Result<(), AppError> {
@@ -64,6 +375,7 @@ impl GameService {
         );
         let lobby_id = lobby.lobby_id;
         let lobby_public = lobby.clone().into();
+        let presence = Self::get_lobby_presence(&lobby);
         let lobby_arc = Arc::new(RwLock::new(lobby));
 
         lobby_arc.write().await.state_broadcaster.set_lobby(lobby_arc.clone());
@@ -71,11 +383,21 @@ impl GameService {
         let mut player_lobby_map_w = self.player_lobby_map.write().await;
         lobby_registry_w.add_lobby(lobby_id, lobby_arc).await?;
         player_lobby_map_w.insert(player_id, lobby_id);
+        drop(player_lobby_map_w);
+        drop(lobby_registry_w);
 
+        self.stop_spectating(&player_id).await;
+        self.notify_presence(player_id, presence).await;
         Ok(lobby_public)
     }
 
-    pub async fn join_lobby_rpc(&self, lobby_id: Uuid, player_id: Uuid) -> Result<(), AppError> {
+    /// Private lobbies may only be joined by invite code.
+    pub async fn join_lobby_rpc(&self, lobby_id: Uuid, player_id: Uuid, password: Option<String>, via_invite_code: bool, seat: Option<u8>) -> Result<(), AppError> {
+        self.join_lobby(lobby_id, player_id, password.as_deref(), via_invite_code, seat, None).await
+    }
+
+    /// Invited players skip the lobby's password and privacy, as long as their invitation is still valid.
+    async fn join_lobby(&self, lobby_id: Uuid, player_id: Uuid, password: Option<&str>, via_invite_code: bool, seat: Option<u8>, invitation: Option<&LobbyInvitation>) -> Result<(), AppError> {
         {
             let player_lobby_map_r = self.player_lobby_map.read().await;
             if let Some(joined_lobby_id) = player_lobby_map_r.get(&player_id) {
@@ -98,20 +420,77 @@ impl GameService {
                 .await
                 .ok_or(AppError::internal("Lobby doesn't exist!"))
         }?;
+        let lobby_registry = self.lobby_registry.read().await.clone(); // avoid lock inversion
         let mut lobby_w = lobby_arc.write().await;
 
         if lobby_w.is_player(&player_id) {
             return Ok(());
         }
 
-        lobby_w.add_player(player_id).await?;
+        match invitation {
+            Some(invitation) => {
+                self.lobby_invitation_registry
+                    .read()
+                    .await
+                    .get_invitation(&invitation.invitation_id, &player_id)?; // not invalidated in the meantime
+                if !lobby_w.is_host_player(&invitation.host_player_id) {
+                    return Err(AppError::precondition_failed("Invitation invalid, its inviter no longer hosts the lobby!"));
+                }
+                lobby_w.add_invited_player(player_id, seat).await?
+            },
+            None => lobby_w.add_player(player_id, password, via_invite_code, seat).await?,
+        }
+        lobby_registry.store_lobby(&lobby_w).await?;
 
-        let mut player_lobby_map_w = self.player_lobby_map.write().await;
-        player_lobby_map_w.insert(player_id, lobby_w.lobby_id);
+        {
+            let mut player_lobby_map_w = self.player_lobby_map.write().await;
+            player_lobby_map_w.insert(player_id, lobby_w.lobby_id);
+        }
+        let presence = Self::get_lobby_presence(&lobby_w);
+        drop(lobby_w);
+
+        self.stop_spectating(&player_id).await;
+        self.notify_presence(player_id, presence).await;
+        Ok(())
+    }
+
+    /// Spectating a lobby drops any previously spectated one.
+    pub async fn spectate_lobby_rpc(&self, lobby_id: Uuid, player_id: Uuid, password: Option<String>, via_invite_code: bool) -> Result<(), AppError> {
+        {
+            let player_lobby_map_r = self.player_lobby_map.read().await;
+            if let Some(joined_lobby_id) = player_lobby_map_r.get(&player_id) {
+                return Err(AppError::precondition_failed(
+                    format!("Player ({player_id}) already participating in a lobby ({joined_lobby_id})!"),
+                ));
+            }
+        }
+
+        let lobby_arc = {
+            let lobby_registry_r = self.lobby_registry.read().await;
+            lobby_registry_r
+                .get_lobby_arc(&lobby_id)
+                .await
+                .ok_or(AppError::internal("Lobby doesn't exist!"))
+        }?;
+        {
+            let mut lobby_w = lobby_arc.write().await;
+            if lobby_w.is_spectator(&player_id) {
+                return Ok(());
+            }
+            lobby_w.add_spectator(player_id, password.as_deref(), via_invite_code).await?;
+        }
+
+        self.stop_spectating(&player_id).await;
+        let mut spectator_lobby_map_w = self.spectator_lobby_map.write().await;
+        spectator_lobby_map_w.insert(player_id, lobby_id);
         Ok(())
     }
 
     pub async fn leave_lobby_rpc(&self, player_id: Uuid) -> Result<(), AppError> {
+        if self.stop_spectating(&player_id).await {
+            return Ok(());
+        }
+
         let lobby_id = {
             let player_lobby_map_r = self.player_lobby_map.read().await;
             player_lobby_map_r
@@ -133,6 +512,7 @@ impl GameService {
                     AppError::internal("Incomplete state [DEBUG]") // TODO
                 )
         }?;
+        let lobby_registry = self.lobby_registry.read().await.clone();
         let mut lobby_w = lobby_arc.write().await;
 
         if lobby_w.is_host_player(&player_id) {
@@ -142,7 +522,241 @@ impl GameService {
         // TODO: only check in_game(), otherwise force-disable matchmaking and leave
         lobby_w.lobby_locked_validation()?;
 
-        lobby_w.rm_player(&player_id).await
+        lobby_w.rm_player(&player_id).await?;
+        {
+            let mut player_lobby_map_w = self.player_lobby_map.write().await;
+            player_lobby_map_w.remove(&player_id);
+        }
+        Self::offer_free_seat(&lobby_arc, &mut lobby_w).await;
+        if lobby_w.seats.is_empty() && lobby_w.waitlist.is_empty() {
+            let spectator_ids = std::mem::take(&mut lobby_w.spectator_ids);
+            drop(lobby_w);
+            {
+                let mut spectator_lobby_map_w = self.spectator_lobby_map.write().await;
+                spectator_lobby_map_w.retain(|spectator_id, _| !spectator_ids.contains(spectator_id));
+            }
+            self.rm_lobby(&lobby_id).await?;
+        } else {
+            lobby_registry.store_lobby(&lobby_w).await?;
+            drop(lobby_w);
+        }
+
+        self.notify_presence(player_id, FriendPresence::Online).await;
+        Ok(())
+    }
+
+    pub async fn change_lobby_seat_rpc(&self, player_id: Uuid, seat: u8) -> Result<(), AppError> {
+        let lobby = self.get_player_lobby(&player_id)
+            .await
+            .ok_or(AppError::precondition_failed(format!("Player ({player_id}) not participating in any lobbies!")))?;
+        let lobby_arc = {
+            let lobby_registry_r = self.lobby_registry.read().await;
+            lobby_registry_r
+                .get_lobby_arc(&lobby.lobby_id)
+                .await
+                .ok_or(AppError::internal("Incomplete state [DEBUG]"))
+        }?;
+        let lobby_registry = self.lobby_registry.read().await.clone();
+        let mut lobby_w = lobby_arc.write().await;
+
+        lobby_w.change_seat(&player_id, seat).await?;
+        lobby_registry.store_lobby(&lobby_w).await
+    }
+
+    /// Queues up for a seat at a full or in-game lobby.<br />
+    /// Waitlisted players may keep spectating the lobby in the meantime.
+    pub async fn join_lobby_waitlist_rpc(&self, lobby_id: Uuid, player_id: Uuid, password: Option<String>, via_invite_code: bool) -> Result<LobbyWaitlistPosition, AppError> {
+        {
+            let player_lobby_map_r = self.player_lobby_map.read().await;
+            if let Some(joined_lobby_id) = player_lobby_map_r.get(&player_id) {
+                return Err(AppError::precondition_failed(
+                    format!("Player ({player_id}) already participating in a lobby ({joined_lobby_id})!"),
+                ));
+            }
+        }
+        let waitlisted_lobby_id = self.waitlist_lobby_map.read().await.get(&player_id).copied();
+        if let Some(waitlisted_lobby_id) = waitlisted_lobby_id {
+            if waitlisted_lobby_id != lobby_id && self.get_waitlist_position(&player_id, &waitlisted_lobby_id).await.is_some() {
+                return Err(AppError::precondition_failed(
+                    format!("Player ({player_id}) already waiting for a lobby ({waitlisted_lobby_id})!"),
+                ));
+            }
+        }
+
+        let lobby_arc = {
+            let lobby_registry_r = self.lobby_registry.read().await;
+            lobby_registry_r
+                .get_lobby_arc(&lobby_id)
+                .await
+                .ok_or(AppError::internal("Lobby doesn't exist!"))
+        }?;
+        let mut lobby_w = lobby_arc.write().await;
+        if !lobby_w.waitlist.contains(&player_id) {
+            lobby_w.join_waitlist(player_id, password.as_deref(), via_invite_code).await?;
+        }
+        {
+            let mut waitlist_lobby_map_w = self.waitlist_lobby_map.write().await;
+            waitlist_lobby_map_w.insert(player_id, lobby_id);
+        }
+        Self::offer_free_seat(&lobby_arc, &mut lobby_w).await;
+
+        Ok(LobbyWaitlistPosition {
+            lobby_id,
+            position: lobby_w.waitlist.get_position(&player_id).unwrap_or_default(),
+            seat_offer: lobby_w.waitlist.get_seat_offer().filter(|offer| offer.player_id == player_id).cloned(),
+        })
+    }
+
+    pub async fn leave_lobby_waitlist_rpc(&self, player_id: Uuid) -> Result<(), AppError> {
+        let (lobby_arc, _) = self.get_waitlisted_lobby_arc(&player_id).await?;
+        {
+            let mut waitlist_lobby_map_w = self.waitlist_lobby_map.write().await;
+            waitlist_lobby_map_w.remove(&player_id);
+        }
+        let mut lobby_w = lobby_arc.write().await;
+        lobby_w.leave_waitlist(&player_id).await;
+        Self::offer_free_seat(&lobby_arc, &mut lobby_w).await;
+        Ok(())
+    }
+
+    pub async fn get_lobby_waitlist_position_rpc(&self, player_id: Uuid) -> Result<LobbyWaitlistPosition, AppError> {
+        let (lobby_arc, lobby_id) = self.get_waitlisted_lobby_arc(&player_id).await?;
+        let lobby_r = lobby_arc.read().await;
+        let position = lobby_r.waitlist
+            .get_position(&player_id)
+            .ok_or(AppError::precondition_failed("Player not waiting for any lobbies!"))?;
+        Ok(LobbyWaitlistPosition {
+            lobby_id,
+            position,
+            seat_offer: lobby_r.waitlist.get_seat_offer().filter(|offer| offer.player_id == player_id).cloned(),
+        })
+    }
+
+    /// Accepting takes the offered seat, declining leaves the waitlist.
+    pub async fn respond_seat_offer_rpc(&self, player_id: Uuid, accept: bool) -> Result<(), AppError> {
+        let (lobby_arc, lobby_id) = self.get_waitlisted_lobby_arc(&player_id).await?;
+        let lobby_registry = self.lobby_registry.read().await.clone();
+        let mut lobby_w = lobby_arc.write().await;
+
+        if !accept {
+            if lobby_w.waitlist.get_position(&player_id) != Some(0) {
+                return Err(AppError::precondition_failed("No pending seat offer!"));
+            }
+            lobby_w.leave_waitlist(&player_id).await;
+        } else {
+            lobby_w.accept_seat_offer(player_id).await?;
+            let mut player_lobby_map_w = self.player_lobby_map.write().await;
+            player_lobby_map_w.insert(player_id, lobby_id);
+        }
+        {
+            let mut waitlist_lobby_map_w = self.waitlist_lobby_map.write().await;
+            waitlist_lobby_map_w.remove(&player_id);
+        }
+        Self::offer_free_seat(&lobby_arc, &mut lobby_w).await;
+        lobby_registry.store_lobby(&lobby_w).await?;
+        let presence = Self::get_lobby_presence(&lobby_w);
+        drop(lobby_w);
+
+        if accept {
+            self.stop_spectating(&player_id).await;
+            self.notify_presence(player_id, presence).await;
+        }
+        Ok(())
+    }
+
+    /// Queues the player for the next fitting table, be it an open seat or a newly opened lobby.
+    pub async fn join_quick_play_rpc(&self, player_id: Uuid, criteria: QuickPlayCriteria) -> Result<QuickPlayStatus, AppError> {
+        {
+            let player_lobby_map_r = self.player_lobby_map.read().await;
+            if let Some(lobby_id) = player_lobby_map_r.get(&player_id) {
+                return Err(AppError::precondition_failed(
+                    format!("Player ({player_id}) already participating in a lobby ({lobby_id})!")
+                ));
+            }
+        }
+        if self.waitlist_lobby_map.read().await.contains_key(&player_id) {
+            return Err(AppError::precondition_failed("Player already waiting for a lobby!"));
+        }
+
+        let rating = self.rating_registry.read().await.get_rating(&player_id, criteria.game_mode).rating;
+        let ticket = QuickPlayTicket::new(player_id, criteria, rating);
+        let queued_at = ticket.queued_at;
+        self.quick_play_queue.write().await.push(ticket)?;
+        self.match_quick_play().await;
+        self.spawn_quick_play_widening(player_id, queued_at);
+        self.get_quick_play_status_rpc(player_id).await
+    }
+
+    pub async fn leave_quick_play_rpc(&self, player_id: Uuid) -> Result<(), AppError> {
+        let mut quick_play_queue_w = self.quick_play_queue.write().await;
+        quick_play_queue_w
+            .remove(&player_id)
+            .map(|_| ())
+            .ok_or(AppError::precondition_failed(format!("Player ({player_id}) not queued for quick play!")))
+    }
+
+    /// Once matched, points the player to the lobby it got seated in.
+    pub async fn get_quick_play_status_rpc(&self, player_id: Uuid) -> Result<QuickPlayStatus, AppError> {
+        if let Some(status) = self.quick_play_queue.read().await.get_status(&player_id) {
+            return Ok(status);
+        }
+        let player_lobby_map_r = self.player_lobby_map.read().await;
+        player_lobby_map_r
+            .get(&player_id)
+            .map(|lobby_id| QuickPlayStatus::Seated(*lobby_id))
+            .ok_or(AppError::precondition_failed(format!("Player ({player_id}) not queued for quick play!")))
+    }
+
+    /// Removes a player or spectator from the host's lobby.<br />
+    /// Seated players may only be kicked while the lobby isn't locked in matchmaking or a match.
+    pub async fn kick_lobby_player_rpc(&self, player_id: Uuid, kicked_player_id: Uuid) -> Result<(), AppError> {
+        let lobby = self.get_hosted_lobby(&player_id, "Only the host player may kick players!").await?;
+        if player_id == kicked_player_id {
+            return Err(AppError::invalid_request("Host players can't kick themselves!"));
+        }
+
+        let lobby_arc = {
+            let lobby_registry_r = self.lobby_registry.read().await;
+            lobby_registry_r
+                .get_lobby_arc(&lobby.lobby_id)
+                .await
+                .ok_or(AppError::internal("Incomplete state [DEBUG]"))
+        }?;
+        let lobby_registry = self.lobby_registry.read().await.clone();
+        let mut lobby_w = lobby_arc.write().await;
+
+        if lobby_w.is_spectator(&kicked_player_id) {
+            lobby_w.rm_spectator(&kicked_player_id).await;
+            let mut spectator_lobby_map_w = self.spectator_lobby_map.write().await;
+            spectator_lobby_map_w.remove(&kicked_player_id);
+            self.notification_hub.publish(Notification::new(kicked_player_id, NotificationKind::Kicked { lobby_id: lobby.lobby_id }));
+            return Ok(());
+        }
+        if !lobby_w.is_player(&kicked_player_id) {
+            return Err(AppError::precondition_failed(format!("Player ({kicked_player_id}) not participating in this lobby!")));
+        }
+
+        lobby_w.rm_player(&kicked_player_id).await?;
+        {
+            let mut player_lobby_map_w = self.player_lobby_map.write().await;
+            player_lobby_map_w.remove(&kicked_player_id);
+        }
+        Self::offer_free_seat(&lobby_arc, &mut lobby_w).await;
+        lobby_registry.store_lobby(&lobby_w).await?;
+        drop(lobby_w);
+
+        self.notification_hub.publish(Notification::new(kicked_player_id, NotificationKind::Kicked { lobby_id: lobby.lobby_id }));
+        self.notify_presence(kicked_player_id, FriendPresence::Online).await;
+        Ok(())
+    }
+
+    /// Host-muted players can no longer chat in the lobby, their past messages remain visible.
+    pub async fn mute_lobby_player_rpc(&self, player_id: Uuid, muted_player_id: Uuid, muted: bool) -> Result<(), AppError> {
+        let lobby = self.get_hosted_lobby(&player_id, "Only the host player may mute players lobby-wide!").await?;
+        if player_id == muted_player_id {
+            return Err(AppError::invalid_request("Host players can't mute themselves!"));
+        }
+        self.chat_registry.set_host_mute(lobby.lobby_id, muted_player_id, muted)
     }
 
     // // TODO: restructure as non-rpc, allow users to call leave_lobby instead?
@@ -177,20 +791,37 @@ impl GameService {
     //
     //     lobby.lobby_locked_validation()?;
     //
-    //     let mut lobby_registry_w = self.lobby_registry.write().await;
-    //     lobby_registry_w.rm_lobby(&lobby.lobby_id).await
+    //     self.rm_lobby(&lobby.lobby_id).await
     // }
 
-    pub async fn get_lobbies_rpc(&self) -> Vec<LobbyInfoPublic> {
-        let lobbies;
-        {
-            let lobby_registry_r = self.lobby_registry.read().await;
-            lobbies = lobby_registry_r.get_lobbies().await;
+    pub async fn get_lobbies_rpc(&self, query: LobbyQuery) -> LobbyPage {
+        let lobby_registry_r = self.lobby_registry.read().await;
+        lobby_registry_r.search_lobbies(&query)
+    }
+
+    /// Streams the filtered lobby list, starting off with every currently matching lobby.
+    pub async fn watch_lobbies_rpc(&self, filter: LobbyFilter) -> impl Stream<Item = Result<LobbyListEvent, AppError>> + Send {
+        let lobby_registry = self.lobby_registry.read().await.clone();
+        let mut receiver = lobby_registry.subscribe();
+        let mut view = LobbyListView::new(filter);
+        let mut pending_events = view.resync(lobby_registry.get_lobby_infos());
+
+        async_stream::stream! {
+            loop {
+                for event in pending_events.drain(..) {
+                    yield Ok(event);
+                }
+                match receiver.recv().await {
+                    Ok(event) => pending_events.extend(view.apply(event)),
+                    Err(RecvError::Lagged(_)) => {
+                        // Items still queued up are older than the resynced lobby list...
+                        receiver = receiver.resubscribe();
+                        pending_events = view.resync(lobby_registry.get_lobby_infos());
+                    },
+                    Err(RecvError::Closed) => break,
+                }
+            }
         }
-        lobbies
-            .into_iter()
-            .map(|lobby| lobby.into())
-            .collect()
     }
 
     pub async fn set_lobby_matchmaking_status_rpc(
@@ -219,6 +850,7 @@ impl GameService {
                     AppError::internal("Incomplete state [DEBUG]") // TODO
                 )
         }?;
+        let lobby_registry = self.lobby_registry.read().await.clone();
         let mut lobby_w = lobby_arc.write().await;
 
         if !lobby_w.is_host_player(&player_id) {
@@ -231,12 +863,245 @@ impl GameService {
 
         if matchmaking {
             lobby_w.start_matchmaking().await?;
+            if let Some(deadline) = lobby_w.matchmaking_deadline {
+                self.spawn_matchmaking_deadline(lobby_arc.clone(), deadline);
+            }
         } else {
             lobby_w.stop_matchmaking().await;
+            Self::offer_free_seat(&lobby_arc, &mut lobby_w).await;
+        }
+        lobby_registry.publish_lobby_update(&lobby_w);
+        Ok(())
+    }
+
+    pub async fn set_lobby_settings_rpc(
+        &self,
+        player_id: Uuid,
+        settings: LobbySettings,
+    ) -> Result<(), AppError> {
+        let lobby_id = {
+            let player_lobby_map_r = self.player_lobby_map.read().await;
+            player_lobby_map_r
+                .get(&player_id)
+                .copied()
+                .ok_or(
+                    AppError::precondition_failed(
+                        format!("Player ({player_id}) not participating in any lobbies!")
+                    )
+                )
+        }?;
+
+        let lobby_arc = {
+            let lobby_registry_r = self.lobby_registry.read().await;
+            lobby_registry_r
+                .get_lobby_arc(&lobby_id)
+                .await
+                .ok_or(
+                    AppError::internal("Incomplete state [DEBUG]") // TODO
+                )
+        }?;
+        let lobby_registry = self.lobby_registry.read().await.clone();
+        let mut lobby_w = lobby_arc.write().await;
+
+        if !lobby_w.is_host_player(&player_id) {
+            return Err(AppError::unauthorized("Only the host player may modify lobby settings!"));
+        }
+
+        lobby_w.set_settings(settings).await?;
+        Self::offer_free_seat(&lobby_arc, &mut lobby_w).await;
+        lobby_registry.store_lobby(&lobby_w).await
+    }
+
+    /// An empty password lifts the protection.<br />
+    /// Pending invitations to the lobby get invalidated, as they'd skip the new password.
+    pub async fn set_lobby_password_rpc(
+        &self,
+        player_id: Uuid,
+        password: Option<String>,
+    ) -> Result<(), AppError> {
+        let lobby_id = {
+            let player_lobby_map_r = self.player_lobby_map.read().await;
+            player_lobby_map_r
+                .get(&player_id)
+                .copied()
+                .ok_or(
+                    AppError::precondition_failed(
+                        format!("Player ({player_id}) not participating in any lobbies!")
+                    )
+                )
+        }?;
+
+        let lobby_arc = {
+            let lobby_registry_r = self.lobby_registry.read().await;
+            lobby_registry_r
+                .get_lobby_arc(&lobby_id)
+                .await
+                .ok_or(
+                    AppError::internal("Incomplete state [DEBUG]") // TODO
+                )
+        }?;
+        let lobby_registry = self.lobby_registry.read().await.clone();
+        let mut lobby_w = lobby_arc.write().await;
+
+        if !lobby_w.is_host_player(&player_id) {
+            return Err(AppError::unauthorized("Only the host player may set the lobby password!"));
         }
+
+        lobby_w.set_password(password).await?;
+        lobby_registry.store_lobby(&lobby_w).await?;
+        self.lobby_invitation_registry.write().await.rm_lobby_invitations(&lobby_id);
+        Ok(())
+    }
+
+    pub async fn create_lobby_invite_rpc(&self, player_id: Uuid, ttl_seconds: Option<u32>) -> Result<LobbyInvite, AppError> {
+        let lobby = self.get_hosted_lobby(&player_id, "Only the host player may create invites!").await?;
+        let mut lobby_invite_registry_w = self.lobby_invite_registry.write().await;
+        lobby_invite_registry_w.create_invite(lobby.lobby_id, player_id, ttl_seconds)
+    }
+
+    pub async fn revoke_lobby_invite_rpc(&self, player_id: Uuid, code: String) -> Result<(), AppError> {
+        let lobby = self.get_hosted_lobby(&player_id, "Only the host player may revoke invites!").await?;
+        let mut lobby_invite_registry_w = self.lobby_invite_registry.write().await;
+        lobby_invite_registry_w.revoke_invite(&lobby.lobby_id, &code)
+    }
+
+    pub async fn get_lobby_invites_rpc(&self, player_id: Uuid) -> Result<Vec<LobbyInvite>, AppError> {
+        let lobby = self.get_hosted_lobby(&player_id, "Only the host player may list invites!").await?;
+        let lobby_invite_registry_r = self.lobby_invite_registry.read().await;
+        Ok(lobby_invite_registry_r.get_lobby_invites(&lobby.lobby_id))
+    }
+
+    pub async fn invite_lobby_player_rpc(&self, player_id: Uuid, invitee_id: Uuid, ttl_seconds: Option<u32>) -> Result<LobbyInvitation, AppError> {
+        let lobby = self.get_hosted_lobby(&player_id, "Only the host player may invite players!").await?;
+        self.get_player_rpc(&invitee_id).await?;
+        if lobby.is_player(&invitee_id) {
+            return Err(AppError::precondition_failed(format!("Player ({invitee_id}) already seated in the lobby!")));
+        }
+        let invitation = {
+            let mut lobby_invitation_registry_w = self.lobby_invitation_registry.write().await;
+            lobby_invitation_registry_w.create_invitation(lobby.lobby_id, lobby.name, player_id, invitee_id, ttl_seconds)?
+        };
+        self.notification_hub.publish(Notification::new(invitee_id, NotificationKind::LobbyInvitation(invitation.clone())));
+        Ok(invitation)
+    }
+
+    /// Accepted invitations get used up once the invitee got seated, failed attempts leave them be.
+    pub async fn respond_lobby_invitation_rpc(&self, player_id: Uuid, invitation_id: Uuid, accept: bool, seat: Option<u8>) -> Result<(), AppError> {
+        let invitation = self.lobby_invitation_registry
+            .read()
+            .await
+            .get_invitation(&invitation_id, &player_id)?;
+        if accept {
+            self.join_lobby(invitation.lobby_id, player_id, None, false, seat, Some(&invitation)).await?;
+        }
+        self.lobby_invitation_registry.write().await.rm_invitation(&invitation_id);
         Ok(())
     }
 
+    pub async fn get_lobby_invitations_rpc(&self, player_id: Uuid) -> Vec<LobbyInvitation> {
+        let lobby_invitation_registry_r = self.lobby_invitation_registry.read().await;
+        lobby_invitation_registry_r.get_invitations(&player_id)
+    }
+
+    /// Streams invitations as they come in, starting off with the pending ones.<br />
+    /// Doesn't require participating in a lobby.
+    pub async fn watch_lobby_invitations_rpc(&self, player_id: Uuid) -> impl Stream<Item = Result<LobbyInvitation, AppError>> + Send {
+        let game_service = self.clone();
+        let mut receiver = self.notification_hub.subscribe(player_id);
+        let mut pending_invitations = self.get_lobby_invitations_rpc(player_id).await;
+
+        async_stream::stream! {
+            loop {
+                for invitation in pending_invitations.drain(..) {
+                    yield Ok(invitation);
+                }
+                match receiver.recv().await {
+                    Ok(notification) => {
+                        if let NotificationKind::LobbyInvitation(invitation) = notification.get_kind() {
+                            pending_invitations.push(invitation.clone());
+                        }
+                    },
+                    Err(RecvError::Lagged(_)) => {
+                        // Pending invitations may get repeated, clients tell them apart by id...
+                        receiver = game_service.notification_hub.subscribe(player_id);
+                        pending_invitations = game_service.get_lobby_invitations_rpc(player_id).await;
+                    },
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        }
+    }
+
+    /// Streams the player's notifications, regardless of whether it's participating in a lobby.<br />
+    /// Starts off with the latest announcement and any pending invitations.
+    pub async fn watch_notifications_rpc(&self, player_id: Uuid) -> impl Stream<Item = Result<Notification, AppError>> + Send {
+        let game_service = self.clone();
+        let mut receiver = self.notification_hub.subscribe(player_id);
+        let mut pending_notifications = self.get_pending_notifications(player_id).await;
+
+        async_stream::stream! {
+            loop {
+                for notification in pending_notifications.drain(..) {
+                    yield Ok(notification);
+                }
+                match receiver.recv().await {
+                    Ok(notification) => pending_notifications.push(notification),
+                    Err(RecvError::Lagged(_)) => {
+                        // Presence changes and the like in between are lost, unlike pending invitations...
+                        receiver = game_service.notification_hub.subscribe(player_id);
+                        pending_notifications = game_service.get_pending_notifications(player_id).await;
+                    },
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        }
+    }
+
+    /// Lets friends know the player came online, or went offline once its last connection dropped.<br />
+    /// Connections are tracked by the gRPC layer, which calls this on every change.
+    pub async fn set_player_online_rpc(&self, player_id: Uuid, is_online: bool) {
+        let presence = match (is_online, self.get_player_lobby(&player_id).await) {
+            (false, _) => FriendPresence::Offline,
+            (true, Some(lobby)) => Self::get_lobby_presence(&lobby),
+            (true, None) => FriendPresence::Online,
+        };
+        self.notify_presence(player_id, presence).await;
+    }
+
+    /// Lets invitees preview private lobbies before joining.
+    pub async fn resolve_lobby_invite_rpc(&self, code: String) -> Result<LobbyInfoPublic, AppError> {
+        let lobby_id = self.resolve_lobby_invite(&code).await?;
+        let lobby_arc = {
+            let lobby_registry_r = self.lobby_registry.read().await;
+            lobby_registry_r
+                .get_lobby_arc(&lobby_id)
+                .await
+                .ok_or(AppError::precondition_failed("Invited lobby no longer exists!"))
+        }?;
+        let lobby = lobby_arc.read().await.clone();
+        Ok(lobby.into())
+    }
+
+    /// Messages are delivered to everyone watching the lobby through WatchEvents.
+    pub async fn send_chat_message_rpc(&self, player_id: Uuid, text: String) -> Result<ChatMessage, AppError> {
+        let (lobby, is_spectator) = self.get_watched_lobby(&player_id).await?;
+        let player = self.get_player_rpc(&player_id).await?;
+        let message = self.chat_registry.post_message(
+            lobby.lobby_id,
+            player_id,
+            player.player_name,
+            is_spectator,
+            &text,
+        )?;
+        lobby.state_broadcaster.publish_event(GameEvent::chat_message(message.clone()));
+        Ok(message)
+    }
+
+    /// Personal mutes apply across lobbies and only affect the muting player's own streams.
+    pub async fn mute_chat_player_rpc(&self, player_id: Uuid, muted_player_id: Uuid, muted: bool) -> Result<(), AppError> {
+        self.chat_registry.set_player_mute(player_id, muted_player_id, muted)
+    }
+
     pub async fn respond_lobby_matchmaking_rpc(
         &self,
         player_id: Uuid,
@@ -263,6 +1128,7 @@ impl GameService {
                     AppError::internal("Incomplete state [DEBUG]") // TODO
                 )
         }?;
+        let lobby_registry = self.lobby_registry.read().await.clone();
         let mut lobby_w = lobby_arc.write().await;
 
         if lobby_w.is_host_player(&player_id) {
@@ -275,7 +1141,9 @@ impl GameService {
             return Err(AppError::precondition_failed("Lobby not currently matchmaking!"));
         }
 
-        lobby_w.set_match_acceptance(player_id, acceptance).await
+        lobby_w.set_match_acceptance(player_id, acceptance).await?;
+        self.auto_start_match(&lobby_arc, &mut lobby_w, lobby_registry).await;
+        Ok(())
     }
 
     pub async fn start_lobby_game_rpc(&self, player_id: Uuid) -> Result<(), AppError> {
@@ -300,6 +1168,7 @@ impl GameService {
                     AppError::internal("Incomplete state [DEBUG]") // TODO
                 )
         }?;
+        let lobby_registry = self.lobby_registry.read().await.clone();
 
         {
             let mut lobby_w = lobby_arc.write().await;
@@ -308,15 +1177,7 @@ impl GameService {
                 return Err(AppError::unauthorized("Only the host player may initiate a game!"));
             }
 
-            if lobby_w.check_game_start_possible().is_ok() {
-                let player_registry_r = self.player_registry.read().await;
-                let players = player_registry_r
-                    .get_players(&lobby_w.player_ids)
-                    .await?
-                    .into_values()
-                    .collect();
-                lobby_w.start_match(players).await;
-            }
+            self.start_match(&lobby_arc, &mut lobby_w, lobby_registry).await?;
         }
 
         Ok(())
@@ -402,44 +1263,536 @@ impl GameService {
         Ok(())
     }
 
-    pub async fn watch_state_rpc(&self, player_id: Uuid) -> Result<AsyncStream<Result<GameStateAsPlayer, AppError>, impl Future<Output=()> + Sized>, AppError> {
-        let Some(lobby) = self.get_player_lobby(&player_id).await else {
-            return Err(AppError::precondition_failed("Player not currently participating in a lobby!"));
-        };
+    pub async fn get_balance_rpc(&self, player_id: Uuid) -> Result<PlayerBalance, AppError> {
+        let player_registry_r = self.player_registry.read().await;
+        player_registry_r.get_player_balance(&player_id).await
+    }
 
-        let mut receiver = lobby.state_broadcaster.subscribe();
+    pub async fn get_hand_histories_rpc(&self, player_id: Uuid, limit: Option<usize>) -> Vec<HandHistory> {
+        let hand_history_registry_r = self.hand_history_registry.read().await;
+        hand_history_registry_r.get_player_hand_histories(&player_id, limit)
+    }
 
-        let stream = async_stream::try_stream! {
-            // Stream Current State
-            match lobby.state_broadcaster.build_state(None).await {
-                Ok(state) => match state.as_player(player_id.clone()) {
-                    Ok(state_as_player) => yield state_as_player,
-                    Err(err) => eprintln!("{}", err),
-                },
-                Err(err) => eprintln!("{}", err),
-            };
+    pub async fn export_hand_histories_rpc(&self, player_id: Uuid, limit: Option<usize>) -> String {
+        self.get_hand_histories_rpc(player_id, limit)
+            .await
+            .iter()
+            .map(|history| history.export_pokerstars())
+            .collect::<Vec<String>>()
+            .join("\n\n")
+    }
 
-            // Stream Upcoming States
-            while let Ok(state) = receiver.recv().await {
-                let Some(state) = state else {
-                    break;
-                };
+    /// Re-emits a past hand's states, paced by their original timing over the speed multiplier.
+    pub async fn replay_hand_rpc(
+        &self,
+        player_id: Uuid,
+        match_id: Uuid,
+        speed: f32,
+    ) -> Result<AsyncStream<Result<GameStateAsPlayer, AppError>, impl Future<Output=()> + Sized>, AppError> {
+        if !speed.is_finite() || !(Self::MIN_REPLAY_SPEED..=Self::MAX_REPLAY_SPEED).contains(&speed) {
+            return Err(AppError::invalid_request(format!(
+                "Replay speed must range from {} to {}!", Self::MIN_REPLAY_SPEED, Self::MAX_REPLAY_SPEED,
+            )));
+        }
+        let frames = {
+            let hand_history_registry_r = self.hand_history_registry.read().await;
+            hand_history_registry_r.get_player_hand_replay(player_id, &match_id)?
+        };
 
-                if let Ok(game_state_as_player) = state.as_player(player_id) {
-                    yield game_state_as_player;
+        let stream = async_stream::try_stream! {
+            let mut prev_timestamp: Option<DateTime<Utc>> = None;
+            for frame in frames {
+                if let Some(prev_timestamp) = prev_timestamp {
+                    let delay = (frame.get_timestamp() - prev_timestamp)
+                        .to_std()
+                        .unwrap_or_default()
+                        .div_f32(speed);
+                    tokio::time::sleep(delay).await;
                 }
+                prev_timestamp = Some(frame.get_timestamp());
+                yield frame;
             }
         };
         Ok(stream)
     }
+
+    /// Spectators get their states redacted and, if so configured, delayed.
+    pub async fn watch_state_rpc(&self, player_id: Uuid) -> Result<impl Stream<Item = Result<GameStateAsPlayer, AppError>> + Send, AppError> {
+        let (lobby, is_spectator) = self.get_watched_lobby(&player_id).await?;
+        let view_state = move |state: &GameState| match is_spectator {
+            true => state.as_spectator(player_id),
+            false => state.as_player(player_id),
+        };
+        let delay = Self::get_watch_delay(&lobby, is_spectator);
+
+        let receiver = lobby.state_broadcaster.subscribe();
+
+        // Current State
+        let mut pending_states = VecDeque::new();
+        match lobby.state_broadcaster.build_state(None).await {
+            Ok(state) => match view_state(&state) {
+                Ok(state_as_player) => pending_states.push_back(state_as_player),
+                Err(err) => eprintln!("{}", err),
+            },
+            Err(err) => eprintln!("{}", err),
+        };
+
+        // Upcoming States, resynced with a fresh snapshot whenever the subscriber lags behind
+        let state_broadcaster = lobby.state_broadcaster.clone();
+        let lag_tracker = state_broadcaster.track_subscriber(player_id);
+        let resync = move |skipped_states| {
+            let lag_count = lag_tracker.record_lag(skipped_states);
+            eprintln!("WatchState subscriber ({player_id}) lagged behind by {skipped_states} states ({lag_count} times so far), resyncing...");
+            let state_broadcaster = state_broadcaster.clone();
+            async move { state_broadcaster.build_state(None).await }
+        };
+        let stream = delayed_broadcast_stream(
+            receiver,
+            pending_states,
+            delay,
+            move |state| view_state(state).ok(),
+            GameStateAsPlayer::get_timestamp,
+            resync,
+        );
+        Ok(stream)
+    }
+
+    /// Streams match events as they happen, subject to the same redaction and delay as watch_state_rpc().<br />
+    /// Starts off with the lobby's recent chat history.
+    pub async fn watch_events_rpc(&self, player_id: Uuid) -> Result<impl Stream<Item = Result<GameEvent, AppError>> + Send, AppError> {
+        let (lobby, is_spectator) = self.get_watched_lobby(&player_id).await?;
+        let delay = Self::get_watch_delay(&lobby, is_spectator);
+
+        let receiver = lobby.state_broadcaster.subscribe_events();
+        let chat_history = self.chat_registry
+            .get_history(&lobby.lobby_id, &player_id)
+            .into_iter()
+            .map(GameEvent::chat_message)
+            .collect();
+        let chat_registry = self.chat_registry.clone();
+        let stream = delayed_broadcast_stream(
+            receiver,
+            chat_history,
+            delay,
+            move |event: &GameEvent| match event.get_chat_sender_id() {
+                _ if !event.is_visible_to(&player_id) => None,
+                Some(sender_id) if chat_registry.is_muted_by(&player_id, &sender_id) => None,
+                _ => Some(event.as_viewer(&player_id)),
+            },
+            GameEvent::get_timestamp,
+            // Missed events are gone for good, unlike states...
+            |skipped_events| async move {
+                Err(AppError::data_loss(format!("Subscriber lagged behind by {skipped_events} events, WatchState may be used to catch up!")))
+            },
+        );
+        Ok(stream)
+    }
+
+    /// Lists the lobby's WatchState subscriptions, along with how often each had to be resynced.
+    pub async fn get_state_subscribers_rpc(&self, player_id: Uuid) -> Result<Vec<SubscriberLag>, AppError> {
+        let (lobby, _) = self.get_watched_lobby(&player_id).await?;
+        if !lobby.is_host_player(&player_id) {
+            return Err(AppError::unauthorized("Only the host player may inspect state subscribers!"));
+        }
+        Ok(lobby.state_broadcaster.get_subscriber_lags())
+    }
 }
 
 impl GameService {
+    pub async fn resolve_lobby_invite(&self, code: &str) -> Result<Uuid, AppError> {
+        let lobby_invite_registry_r = self.lobby_invite_registry.read().await;
+        lobby_invite_registry_r.resolve_invite(code)
+    }
+
+    async fn get_hosted_lobby(&self, player_id: &Uuid, unauthorized_info: &str) -> Result<Lobby, AppError> {
+        let lobby = self.get_player_lobby(player_id)
+            .await
+            .ok_or(AppError::precondition_failed(format!("Player ({player_id}) not participating in any lobbies!")))?;
+        if !lobby.is_host_player(player_id) {
+            return Err(AppError::unauthorized(unauthorized_info));
+        }
+        Ok(lobby)
+    }
+
+    async fn get_waitlisted_lobby_arc(&self, player_id: &Uuid) -> Result<(Arc<RwLock<Lobby>>, Uuid), AppError> {
+        let lobby_id = {
+            let waitlist_lobby_map_r = self.waitlist_lobby_map.read().await;
+            waitlist_lobby_map_r
+                .get(player_id)
+                .copied()
+                .ok_or(AppError::precondition_failed("Player not waiting for any lobbies!"))
+        }?;
+        let lobby_registry_r = self.lobby_registry.read().await;
+        let lobby_arc = lobby_registry_r
+            .get_lobby_arc(&lobby_id)
+            .await
+            .ok_or(AppError::precondition_failed("Waitlisted lobby no longer exists!"))?;
+        Ok((lobby_arc, lobby_id))
+    }
+
+    async fn get_waitlist_position(&self, player_id: &Uuid, lobby_id: &Uuid) -> Option<u32> {
+        let lobby_registry_r = self.lobby_registry.read().await;
+        let lobby_arc = lobby_registry_r.get_lobby_arc(lobby_id).await?;
+        let position = lobby_arc.read().await.waitlist.get_position(player_id);
+        position
+    }
+
+    /// Offers a freed up seat to the waitlist, passing it on whenever an offer expires.
+    async fn offer_free_seat(lobby_arc: &Arc<RwLock<Lobby>>, lobby: &mut Lobby) {
+        let Some(mut offer) = lobby.offer_free_seat().await else {
+            return;
+        };
+        let lobby_arc = lobby_arc.clone();
+        tokio::spawn(async move {
+            loop {
+                let window = (offer.expires_at - Utc::now()).to_std().unwrap_or_default();
+                tokio::time::sleep(window).await;
+                let mut lobby_w = lobby_arc.write().await;
+                match lobby_w.expire_seat_offer(&offer).await {
+                    Some(next_offer) => offer = next_offer,
+                    None => break,
+                }
+            }
+        });
+    }
+
+    async fn start_match(&self, lobby_arc: &Arc<RwLock<Lobby>>, lobby: &mut Lobby, lobby_registry: LobbyRegistry) -> Result<(), AppError> {
+        lobby.check_game_start_possible()?;
+        let players = {
+            let player_registry_r = self.player_registry.read().await;
+            player_registry_r
+                .get_players(&lobby.get_player_ids())
+                .await?
+                .into_values()
+                .collect()
+        };
+        lobby.ante_validation(&players)?;
+        let player_huds = match lobby.settings.hud_enabled {
+            true => self.stats_registry.read().await.get_huds(&lobby.get_player_ids()),
+            false => HashMap::new(),
+        };
+        lobby.start_match(
+            players,
+            player_huds,
+            self.player_registry.clone(),
+            self.hand_history_registry.clone(),
+            self.storage.clone(),
+        ).await;
+        lobby_registry.store_lobby(lobby).await?; // persists the moved dealer button
+        self.spawn_match_cleanup(lobby_arc.clone(), lobby);
+        self.notify_lobby_presence(lobby).await;
+        Ok(())
+    }
+
+    /// Starts the match right away for lobbies opting into auto-start, once every player accepted.
+    async fn auto_start_match(&self, lobby_arc: &Arc<RwLock<Lobby>>, lobby: &mut Lobby, lobby_registry: LobbyRegistry) {
+        if !lobby.settings.matchmaking.auto_start || lobby.check_game_start_possible().is_err() {
+            return;
+        }
+        if let Err(err) = self.start_match(lobby_arc, lobby, lobby_registry).await {
+            eprintln!("Auto-starting lobby ({}) match failed: {err}", lobby.lobby_id);
+        }
+    }
+
+    /// Deals with unresponsive players once the matchmaking round's accept deadline passes.
+    fn spawn_matchmaking_deadline(&self, lobby_arc: Arc<RwLock<Lobby>>, deadline: DateTime<Utc>) {
+        let game_service = self.clone();
+        tokio::spawn(async move {
+            let timeout = (deadline - Utc::now()).to_std().unwrap_or_default();
+            tokio::time::sleep(timeout).await;
+            let lobby_registry = game_service.lobby_registry.read().await.clone();
+            let mut lobby_w = lobby_arc.write().await;
+            if lobby_w.matchmaking_deadline != Some(deadline) {
+                return; // round already over
+            }
+
+            let removed_player_ids = lobby_w.expire_matchmaking().await;
+            {
+                let mut player_lobby_map_w = game_service.player_lobby_map.write().await;
+                for player_id in &removed_player_ids {
+                    player_lobby_map_w.remove(player_id);
+                }
+            }
+            for player_id in removed_player_ids {
+                game_service.notify_presence(player_id, FriendPresence::Online).await;
+            }
+            game_service.auto_start_match(&lobby_arc, &mut lobby_w, lobby_registry.clone()).await;
+            Self::offer_free_seat(&lobby_arc, &mut lobby_w).await;
+            if let Err(err) = lobby_registry.store_lobby(&lobby_w).await {
+                eprintln!("Storing lobby ({}) after its matchmaking deadline failed: {err}", lobby_w.lobby_id);
+            }
+        });
+    }
+
+    /// Seats queued players at open tables first, then opens new tables for whoever can be grouped.
+    async fn match_quick_play(&self) {
+        let mut quick_play_queue_w = self.quick_play_queue.write().await;
+        let lobby_registry = self.lobby_registry.read().await.clone();
+        let now = Utc::now();
+
+        for ticket in quick_play_queue_w.get_tickets().to_vec() {
+            let seated = self.player_lobby_map.read().await.contains_key(&ticket.player_id);
+            if seated || self.fill_open_seat(&ticket, &lobby_registry, now).await {
+                quick_play_queue_w.remove(&ticket.player_id);
+            }
+        }
+        while let Some((settings, tickets)) = quick_play_queue_w.take_table_group(now) {
+            if let Err(err) = self.open_quick_play_table(settings, tickets).await {
+                eprintln!("Opening a quick play table failed: {err}");
+            }
+        }
+    }
+
+    /// Fuller tables get filled first, to get them going sooner.
+    async fn fill_open_seat(&self, ticket: &QuickPlayTicket, lobby_registry: &LobbyRegistry, now: DateTime<Utc>) -> bool {
+        let mut lobbies: Vec<LobbyInfoPublic> = lobby_registry
+            .get_lobby_infos()
+            .into_iter()
+            .filter(|lobby| lobby.is_open_to_public() && ticket.accepts(lobby.get_settings(), now))
+            .collect();
+        lobbies.sort_by_key(|lobby| lobby.get_available_seats());
+
+        for lobby in lobbies {
+            let Some(lobby_arc) = lobby_registry.get_lobby_arc(&lobby.get_lobby_id()).await else {
+                continue;
+            };
+            let mut lobby_w = lobby_arc.write().await;
+            let table_rating = self.get_average_rating(&lobby_w.get_player_ids(), lobby_w.settings.get_game_mode()).await;
+            if !ticket.accepts_rating(table_rating, now) {
+                continue;
+            }
+            if lobby_w.add_player(ticket.player_id, None, false, None).await.is_err() {
+                continue; // filled up or locked in the meantime
+            }
+            if let Err(err) = lobby_registry.store_lobby(&lobby_w).await {
+                eprintln!("Storing lobby ({}) after seating quick play player ({}) failed: {err}", lobby_w.lobby_id, ticket.player_id);
+            }
+            {
+                let mut player_lobby_map_w = self.player_lobby_map.write().await;
+                player_lobby_map_w.insert(ticket.player_id, lobby_w.lobby_id);
+            }
+            let presence = Self::get_lobby_presence(&lobby_w);
+            drop(lobby_w);
+
+            self.stop_spectating(&ticket.player_id).await;
+            self.notify_presence(ticket.player_id, presence).await;
+            return true;
+        }
+        false
+    }
+
+    async fn get_average_rating(&self, player_ids: &HashSet<Uuid>, game_mode: GameMode) -> f64 {
+        let rating_registry_r = self.rating_registry.read().await;
+        let rating_sum: f64 = player_ids
+            .iter()
+            .map(|player_id| rating_registry_r.get_rating(player_id, game_mode).rating)
+            .sum();
+        rating_sum / player_ids.len().max(1) as f64
+    }
+
+    /// Hosted by the longest waiting player, starting matchmaking right away.
+    async fn open_quick_play_table(&self, settings: LobbySettings, tickets: Vec<QuickPlayTicket>) -> Result<(), AppError> {
+        let Some((host_ticket, guest_tickets)) = tickets.split_first() else {
+            return Ok(());
+        };
+        let host_player = {
+            let player_registry_r = self.player_registry.read().await;
+            player_registry_r
+                .get_player(&host_ticket.player_id)
+                .await
+                .ok_or(AppError::internal("Couldn't retrieve player information!"))?
+        };
+
+        let lobby = Lobby::new(
+            Self::LOBBY_BROADCAST_CHANNEL_CAPACITY,
+            self.player_registry.clone(),
+            format!("Quick play ({} ante)", settings.ante_amount),
+            host_player.player_id,
+            host_player.player_name,
+        );
+        let lobby_id = lobby.lobby_id;
+        let lobby_arc = Arc::new(RwLock::new(lobby));
+
+        let (matchmaking_deadline, presence) = {
+            let mut lobby_w = lobby_arc.write().await;
+            lobby_w.state_broadcaster.set_lobby(lobby_arc.clone());
+            lobby_w.set_settings(settings).await?;
+            for ticket in guest_tickets {
+                lobby_w.add_player(ticket.player_id, None, false, None).await?;
+            }
+            lobby_w.start_matchmaking().await?;
+            (lobby_w.matchmaking_deadline, Self::get_lobby_presence(&lobby_w))
+        };
+        let mut lobby_registry_w = self.lobby_registry.write().await;
+        let mut player_lobby_map_w = self.player_lobby_map.write().await;
+        lobby_registry_w.add_lobby(lobby_id, lobby_arc.clone()).await?;
+        for ticket in &tickets {
+            player_lobby_map_w.insert(ticket.player_id, lobby_id);
+        }
+        drop(player_lobby_map_w);
+        drop(lobby_registry_w);
+
+        for ticket in &tickets {
+            self.stop_spectating(&ticket.player_id).await;
+            self.notify_presence(ticket.player_id, presence).await;
+        }
+        if let Some(deadline) = matchmaking_deadline {
+            self.spawn_matchmaking_deadline(lobby_arc, deadline);
+        }
+        Ok(())
+    }
+
+    /// Retries matching as the player's criteria widen, for as long as it stays queued.
+    fn spawn_quick_play_widening(&self, player_id: Uuid, queued_at: DateTime<Utc>) {
+        let game_service = self.clone();
+        tokio::spawn(async move {
+            let widen_interval = std::time::Duration::from_secs(QuickPlayTicket::WIDEN_INTERVAL_SECONDS as u64);
+            loop {
+                tokio::time::sleep(widen_interval).await;
+                let queued = game_service.quick_play_queue
+                    .read()
+                    .await
+                    .get_ticket(&player_id)
+                    .is_some_and(|ticket| ticket.queued_at == queued_at);
+                if !queued {
+                    break;
+                }
+                game_service.match_quick_play().await;
+            }
+        });
+    }
+
+    /// Returns the lobby to idle once its match got played out, offering any free seats.<br />
+    /// Also rates the players and updates their stats based on the recorded hand.
+    fn spawn_match_cleanup(&self, lobby_arc: Arc<RwLock<Lobby>>, lobby: &Lobby) {
+        let Some(r#match) = lobby.r#match.clone() else {
+            return;
+        };
+        let game_service = self.clone();
+        tokio::spawn(async move {
+            r#match.wait_finished().await;
+            let unlocks = game_service.record_hand_results(&r#match.match_id).await;
+            let lobby_registry = game_service.lobby_registry.read().await.clone();
+            let mut lobby_w = lobby_arc.write().await;
+            for unlock in unlocks {
+                lobby_w.state_broadcaster.publish_event(GameEvent::achievement_unlocked(unlock.clone()));
+                game_service.notification_hub.publish(Notification::new(unlock.player_id, NotificationKind::AchievementUnlocked(unlock)));
+            }
+            if lobby_w.is_in_game_then_id() != Some(r#match.match_id) {
+                return;
+            }
+            lobby_w.finish_match().await;
+            game_service.notify_lobby_presence(&lobby_w).await;
+            Self::offer_free_seat(&lobby_arc, &mut lobby_w).await;
+            if let Err(err) = lobby_registry.store_lobby(&lobby_w).await {
+                eprintln!("Storing lobby ({}) after its match ({}) failed: {err}", lobby_w.lobby_id, r#match.match_id);
+            }
+        });
+    }
+
+    /// Hands that didn't get played out have no history to record.<br />
+    /// Notifies players of their settled balances, returning any achievements the hand unlocked.
+    async fn record_hand_results(&self, match_id: &Uuid) -> Vec<AchievementUnlock> {
+        let (unlocks, net_results) = {
+            let hand_history_registry_r = self.hand_history_registry.read().await;
+            let Some(history) = hand_history_registry_r.get_hand_history(match_id) else {
+                return Vec::new();
+            };
+            self.rating_registry.write().await.record_hand(history);
+            self.stats_registry.write().await.record_hand(history);
+            self.leaderboard_registry.write().await.record_hand(history);
+            (self.achievement_registry.write().await.record_hand(history), history.get_net_results())
+        };
+
+        let player_registry_r = self.player_registry.read().await;
+        for (player_id, delta) in net_results.into_iter().filter(|(_, delta)| *delta != 0) {
+            if let Ok(PlayerBalance { balance, .. }) = player_registry_r.get_player_balance(&player_id).await {
+                self.notification_hub.publish(Notification::new(player_id, NotificationKind::BalanceChanged { balance, delta }));
+            }
+        }
+        unlocks
+    }
+
+    /// Latest announcement first, followed by any invitations still awaiting a response.
+    async fn get_pending_notifications(&self, player_id: Uuid) -> Vec<Notification> {
+        let invitation_notifications = self.get_lobby_invitations_rpc(player_id)
+            .await
+            .into_iter()
+            .map(|invitation| Notification::new(player_id, NotificationKind::LobbyInvitation(invitation)));
+        self.notification_hub
+            .get_announcement()
+            .into_iter()
+            .chain(invitation_notifications)
+            .collect()
+    }
+
+    /// Only accepted friends get notified, regardless of whether they're online.
+    async fn notify_presence(&self, player_id: Uuid, presence: FriendPresence) {
+        let friend_ids = self.friend_registry.read().await.get_friend_ids(&player_id);
+        for friend_id in friend_ids {
+            self.notification_hub.publish(Notification::new(
+                friend_id,
+                NotificationKind::FriendPresence { friend_id: player_id, presence },
+            ));
+        }
+    }
+
+    async fn notify_lobby_presence(&self, lobby: &Lobby) {
+        let presence = Self::get_lobby_presence(lobby);
+        for player_id in lobby.get_player_ids() {
+            self.notify_presence(player_id, presence).await;
+        }
+    }
+
+    async fn get_presence(&self, player_id: &Uuid, online_player_ids: &HashSet<Uuid>) -> FriendPresence {
+        if !online_player_ids.contains(player_id) {
+            return FriendPresence::Offline;
+        }
+        match self.get_player_lobby(player_id).await {
+            Some(lobby) => Self::get_lobby_presence(&lobby),
+            None => FriendPresence::Online,
+        }
+    }
+
+    fn get_lobby_presence(lobby: &Lobby) -> FriendPresence {
+        let lobby_id = (!lobby.settings.is_private).then_some(lobby.lobby_id);
+        match lobby.is_in_game() {
+            true => FriendPresence::InGame(lobby_id),
+            false => FriendPresence::InLobby(lobby_id),
+        }
+    }
+
+    /// Resolves the lobby a player is either seated at or spectating.
+    async fn get_watched_lobby(&self, player_id: &Uuid) -> Result<(Lobby, bool), AppError> {
+        if let Some(lobby) = self.get_player_lobby(player_id).await {
+            return Ok((lobby, false));
+        }
+        match self.get_spectated_lobby(player_id).await {
+            Some(lobby) => Ok((lobby, true)),
+            None => Err(AppError::precondition_failed("Player not currently participating in a lobby!")),
+        }
+    }
+
+    fn get_watch_delay(lobby: &Lobby, is_spectator: bool) -> TimeDelta {
+        match is_spectator {
+            true => TimeDelta::seconds(lobby.settings.spectators.delay_seconds.into()),
+            false => TimeDelta::zero(),
+        }
+    }
+
     async fn get_player_lobby(&self, player_id: &Uuid) -> Option<Lobby> {
+        self.get_mapped_lobby(&self.player_lobby_map, player_id).await
+    }
+
+    async fn get_spectated_lobby(&self, player_id: &Uuid) -> Option<Lobby> {
+        self.get_mapped_lobby(&self.spectator_lobby_map, player_id).await
+    }
+
+    async fn get_mapped_lobby(&self, lobby_map: &RwLock<HashMap<Uuid, Uuid>>, player_id: &Uuid) -> Option<Lobby> {
         let lobby_id;
         {
-            let player_lobby_map_r = self.player_lobby_map.read().await;
-            lobby_id = match player_lobby_map_r.get(player_id) {
+            let lobby_map_r = lobby_map.read().await;
+            lobby_id = match lobby_map_r.get(player_id) {
                 Some(id) => *id,
                 None => return None,
             };
@@ -447,11 +1800,40 @@ impl GameService {
 
         let lobby_registry_r = self.lobby_registry.read().await;
         let Some(lobby_arc) = lobby_registry_r.get_lobby_arc(&lobby_id).await else {
-            let mut player_lobby_map_w = self.player_lobby_map.write().await;
-            player_lobby_map_w.remove(player_id);
+            let mut lobby_map_w = lobby_map.write().await;
+            lobby_map_w.remove(player_id);
             return None;
         };
         let lobby = lobby_arc.read().await.clone();
         Some(lobby)
     }
+
+    /// Lobbies are meant to only ever get removed through here, taking their chat along.<br />
+    /// Happens once the last player left, with nobody waitlisted either.
+    async fn rm_lobby(&self, lobby_id: &Uuid) -> Result<(), AppError> {
+        self.lobby_registry.write().await.rm_lobby(lobby_id).await?;
+        self.chat_registry.remove_lobby(lobby_id);
+        self.lobby_invitation_registry.write().await.rm_lobby_invitations(lobby_id);
+        Ok(())
+    }
+
+    /// Returns whether the player was actually spectating.
+    async fn stop_spectating(&self, player_id: &Uuid) -> bool {
+        let spectated_lobby_id = {
+            let mut spectator_lobby_map_w = self.spectator_lobby_map.write().await;
+            spectator_lobby_map_w.remove(player_id)
+        };
+        let Some(lobby_id) = spectated_lobby_id else {
+            return false;
+        };
+
+        let lobby_arc = {
+            let lobby_registry_r = self.lobby_registry.read().await;
+            lobby_registry_r.get_lobby_arc(&lobby_id).await
+        };
+        if let Some(lobby_arc) = lobby_arc {
+            lobby_arc.write().await.rm_spectator(player_id).await;
+        }
+        true
+    }
 }
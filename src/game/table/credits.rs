@@ -1,17 +1,19 @@
 use std::collections::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::common::error::AppError;
 use crate::service::proto::game_state::match_state as proto;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(crate) struct CreditPot {
-    pub(super) pot_id: Uuid,
+    pub(crate) pot_id: Uuid,
     // pub(super) match_id: Uuid,
-    pub(super) is_main_pot: bool,
+    pub(crate) is_main_pot: bool,
     // pub(super) contributor_ids: HashSet<Uuid>,
-    pub(super) total_credits: u64,
-    pub(super) player_credits: HashMap<Uuid, u64>,
+    pub(crate) total_credits: u64,
+    pub(crate) player_credits: HashMap<Uuid, u64>,
+    pub(crate) raked_credits: u64,
 }
 
 impl CreditPot {
@@ -23,6 +25,7 @@ impl CreditPot {
             // contributor_ids: HashSet::new(),
             total_credits: 0,
             player_credits: HashMap::new(),
+            raked_credits: 0,
         }
     }
 
@@ -38,12 +41,17 @@ impl CreditPot {
         player_credits.pot_credits.insert(self.pot_id, amount);
     }
 
+    pub fn take_rake(&mut self, amount: u64) {
+        self.total_credits -= amount;
+        self.raked_credits += amount;
+    }
+
     pub fn get_participants(&self) -> HashSet<Uuid> {
         self.player_credits.keys().cloned().collect()
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(crate) struct CalculatedPlayerCredits {
     // TODO: re-evaluate field visibility
     pub(crate) player_id: Uuid,
@@ -74,6 +82,10 @@ impl CalculatedPlayerCredits {
         credit_pot.add_credits(self, amount);
         Ok(())
     }
+
+    pub fn receive_credits(&mut self, amount: u64) {
+        self.remaining_credits += amount;
+    }
 }
 
 impl CalculatedPlayerCredits {
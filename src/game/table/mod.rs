@@ -3,22 +3,39 @@ mod credits;
 pub(crate) use credits::{CreditPot, CalculatedPlayerCredits};
 
 use std::collections::{HashMap, HashSet, VecDeque};
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::history::{HandHistory, HandHistoryAction};
+use crate::ledger::{LedgerTransaction, LedgerTransactionKind};
+use crate::lobby::RakeSettings;
 use crate::r#match::MatchStartPlayers;
+use crate::stats::PlayerHud;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct GameTable {
     match_id: Uuid,
     pub(super) player_queue_immut: VecDeque<Uuid>,
     pub player_ids: HashSet<Uuid>,
+    #[serde(default)]
+    player_seats: HashMap<Uuid, u8>,
+    #[serde(default)]
+    player_huds: HashMap<Uuid, PlayerHud>,
     dealer_id: Uuid,
     pub credit_pots: HashMap<Uuid, CreditPot>,
     pub player_credits: HashMap<Uuid, CalculatedPlayerCredits>,
+    pub(crate) rake_settings: RakeSettings,
+    pub(crate) transactions: Vec<LedgerTransaction>, // settled once the match is over
+    pub(crate) history: HandHistory,
 }
 
 impl GameTable {
-    pub fn new(match_id: Uuid, players: MatchStartPlayers) -> Self {
+    pub fn new(
+        match_id: Uuid,
+        players: MatchStartPlayers,
+        rake_settings: RakeSettings,
+        history: HandHistory,
+    ) -> Self {
         let player_ids = players.player_credits.keys().cloned().collect();
         let player_credits = players.player_credits
             .into_iter()
@@ -30,20 +47,77 @@ impl GameTable {
             match_id,
             player_queue_immut: players.ordered_player_queue,
             dealer_id: players.dealer_id,
+            player_seats: players.player_seats,
+            player_huds: players.player_huds,
             player_ids,
             credit_pots: HashMap::new(),
             player_credits,
+            rake_settings,
+            transactions: Vec::new(),
+            history,
         };
         let main_pot = CreditPot::new(true);
         table.add_pot(main_pot);
         table
     }
 
+    pub fn get_match_id(&self) -> Uuid {
+        self.match_id
+    }
+
+    pub fn get_player_hud(&self, player_id: &Uuid) -> Option<PlayerHud> {
+        self.player_huds.get(player_id).cloned()
+    }
+
     pub fn clone_player_queue(&self) -> VecDeque<Uuid> {
         self.player_queue_immut.clone()
     }
 
+    /// Players along with their seat numbers, ordered by seat.<br />
+    /// Tables predating seat selection fall back to their play order.
+    pub fn get_table_seats(&self) -> Vec<(u8, Uuid)> {
+        let mut seats: Vec<_> = self.player_queue_immut
+            .iter()
+            .enumerate()
+            .map(|(index, player_id)| {
+                let seat = self.player_seats.get(player_id).copied().unwrap_or(index as u8);
+                (seat, *player_id)
+            })
+            .collect();
+        seats.sort();
+        seats
+    }
+
+    pub fn get_dealer_id(&self) -> Uuid {
+        self.dealer_id
+    }
+
     pub fn add_pot(&mut self, pot: CreditPot) {
         self.credit_pots.insert(pot.pot_id.clone(), pot);
     }
+
+    pub fn record_transaction(&mut self, player_id: Uuid, kind: LedgerTransactionKind, amount: u64) {
+        if amount == 0 {
+            return;
+        }
+        let transaction = LedgerTransaction::new(player_id, Some(self.match_id), kind, amount);
+        self.transactions.push(transaction);
+    }
+
+    pub fn record_history(&mut self, action: HandHistoryAction) {
+        self.history.record(action);
+    }
+
+    /// Refunds every pot contribution, for matches that can't be played out.
+    pub fn refund_pot_contributions(&mut self) {
+        let mut contributions: HashMap<Uuid, u64> = HashMap::new();
+        for pot in self.credit_pots.values() {
+            for (player_id, credits) in &pot.player_credits {
+                *contributions.entry(*player_id).or_insert(0) += credits;
+            }
+        }
+        for (player_id, credits) in contributions {
+            self.record_transaction(player_id, LedgerTransactionKind::Refund, credits);
+        }
+    }
 }
@@ -0,0 +1,224 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use uuid::Uuid;
+
+use crate::game::BettingRoundAction;
+use crate::types::card::{Card, CardRank};
+use crate::types::hand::HandRank;
+use super::{HandHistory, HandHistoryAction};
+
+#[derive(PartialEq)]
+enum ExportStage {
+    Seating,
+    Dealing,
+    Drawing,
+    Showdown,
+}
+
+enum SeatOutcome {
+    Folded { after_draw: bool },
+    Showed { cards: Vec<Card>, won: u64 },
+    Collected(u64),
+}
+
+impl HandHistory {
+    /// Exports the hand history in a PokerStars-compatible text format.<br />
+    /// Cards get exported as recorded, so histories should be redacted beforehand.
+    pub fn export_pokerstars(&self) -> String {
+        let labels = self.get_player_labels();
+        let mut text = String::new();
+        let mut stage = ExportStage::Seating;
+        let mut holdings: HashMap<Uuid, Vec<Card>> = HashMap::new();
+        let mut outcomes: HashMap<Uuid, SeatOutcome> = HashMap::new();
+        let mut highest_bet = 0;
+        let mut total_pot = 0;
+        let mut total_rake = 0;
+
+        let _ = writeln!(
+            text,
+            "PokerStars Hand #{}: 5 Card Draw No Limit ({} ante) - {}",
+            self.match_id.as_u64_pair().0,
+            self.ante_amount,
+            self.started_at.format("%Y/%m/%d %H:%M:%S UTC"),
+        );
+        let button_seat = self.get_seat(&self.button_player_id).map_or(1, |seat| seat.seat_number);
+        let _ = writeln!(
+            text,
+            "Table '{}' {}-max (Play Money) Seat #{} is the button",
+            self.table_name,
+            self.max_seats,
+            button_seat,
+        );
+        for seat in &self.seats {
+            let _ = writeln!(
+                text,
+                "Seat {}: {} ({} in chips)",
+                seat.seat_number,
+                labels[&seat.player_id],
+                seat.starting_credits,
+            );
+        }
+
+        for event in &self.events {
+            match &event.action {
+                HandHistoryAction::Ante { player_id, amount } => {
+                    let _ = writeln!(text, "{}: posts the ante {}", labels[player_id], amount);
+                    highest_bet = highest_bet.max(*amount); // bets are compound, antes included
+                },
+                HandHistoryAction::CardsDealt { player_id, cards, .. } => {
+                    if stage == ExportStage::Seating {
+                        stage = ExportStage::Dealing;
+                        let _ = writeln!(text, "*** DEALING HANDS ***");
+                    }
+                    if let Some(cards) = cards {
+                        let _ = writeln!(text, "Dealt to {} [{}]", labels[player_id], format_cards(cards));
+                        holdings.insert(*player_id, cards.clone());
+                    }
+                },
+                HandHistoryAction::BettingAction { player_id, action, bet_amount, added_credits } => {
+                    let label = &labels[player_id];
+                    let _ = match action {
+                        BettingRoundAction::Fold => {
+                            let after_draw = stage == ExportStage::Drawing;
+                            outcomes.insert(*player_id, SeatOutcome::Folded { after_draw });
+                            writeln!(text, "{label}: folds")
+                        },
+                        BettingRoundAction::Call => writeln!(text, "{label}: calls {added_credits}"),
+                        BettingRoundAction::Bet(_) if highest_bet == 0 => {
+                            writeln!(text, "{label}: bets {added_credits}")
+                        },
+                        BettingRoundAction::Bet(_) | BettingRoundAction::Raise(_) => {
+                            let raised_by = bet_amount.saturating_sub(highest_bet);
+                            writeln!(text, "{label}: raises {raised_by} to {bet_amount}")
+                        },
+                    };
+                    highest_bet = highest_bet.max(*bet_amount);
+                },
+                HandHistoryAction::CardsDiscarded { player_id, card_count, cards } => {
+                    if stage != ExportStage::Drawing {
+                        stage = ExportStage::Drawing;
+                        let _ = writeln!(text, "*** FIRST DRAW ***");
+                    }
+                    let label = &labels[player_id];
+                    let _ = match cards {
+                        _ if *card_count == 0 => writeln!(text, "{label}: stands pat"),
+                        Some(cards) => {
+                            if let Some(holding) = holdings.get_mut(player_id) {
+                                holding.retain(|card| !cards.contains(card));
+                            }
+                            writeln!(text, "{label}: discards {card_count} cards [{}]", format_cards(cards))
+                        },
+                        None => writeln!(text, "{label}: discards {card_count} cards"),
+                    };
+                },
+                HandHistoryAction::CardsDrawn { player_id, cards, .. } => {
+                    if let Some(cards) = cards {
+                        let kept = holdings.get(player_id).cloned().unwrap_or_default();
+                        let _ = writeln!(
+                            text,
+                            "Dealt to {} [{}] [{}]",
+                            labels[player_id],
+                            format_cards(&kept),
+                            format_cards(cards),
+                        );
+                    }
+                },
+                HandHistoryAction::HandShown { player_id, cards, rank } => {
+                    if stage != ExportStage::Showdown {
+                        stage = ExportStage::Showdown;
+                        let _ = writeln!(text, "*** SHOW DOWN ***");
+                    }
+                    let _ = writeln!(
+                        text,
+                        "{}: shows [{}] ({})",
+                        labels[player_id],
+                        format_cards(cards),
+                        format_hand_rank(rank),
+                    );
+                    outcomes.insert(*player_id, SeatOutcome::Showed { cards: cards.clone(), won: 0 });
+                },
+                HandHistoryAction::PotAwarded { player_id, amount } => {
+                    let _ = writeln!(text, "{} collected {} from pot", labels[player_id], amount);
+                    total_pot += amount;
+                    match outcomes.get_mut(player_id) {
+                        Some(SeatOutcome::Showed { won, .. }) => *won += amount,
+                        _ => {
+                            outcomes.insert(*player_id, SeatOutcome::Collected(*amount));
+                        },
+                    }
+                },
+                HandHistoryAction::UncalledBetReturned { player_id, amount } => {
+                    let _ = writeln!(text, "Uncalled bet ({}) returned to {}", amount, labels[player_id]);
+                },
+                HandHistoryAction::Rake { amount } => {
+                    total_rake += amount;
+                },
+            }
+        }
+
+        let _ = writeln!(text, "*** SUMMARY ***");
+        let _ = writeln!(text, "Total pot {} | Rake {}", total_pot + total_rake, total_rake);
+        for seat in &self.seats {
+            let button = if seat.player_id == self.button_player_id { " (button)" } else { "" };
+            let outcome = match outcomes.get(&seat.player_id) {
+                Some(SeatOutcome::Folded { after_draw: false }) => String::from("folded before the Draw"),
+                Some(SeatOutcome::Folded { after_draw: true }) => String::from("folded after the Draw"),
+                Some(SeatOutcome::Showed { cards, won: 0 }) => format!("showed [{}] and lost", format_cards(cards)),
+                Some(SeatOutcome::Showed { cards, won }) => format!("showed [{}] and won ({won})", format_cards(cards)),
+                Some(SeatOutcome::Collected(amount)) => format!("collected ({amount})"),
+                None => String::from("mucked"),
+            };
+            let _ = writeln!(text, "Seat {}: {}{} {}", seat.seat_number, labels[&seat.player_id], button, outcome);
+        }
+        text
+    }
+
+    /// Player names aren't unique, so duplicates get disambiguated by their player id.
+    fn get_player_labels(&self) -> HashMap<Uuid, String> {
+        self.seats
+            .iter()
+            .map(|seat| {
+                let duplicates = self.seats
+                    .iter()
+                    .filter(|other| other.player_name == seat.player_name)
+                    .count();
+                let label = if duplicates > 1 {
+                    let player_id = seat.player_id.simple().to_string();
+                    format!("{}_{}", seat.player_name, &player_id[..8])
+                } else {
+                    seat.player_name.clone()
+                };
+                (seat.player_id, label)
+            })
+            .collect()
+    }
+}
+
+fn format_cards(cards: &[Card]) -> String {
+    cards
+        .iter()
+        .map(|card| {
+            let rank = match card.rank {
+                CardRank::Ten => String::from("T"),
+                ref rank => rank.to_string(),
+            };
+            format!("{}{}", rank, card.suit.to_string().to_lowercase())
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn format_hand_rank(rank: &HandRank) -> &'static str {
+    match rank {
+        HandRank::RoyalFlush => "a Royal Flush",
+        HandRank::StraightFlush => "a straight flush",
+        HandRank::FourOfAKind => "four of a kind",
+        HandRank::FullHouse => "a full house",
+        HandRank::Flush => "a flush",
+        HandRank::Straight => "a straight",
+        HandRank::ThreeOfAKind => "three of a kind",
+        HandRank::TwoPair => "two pair",
+        HandRank::Pair => "a pair",
+        HandRank::HighCard => "high card",
+    }
+}
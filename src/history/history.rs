@@ -0,0 +1,247 @@
+use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::common::error::AppError;
+use crate::game::BettingRoundAction;
+use crate::lobby::{GameMode, LobbySettings};
+use crate::output::{GameState, GameStateAsPlayer};
+use crate::r#match::MatchStartPlayers;
+use crate::types::card::Card;
+use crate::types::hand::HandRank;
+
+/// Complete record of a single hand, from seating to payouts.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct HandHistory {
+    pub(crate) match_id: Uuid,
+    pub(crate) lobby_id: Uuid,
+    pub(crate) table_name: String,
+    #[serde(default)]
+    pub(crate) game_mode: GameMode,
+    pub(crate) max_seats: u8,
+    pub(crate) ante_amount: u64,
+    pub(crate) button_player_id: Uuid,
+    pub(crate) seats: Vec<HandHistorySeat>, // table order
+    pub(crate) events: Vec<HandHistoryEvent>,
+    pub(crate) started_at: DateTime<Utc>,
+    pub(crate) ended_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub(crate) frames: Vec<GameState>, // unredacted, one per published state
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct HandHistorySeat {
+    pub(crate) seat_number: u8,
+    pub(crate) player_id: Uuid,
+    pub(crate) player_name: String,
+    pub(crate) starting_credits: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct HandHistoryEvent {
+    pub(crate) action: HandHistoryAction,
+    pub(crate) timestamp: DateTime<Utc>,
+}
+
+/// Card lists are only ever revealed to their owners, unless shown at showdown.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) enum HandHistoryAction {
+    Ante {
+        player_id: Uuid,
+        amount: u64,
+    },
+    CardsDealt {
+        player_id: Uuid,
+        card_count: usize,
+        cards: Option<Vec<Card>>,
+    },
+    BettingAction {
+        player_id: Uuid,
+        action: BettingRoundAction,
+        bet_amount: u64,
+        added_credits: u64,
+    },
+    CardsDiscarded {
+        player_id: Uuid,
+        card_count: usize,
+        cards: Option<Vec<Card>>,
+    },
+    CardsDrawn {
+        player_id: Uuid,
+        card_count: usize,
+        cards: Option<Vec<Card>>,
+    },
+    HandShown {
+        player_id: Uuid,
+        cards: Vec<Card>,
+        rank: HandRank,
+    },
+    PotAwarded {
+        player_id: Uuid,
+        amount: u64,
+    },
+    UncalledBetReturned {
+        player_id: Uuid,
+        amount: u64,
+    },
+    Rake {
+        amount: u64,
+    },
+}
+
+impl HandHistoryAction {
+    /// Hides card lists from anyone other than their owner.
+    pub fn redact(&mut self, viewer_id: &Uuid) {
+        match self {
+            HandHistoryAction::CardsDealt { player_id: owner_id, cards, .. }
+            | HandHistoryAction::CardsDiscarded { player_id: owner_id, cards, .. }
+            | HandHistoryAction::CardsDrawn { player_id: owner_id, cards, .. } if owner_id != viewer_id => {
+                *cards = None;
+            },
+            _ => {},
+        }
+    }
+}
+
+impl HandHistory {
+    pub fn new(
+        match_id: Uuid,
+        lobby_id: Uuid,
+        table_name: String,
+        settings: &LobbySettings,
+        players: &MatchStartPlayers,
+    ) -> Self {
+        let mut seats: Vec<_> = players.ordered_player_queue
+            .iter()
+            .map(|player_id| HandHistorySeat {
+                seat_number: players.player_seats[player_id] + 1,
+                player_id: *player_id,
+                player_name: players.player_names[player_id].clone(),
+                starting_credits: players.player_credits[player_id],
+            })
+            .collect();
+        seats.sort_by_key(|seat| seat.seat_number);
+        HandHistory {
+            match_id,
+            lobby_id,
+            table_name,
+            game_mode: settings.get_game_mode(),
+            max_seats: settings.max_players,
+            ante_amount: settings.ante_amount,
+            button_player_id: players.dealer_id,
+            seats,
+            events: Vec::new(),
+            started_at: Utc::now(),
+            ended_at: None,
+            frames: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, action: HandHistoryAction) {
+        self.events.push(HandHistoryEvent {
+            action,
+            timestamp: Utc::now(),
+        });
+    }
+
+    pub fn finish(&mut self) {
+        self.ended_at = Some(Utc::now());
+    }
+
+    /// Keeps an unredacted copy of a published state, for replaying the hand later on.
+    pub fn record_frame(&mut self, state: GameState) {
+        self.frames.push(state);
+    }
+
+    /// Replay frames are left out, see get_replay_frames().
+    pub fn as_player(&self, player_id: &Uuid) -> Self {
+        let mut history = HandHistory {
+            frames: Vec::new(),
+            ..self.clone()
+        };
+        for event in history.events.iter_mut() {
+            event.action.redact(player_id);
+        }
+        history
+    }
+
+    /// Returns the states a player got to see throughout the hand.
+    pub fn get_replay_frames(&self, player_id: Uuid) -> Result<Vec<GameStateAsPlayer>, AppError> {
+        self.frames
+            .iter()
+            .map(|state| state.as_player(player_id))
+            .collect()
+    }
+
+    /// Credits won or lost by each seated player over the hand, net of rake.
+    pub fn get_net_results(&self) -> HashMap<Uuid, i64> {
+        let mut net_results: HashMap<Uuid, i64> = self.seats
+            .iter()
+            .map(|seat| (seat.player_id, 0))
+            .collect();
+        for event in &self.events {
+            let (player_id, amount) = match &event.action {
+                HandHistoryAction::Ante { player_id, amount } => (player_id, -(*amount as i64)),
+                HandHistoryAction::BettingAction { player_id, added_credits, .. } => (player_id, -(*added_credits as i64)),
+                HandHistoryAction::PotAwarded { player_id, amount }
+                | HandHistoryAction::UncalledBetReturned { player_id, amount } => (player_id, *amount as i64),
+                _ => continue,
+            };
+            *net_results.entry(*player_id).or_default() += amount;
+        }
+        net_results
+    }
+
+    pub(crate) fn get_seat(&self, player_id: &Uuid) -> Option<&HandHistorySeat> {
+        self.seats.iter().find(|seat| seat.player_id == *player_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::card::{CardRank, CardSuit};
+    #[test]
+    fn as_player() {
+        let player_id = Uuid::new_v4();
+        let opponent_id = Uuid::new_v4();
+        let card = Card { rank: CardRank::Ace, suit: CardSuit::Spades };
+        let mut history = HandHistory {
+            match_id: Uuid::new_v4(),
+            lobby_id: Uuid::new_v4(),
+            table_name: String::from("Table"),
+            game_mode: GameMode::Single,
+            max_seats: 6,
+            ante_amount: 10,
+            button_player_id: player_id,
+            seats: Vec::new(),
+            events: Vec::new(),
+            started_at: Utc::now(),
+            ended_at: None,
+            frames: Vec::new(),
+        };
+        for owner_id in [player_id, opponent_id] {
+            history.record(HandHistoryAction::CardsDealt {
+                player_id: owner_id,
+                card_count: 1,
+                cards: Some(vec![card.clone()]),
+            });
+        }
+        history.record(HandHistoryAction::HandShown {
+            player_id: opponent_id,
+            cards: vec![card.clone()],
+            rank: HandRank::HighCard,
+        });
+        let redacted = history.as_player(&player_id);
+        let revealed: Vec<bool> = redacted.events
+            .iter()
+            .map(|event| match &event.action {
+                HandHistoryAction::CardsDealt { cards, .. } => cards.is_some(),
+                HandHistoryAction::HandShown { .. } => true,
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(revealed, vec![true, false, true]);
+    }
+}
@@ -0,0 +1,7 @@
+mod export;
+mod history;
+mod proto;
+mod registry;
+
+pub(crate) use history::{HandHistory, HandHistoryAction, HandHistoryEvent, HandHistorySeat};
+pub(crate) use registry::HandHistoryRegistry;
@@ -0,0 +1,107 @@
+use crate::common::time::chrono_to_prost_timestamp;
+use crate::game::BettingRoundAction;
+use crate::service::proto;
+use super::{HandHistory, HandHistoryAction, HandHistoryEvent, HandHistorySeat};
+
+use proto::hand_history::hand_history_event as proto_event;
+
+impl From<HandHistory> for proto::HandHistory {
+    fn from(history: HandHistory) -> Self {
+        proto::HandHistory {
+            match_id: history.match_id.to_string(),
+            lobby_id: history.lobby_id.to_string(),
+            table_name: history.table_name,
+            max_seats: history.max_seats.into(),
+            ante_amount: history.ante_amount,
+            button_player_id: history.button_player_id.to_string(),
+            seats: history.seats.into_iter().map(|seat| seat.into()).collect(),
+            events: history.events.into_iter().map(|event| event.into()).collect(),
+            started_at: Some(chrono_to_prost_timestamp(history.started_at)),
+            ended_at: history.ended_at.map(chrono_to_prost_timestamp),
+        }
+    }
+}
+
+impl From<HandHistorySeat> for proto::hand_history::HandHistorySeat {
+    fn from(seat: HandHistorySeat) -> Self {
+        proto::hand_history::HandHistorySeat {
+            seat_number: seat.seat_number.into(),
+            player_id: seat.player_id.to_string(),
+            player_name: seat.player_name,
+            starting_credits: seat.starting_credits,
+        }
+    }
+}
+
+impl From<HandHistoryEvent> for proto::hand_history::HandHistoryEvent {
+    fn from(event: HandHistoryEvent) -> Self {
+        let event_proto = match event.action {
+            HandHistoryAction::Ante { player_id, amount } => {
+                proto_event::Event::Ante(proto_event::Ante {
+                    player_id: player_id.to_string(),
+                    amount,
+                })
+            },
+            HandHistoryAction::CardsDealt { player_id, card_count, cards } => {
+                proto_event::Event::CardsDealt(proto_event::CardsDealt {
+                    player_id: player_id.to_string(),
+                    card_count: card_count as u32,
+                    cards: cards.unwrap_or_default().into_iter().map(|card| card.into()).collect(),
+                })
+            },
+            HandHistoryAction::BettingAction { player_id, action, bet_amount, added_credits } => {
+                let kind = match action {
+                    BettingRoundAction::Bet(_) => proto_event::betting_action::BettingActionKind::Bet,
+                    BettingRoundAction::Call => proto_event::betting_action::BettingActionKind::Call,
+                    BettingRoundAction::Raise(_) => proto_event::betting_action::BettingActionKind::Raise,
+                    BettingRoundAction::Fold => proto_event::betting_action::BettingActionKind::Fold,
+                };
+                proto_event::Event::BettingAction(proto_event::BettingAction {
+                    player_id: player_id.to_string(),
+                    kind: kind as i32,
+                    bet_amount,
+                    added_credits,
+                })
+            },
+            HandHistoryAction::CardsDiscarded { player_id, card_count, cards } => {
+                proto_event::Event::CardsDiscarded(proto_event::CardsDiscarded {
+                    player_id: player_id.to_string(),
+                    card_count: card_count as u32,
+                    cards: cards.unwrap_or_default().into_iter().map(|card| card.into()).collect(),
+                })
+            },
+            HandHistoryAction::CardsDrawn { player_id, card_count, cards } => {
+                proto_event::Event::CardsDrawn(proto_event::CardsDealt {
+                    player_id: player_id.to_string(),
+                    card_count: card_count as u32,
+                    cards: cards.unwrap_or_default().into_iter().map(|card| card.into()).collect(),
+                })
+            },
+            HandHistoryAction::HandShown { player_id, cards, rank } => {
+                let rank: proto::game_state::poker_phase::poker_phase_showdown::showdown_results::PokerHandRank = rank.into();
+                proto_event::Event::HandShown(proto_event::HandShown {
+                    player_id: player_id.to_string(),
+                    cards: cards.into_iter().map(|card| card.into()).collect(),
+                    rank: rank as i32,
+                })
+            },
+            HandHistoryAction::PotAwarded { player_id, amount } => {
+                proto_event::Event::PotAwarded(proto_event::PotAwarded {
+                    player_id: player_id.to_string(),
+                    amount,
+                })
+            },
+            HandHistoryAction::UncalledBetReturned { player_id, amount } => {
+                proto_event::Event::UncalledBetReturned(proto_event::PotAwarded {
+                    player_id: player_id.to_string(),
+                    amount,
+                })
+            },
+            HandHistoryAction::Rake { amount } => proto_event::Event::Rake(amount),
+        };
+        proto::hand_history::HandHistoryEvent {
+            timestamp: Some(chrono_to_prost_timestamp(event.timestamp)),
+            event: Some(event_proto),
+        }
+    }
+}
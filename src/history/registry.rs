@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::common::error::AppError;
+use crate::output::GameStateAsPlayer;
+use crate::storage::{self, Storage};
+use super::HandHistory;
+
+#[derive(Debug)]
+pub(crate) struct HandHistoryRegistry {
+    hand_histories: HashMap<Uuid, HandHistory>,
+    player_match_ids: HashMap<Uuid, Vec<Uuid>>, // chronological
+    storage: Arc<dyn Storage>,
+}
+
+impl HandHistoryRegistry {
+    pub fn restore(storage: Arc<dyn Storage>, mut hand_histories: Vec<HandHistory>) -> Self {
+        hand_histories.sort_by_key(|history| history.started_at);
+        let mut registry = HandHistoryRegistry {
+            hand_histories: HashMap::with_capacity(hand_histories.len()),
+            player_match_ids: HashMap::new(),
+            storage,
+        };
+        for history in hand_histories {
+            registry.insert(history);
+        }
+        registry
+    }
+
+    pub async fn add_hand_history(&mut self, history: HandHistory) -> Result<(), AppError> {
+        let stored_history = history.clone();
+        storage::run_blocking(&self.storage, move |storage| storage.store_hand_history(&stored_history)).await?;
+        self.insert(history);
+        Ok(())
+    }
+
+    pub fn get_hand_history(&self, match_id: &Uuid) -> Option<&HandHistory> {
+        self.hand_histories.get(match_id)
+    }
+
+    /// Returns a player's past hands as seen by them, most recent first.
+    pub fn get_player_hand_histories(&self, player_id: &Uuid, limit: Option<usize>) -> Vec<HandHistory> {
+        let Some(match_ids) = self.player_match_ids.get(player_id) else {
+            return Vec::new();
+        };
+        match_ids
+            .iter()
+            .rev()
+            .take(limit.unwrap_or(usize::MAX))
+            .map(|match_id| self.hand_histories[match_id].as_player(player_id))
+            .collect()
+    }
+
+    /// Only players seated for the hand get to replay it.
+    pub fn get_player_hand_replay(
+        &self,
+        player_id: Uuid,
+        match_id: &Uuid,
+    ) -> Result<Vec<GameStateAsPlayer>, AppError> {
+        let history = self.hand_histories
+            .get(match_id)
+            .filter(|history| history.get_seat(&player_id).is_some())
+            .ok_or(AppError::not_found(*match_id))?;
+        history.get_replay_frames(player_id)
+    }
+
+    fn insert(&mut self, history: HandHistory) {
+        for seat in &history.seats {
+            self.player_match_ids
+                .entry(seat.player_id)
+                .or_default()
+                .push(history.match_id);
+        }
+        self.hand_histories.insert(history.match_id, history);
+    }
+}
@@ -0,0 +1,128 @@
+use chrono::{DateTime, Datelike, TimeDelta, Utc};
+use uuid::Uuid;
+
+use crate::common::error::AppError;
+use crate::lobby::GameMode;
+
+/// Tournament points to come along with tournaments.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum LeaderboardMetric {
+    #[default]
+    NetCredits,
+    Rating,
+}
+
+/// Calendar days and ISO weeks, in UTC.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum LeaderboardPeriod {
+    #[default]
+    AllTime,
+    Daily,
+    Weekly,
+}
+
+impl LeaderboardPeriod {
+    pub fn get_start(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let today = now.date_naive();
+        let start_date = match self {
+            LeaderboardPeriod::AllTime => return None,
+            LeaderboardPeriod::Daily => today,
+            LeaderboardPeriod::Weekly => today - TimeDelta::days(today.weekday().num_days_from_monday().into()),
+        };
+        Some(start_date.and_hms_opt(0, 0, 0)?.and_utc())
+    }
+}
+
+/// Tied players share a rank, e.g. 1, 2, 2, 4.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct LeaderboardEntry {
+    pub rank: u32,
+    pub player_id: Uuid,
+    pub player_name: String,
+    pub score: f64, // net credits or rating
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct LeaderboardQuery {
+    pub metric: LeaderboardMetric,
+    pub period: LeaderboardPeriod,
+    pub game_mode: GameMode,
+    pub offset: usize, // decoded from the cursor
+    pub page_size: usize,
+}
+
+#[derive(Debug)]
+pub(crate) struct LeaderboardPage {
+    pub entries: Vec<LeaderboardEntry>,
+    /// Rank offset of the next page, which may shift as new results come in.
+    pub next_cursor: Option<usize>,
+    pub self_entry: Option<LeaderboardEntry>,
+}
+
+impl LeaderboardQuery {
+    pub const DEFAULT_PAGE_SIZE: usize = 20;
+    pub const MAX_PAGE_SIZE: usize = 100;
+
+    pub fn new(
+        metric: LeaderboardMetric,
+        period: LeaderboardPeriod,
+        game_mode: GameMode,
+        offset: Option<usize>,
+        page_size: Option<usize>,
+    ) -> Result<Self, AppError> {
+        let offset = offset.unwrap_or_default();
+        let page_size = page_size.unwrap_or(Self::DEFAULT_PAGE_SIZE);
+        if page_size == 0 || page_size > Self::MAX_PAGE_SIZE {
+            return Err(
+                AppError::invalid_request(
+                    format!("Page size must range from 1 to {}!", Self::MAX_PAGE_SIZE)
+                )
+            );
+        }
+        Ok(LeaderboardQuery { metric, period, game_mode, offset, page_size })
+    }
+
+    /// Ranks the scores, highest first, looking up the viewer's own entry along the way.
+    pub fn paginate(&self, scores: Vec<(Uuid, String, f64)>, viewer_id: &Uuid) -> LeaderboardPage {
+        let mut scores = scores;
+        scores.sort_by(|(a_id, _, a_score), (b_id, _, b_score)| b_score.total_cmp(a_score).then_with(|| a_id.cmp(b_id)));
+
+        let mut entries: Vec<LeaderboardEntry> = Vec::with_capacity(scores.len());
+        for (index, (player_id, player_name, score)) in scores.into_iter().enumerate() {
+            let rank = match entries.last() {
+                Some(previous) if previous.score == score => previous.rank,
+                _ => index as u32 + 1,
+            };
+            entries.push(LeaderboardEntry { rank, player_id, player_name, score });
+        }
+
+        let self_entry = entries.iter().find(|entry| entry.player_id == *viewer_id).cloned();
+        let has_more = entries.len() > self.offset + self.page_size;
+        let entries: Vec<_> = entries.into_iter().skip(self.offset).take(self.page_size).collect();
+        LeaderboardPage {
+            entries,
+            next_cursor: has_more.then_some(self.offset + self.page_size),
+            self_entry,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn paginate() {
+        let query = LeaderboardQuery::new(LeaderboardMetric::NetCredits, LeaderboardPeriod::AllTime, GameMode::Single, Some(1), Some(2)).unwrap();
+        let player_ids: Vec<Uuid> = (0..4).map(|_| Uuid::new_v4()).collect();
+        let scores = player_ids
+            .iter()
+            .zip([10.0, 30.0, 10.0, -5.0])
+            .map(|(player_id, score)| (*player_id, String::from("Anonymous"), score))
+            .collect();
+        let page = query.paginate(scores, &player_ids[3]);
+        assert_eq!(page.entries.iter().map(|entry| entry.rank).collect::<Vec<_>>(), vec![2, 2]);
+        assert_eq!(page.next_cursor, Some(3));
+        assert_eq!(page.self_entry.map(|entry| entry.rank), Some(4));
+        assert!(LeaderboardQuery::new(LeaderboardMetric::Rating, LeaderboardPeriod::Daily, GameMode::Single, None, Some(0)).is_err());
+    }
+}
@@ -0,0 +1,6 @@
+mod leaderboard;
+mod proto;
+mod registry;
+
+pub(crate) use leaderboard::{LeaderboardEntry, LeaderboardMetric, LeaderboardPage, LeaderboardPeriod, LeaderboardQuery};
+pub(crate) use registry::LeaderboardRegistry;
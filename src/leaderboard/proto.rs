@@ -0,0 +1,68 @@
+use crate::common::error::AppError;
+use crate::lobby::GameMode;
+use crate::service::proto;
+use super::{LeaderboardEntry, LeaderboardMetric, LeaderboardPage, LeaderboardPeriod, LeaderboardQuery};
+
+impl TryFrom<proto::GetLeaderboardRequest> for LeaderboardQuery {
+    type Error = AppError;
+
+    fn try_from(request: proto::GetLeaderboardRequest) -> Result<Self, Self::Error> {
+        let metric = proto::get_leaderboard_request::LeaderboardMetric::try_from(request.metric)
+            .map_err(|_| AppError::invalid_request("Invalid GetLeaderboardRequest.metric value!"))?
+            .into();
+        let period = proto::get_leaderboard_request::LeaderboardPeriod::try_from(request.period)
+            .map_err(|_| AppError::invalid_request("Invalid GetLeaderboardRequest.period value!"))?
+            .into();
+        let game_mode = proto::lobby_settings::GameMode::try_from(request.game_mode)
+            .map(GameMode::from)
+            .map_err(|_| AppError::invalid_request("Invalid GetLeaderboardRequest.game_mode value!"))?;
+        let offset = request.cursor
+            .map(|cursor| cursor.parse::<usize>())
+            .transpose()
+            .map_err(|_| AppError::invalid_request("Invalid GetLeaderboardRequest.cursor value!"))?;
+        let page_size = request.page_size.map(|page_size| page_size as usize);
+        LeaderboardQuery::new(metric, period, game_mode, offset, page_size)
+    }
+}
+
+impl From<proto::get_leaderboard_request::LeaderboardMetric> for LeaderboardMetric {
+    fn from(metric: proto::get_leaderboard_request::LeaderboardMetric) -> Self {
+        match metric {
+            proto::get_leaderboard_request::LeaderboardMetric::NetCredits => LeaderboardMetric::NetCredits,
+            proto::get_leaderboard_request::LeaderboardMetric::Rating => LeaderboardMetric::Rating,
+        }
+    }
+}
+
+impl From<proto::get_leaderboard_request::LeaderboardPeriod> for LeaderboardPeriod {
+    fn from(period: proto::get_leaderboard_request::LeaderboardPeriod) -> Self {
+        match period {
+            proto::get_leaderboard_request::LeaderboardPeriod::AllTime => LeaderboardPeriod::AllTime,
+            proto::get_leaderboard_request::LeaderboardPeriod::Daily => LeaderboardPeriod::Daily,
+            proto::get_leaderboard_request::LeaderboardPeriod::Weekly => LeaderboardPeriod::Weekly,
+        }
+    }
+}
+
+impl From<LeaderboardEntry> for proto::LeaderboardEntry {
+    fn from(entry: LeaderboardEntry) -> Self {
+        proto::LeaderboardEntry {
+            rank: entry.rank,
+            player: Some(proto::PlayerPublicInfo {
+                player_id: entry.player_id.to_string(),
+                player_name: entry.player_name,
+            }),
+            score: entry.score,
+        }
+    }
+}
+
+impl From<LeaderboardPage> for proto::GetLeaderboardResponse {
+    fn from(page: LeaderboardPage) -> Self {
+        proto::GetLeaderboardResponse {
+            entries: page.entries.into_iter().map(|entry| entry.into()).collect(),
+            next_cursor: page.next_cursor.map(|cursor| cursor.to_string()),
+            self_entry: page.self_entry.map(|entry| entry.into()),
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::history::HandHistory;
+use crate::lobby::GameMode;
+use crate::rating::RatingRegistry;
+use super::{LeaderboardMetric, LeaderboardPeriod};
+
+/// Match results backing the leaderboards, derived from the recorded hand histories.
+#[derive(Debug, Default)]
+pub(crate) struct LeaderboardRegistry {
+    match_results: Vec<MatchResult>,
+    player_names: HashMap<Uuid, String>, // as last seated
+}
+
+#[derive(Debug)]
+struct MatchResult {
+    game_mode: GameMode,
+    ended_at: DateTime<Utc>,
+    net_results: HashMap<Uuid, i64>,
+}
+
+impl LeaderboardRegistry {
+    pub fn restore(hand_histories: &[HandHistory]) -> Self {
+        let mut hand_histories: Vec<&HandHistory> = hand_histories.iter().collect();
+        hand_histories.sort_by_key(|history| history.started_at);
+        let mut registry = LeaderboardRegistry::default();
+        for history in hand_histories {
+            registry.record_hand(history);
+        }
+        registry
+    }
+
+    pub fn record_hand(&mut self, history: &HandHistory) {
+        for seat in &history.seats {
+            self.player_names.insert(seat.player_id, seat.player_name.clone());
+        }
+        self.match_results.push(MatchResult {
+            game_mode: history.game_mode,
+            ended_at: history.ended_at.unwrap_or(history.started_at),
+            net_results: history.get_net_results(),
+        });
+    }
+
+    /// Scores every player with results in the period.<br />
+    /// Ratings can't be split up by period, so those rank the period's players by their current rating.
+    pub fn get_scores(
+        &self,
+        metric: LeaderboardMetric,
+        period: LeaderboardPeriod,
+        game_mode: GameMode,
+        rating_registry: &RatingRegistry,
+        now: DateTime<Utc>,
+    ) -> Vec<(Uuid, String, f64)> {
+        let period_start = period.get_start(now);
+        let mut net_credits: HashMap<Uuid, i64> = HashMap::new();
+        for result in &self.match_results {
+            if result.game_mode != game_mode || period_start.is_some_and(|start| result.ended_at < start) {
+                continue;
+            }
+            for (player_id, net_result) in &result.net_results {
+                *net_credits.entry(*player_id).or_default() += net_result;
+            }
+        }
+
+        net_credits
+            .into_iter()
+            .map(|(player_id, net_result)| {
+                let score = match metric {
+                    LeaderboardMetric::NetCredits => net_result as f64,
+                    LeaderboardMetric::Rating => rating_registry.get_rating(&player_id, game_mode).rating,
+                };
+                let player_name = self.player_names.get(&player_id).cloned().unwrap_or_default();
+                (player_id, player_name, score)
+            })
+            .collect()
+    }
+}
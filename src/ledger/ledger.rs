@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::common::error::AppError;
+use super::LedgerTransaction;
+
+/// Append-only record of every credit movement.<br />
+/// Player balances are always derived from their transaction history.<br />
+/// House earnings (rake) are tracked under a dedicated house account.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct CreditLedger {
+    player_transactions: HashMap<Uuid, Vec<LedgerTransaction>>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct PlayerBalance {
+    pub(crate) balance: u64,
+    pub(crate) transactions: Vec<LedgerTransaction>,
+}
+
+impl CreditLedger {
+    pub(crate) const HOUSE_ACCOUNT_ID: Uuid = Uuid::nil();
+
+    pub fn record(&mut self, transaction: LedgerTransaction) {
+        self.player_transactions
+            .entry(transaction.player_id)
+            .or_default()
+            .push(transaction);
+    }
+
+    pub fn get_balance(&self, player_id: &Uuid) -> u64 {
+        let delta: i128 = self.player_transactions
+            .get(player_id)
+            .map_or(0, |transactions| {
+                transactions
+                    .iter()
+                    .map(|transaction| transaction.get_balance_delta())
+                    .sum()
+            });
+        // Settlement validation guarantees non-negative balances.
+        delta.try_into().unwrap_or(0)
+    }
+
+    pub fn get_player_balance(&self, player_id: &Uuid) -> PlayerBalance {
+        let transactions = self.player_transactions
+            .get(player_id)
+            .cloned()
+            .unwrap_or_default();
+        PlayerBalance {
+            balance: self.get_balance(player_id),
+            transactions,
+        }
+    }
+
+    /// Calculates the resulting player balances for a batch of transactions without recording them.
+    pub fn preview_balances(&self, transactions: &[LedgerTransaction]) -> Result<HashMap<Uuid, u64>, AppError> {
+        let mut deltas: HashMap<Uuid, i128> = HashMap::new();
+        for transaction in transactions {
+            *deltas.entry(transaction.player_id).or_insert(0) += transaction.get_balance_delta();
+        }
+        deltas
+            .into_iter()
+            .map(|(player_id, delta)| {
+                let balance = self.get_balance(&player_id) as i128 + delta;
+                let balance = u64::try_from(balance).map_err(|_| {
+                    AppError::precondition_failed(format!("Player ({player_id}) can't afford settlement!"))
+                })?;
+                Ok((player_id, balance))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ledger::LedgerTransactionKind;
+    #[test]
+    fn derived_balance() {
+        let player_id = Uuid::new_v4();
+        let match_id = Some(Uuid::new_v4());
+        let mut ledger = CreditLedger::default();
+        ledger.record(LedgerTransaction::new(player_id, None, LedgerTransactionKind::BuyIn, 500));
+        ledger.record(LedgerTransaction::new(player_id, match_id, LedgerTransactionKind::Ante, 10));
+        ledger.record(LedgerTransaction::new(player_id, match_id, LedgerTransactionKind::Bet, 40));
+        ledger.record(LedgerTransaction::new(player_id, match_id, LedgerTransactionKind::PotWin, 100));
+        ledger.record(LedgerTransaction::new(player_id, match_id, LedgerTransactionKind::Refund, 5));
+        assert_eq!(ledger.get_balance(&player_id), 555);
+        assert_eq!(ledger.get_player_balance(&player_id).transactions.len(), 5);
+    }
+    #[test]
+    fn preview_balances_underflow() {
+        let player_id = Uuid::new_v4();
+        let mut ledger = CreditLedger::default();
+        ledger.record(LedgerTransaction::new(player_id, None, LedgerTransactionKind::BuyIn, 10));
+        let transactions = [LedgerTransaction::new(player_id, None, LedgerTransactionKind::Bet, 20)];
+        assert!(ledger.preview_balances(&transactions).is_err());
+        let transactions = [LedgerTransaction::new(player_id, None, LedgerTransactionKind::Bet, 10)];
+        assert_eq!(ledger.preview_balances(&transactions).unwrap().get(&player_id), Some(&0));
+    }
+}
@@ -0,0 +1,6 @@
+mod ledger;
+mod proto;
+mod transaction;
+
+pub(crate) use ledger::{CreditLedger, PlayerBalance};
+pub(crate) use transaction::{LedgerTransaction, LedgerTransactionKind};
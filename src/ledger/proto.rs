@@ -0,0 +1,42 @@
+use crate::common::time::chrono_to_prost_timestamp;
+use crate::service::proto;
+use super::{LedgerTransaction, LedgerTransactionKind, PlayerBalance};
+
+impl From<LedgerTransactionKind> for proto::ledger_transaction::TransactionKind {
+    fn from(kind: LedgerTransactionKind) -> Self {
+        match kind {
+            LedgerTransactionKind::BuyIn => Self::BuyIn,
+            LedgerTransactionKind::Ante => Self::Ante,
+            LedgerTransactionKind::Bet => Self::Bet,
+            LedgerTransactionKind::PotWin => Self::PotWin,
+            LedgerTransactionKind::Refund => Self::Refund,
+            LedgerTransactionKind::Rake => Self::Rake,
+        }
+    }
+}
+
+impl From<LedgerTransaction> for proto::LedgerTransaction {
+    fn from(transaction: LedgerTransaction) -> Self {
+        let kind: proto::ledger_transaction::TransactionKind = transaction.kind.into();
+        proto::LedgerTransaction {
+            transaction_id: transaction.transaction_id.to_string(),
+            match_id: transaction.match_id.map(|match_id| match_id.to_string()),
+            kind: kind as i32,
+            amount: transaction.amount,
+            timestamp: Some(chrono_to_prost_timestamp(transaction.timestamp)),
+        }
+    }
+}
+
+impl From<PlayerBalance> for proto::GetBalanceResponse {
+    fn from(balance: PlayerBalance) -> Self {
+        let transactions = balance.transactions
+            .into_iter()
+            .map(|transaction| transaction.into())
+            .collect();
+        proto::GetBalanceResponse {
+            balance: balance.balance,
+            transactions,
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub(crate) enum LedgerTransactionKind {
+    /// Credits granted to a player upon joining the game economy.
+    BuyIn,
+    /// Forced bet placed at the start of a match.
+    Ante,
+    /// Credits committed to a pot during a betting round.
+    Bet,
+    /// Credits awarded from a pot at showdown.
+    PotWin,
+    /// Credits returned to a player, eg: uncalled bets.
+    Refund,
+    /// House's cut of a pot.
+    Rake,
+}
+
+impl LedgerTransactionKind {
+    /// Checks whether the transaction adds credits to a player's balance.
+    pub fn is_credit(&self) -> bool {
+        matches!(
+            self,
+            LedgerTransactionKind::BuyIn
+                | LedgerTransactionKind::PotWin
+                | LedgerTransactionKind::Refund
+                | LedgerTransactionKind::Rake
+        )
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct LedgerTransaction {
+    pub(crate) transaction_id: Uuid,
+    pub(crate) player_id: Uuid,
+    pub(crate) match_id: Option<Uuid>,
+    pub(crate) kind: LedgerTransactionKind,
+    pub(crate) amount: u64,
+    pub(crate) timestamp: DateTime<Utc>,
+}
+
+impl LedgerTransaction {
+    pub fn new(
+        player_id: Uuid,
+        match_id: Option<Uuid>,
+        kind: LedgerTransactionKind,
+        amount: u64,
+    ) -> Self {
+        LedgerTransaction {
+            transaction_id: Uuid::new_v4(),
+            player_id,
+            match_id,
+            kind,
+            amount,
+            timestamp: Utc::now(),
+        }
+    }
+
+    /// Returns the signed effect of the transaction on a player's balance.
+    pub fn get_balance_delta(&self) -> i128 {
+        if self.kind.is_credit() {
+            self.amount as i128
+        } else {
+            -(self.amount as i128)
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+use chrono::{DateTime, TimeDelta, Utc};
+use uuid::Uuid;
+
+use crate::common::error::AppError;
+use crate::common::time::chrono_to_prost_timestamp;
+use crate::service::proto;
+
+/// Invitation of a specific player by a lobby's host.<br />
+/// Unlike invite codes, accepting one skips the lobby's password.
+#[derive(Clone, Debug)]
+pub struct LobbyInvitation {
+    pub invitation_id: Uuid,
+    pub lobby_id: Uuid,
+    pub lobby_name: String,
+    pub host_player_id: Uuid,
+    pub invitee_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl LobbyInvitation {
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= Utc::now()
+    }
+}
+
+/// Invitations are transient and don't survive restarts.
+#[derive(Debug, Default)]
+pub struct LobbyInvitationRegistry {
+    invitations: HashMap<Uuid, LobbyInvitation>,
+}
+
+impl LobbyInvitationRegistry {
+    const DEFAULT_TTL_SECONDS: u32 = 5 * 60;
+    const MAX_TTL_SECONDS: u32 = 60 * 60;
+
+    /// Re-inviting a player replaces their previous invitation to the lobby.
+    pub fn create_invitation(
+        &mut self,
+        lobby_id: Uuid,
+        lobby_name: String,
+        host_player_id: Uuid,
+        invitee_id: Uuid,
+        ttl_seconds: Option<u32>,
+    ) -> Result<LobbyInvitation, AppError> {
+        let ttl_seconds = ttl_seconds.unwrap_or(Self::DEFAULT_TTL_SECONDS);
+        if ttl_seconds == 0 || ttl_seconds > Self::MAX_TTL_SECONDS {
+            return Err(
+                AppError::invalid_request(
+                    format!("Invitation lifetime must range from 1 to {} seconds!", Self::MAX_TTL_SECONDS)
+                )
+            );
+        }
+        if host_player_id == invitee_id {
+            return Err(AppError::invalid_request("Players can't invite themselves!"));
+        }
+        self.invitations.retain(|_, invitation| {
+            !invitation.is_expired() && (invitation.lobby_id, invitation.invitee_id) != (lobby_id, invitee_id)
+        });
+
+        let created_at = Utc::now();
+        let invitation = LobbyInvitation {
+            invitation_id: Uuid::new_v4(),
+            lobby_id,
+            lobby_name,
+            host_player_id,
+            invitee_id,
+            created_at,
+            expires_at: created_at + TimeDelta::seconds(ttl_seconds.into()),
+        };
+        self.invitations.insert(invitation.invitation_id, invitation.clone());
+        Ok(invitation)
+    }
+
+    pub fn get_invitation(&self, invitation_id: &Uuid, invitee_id: &Uuid) -> Result<LobbyInvitation, AppError> {
+        match self.invitations.get(invitation_id) {
+            Some(invitation) if invitation.invitee_id == *invitee_id && !invitation.is_expired() => Ok(invitation.clone()),
+            _ => Err(AppError::precondition_failed("Invitation invalid or expired!")),
+        }
+    }
+
+    pub fn rm_invitation(&mut self, invitation_id: &Uuid) {
+        self.invitations.remove(invitation_id);
+    }
+
+    pub fn rm_lobby_invitations(&mut self, lobby_id: &Uuid) {
+        self.invitations.retain(|_, invitation| invitation.lobby_id != *lobby_id);
+    }
+
+    /// Pending invitations of a player, oldest first.
+    pub fn get_invitations(&self, invitee_id: &Uuid) -> Vec<LobbyInvitation> {
+        let mut invitations: Vec<_> = self.invitations
+            .values()
+            .filter(|invitation| invitation.invitee_id == *invitee_id && !invitation.is_expired())
+            .cloned()
+            .collect();
+        invitations.sort_by_key(|invitation| invitation.created_at);
+        invitations
+    }
+}
+
+impl From<LobbyInvitation> for proto::LobbyInvitation {
+    fn from(invitation: LobbyInvitation) -> Self {
+        proto::LobbyInvitation {
+            invitation_id: invitation.invitation_id.to_string(),
+            lobby_id: invitation.lobby_id.to_string(),
+            lobby_name: invitation.lobby_name,
+            host_player_id: invitation.host_player_id.to_string(),
+            expires_at: Some(chrono_to_prost_timestamp(invitation.expires_at)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn create_invitation() {
+        let mut registry = LobbyInvitationRegistry::default();
+        let (lobby_id, host_id, invitee_id) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        assert!(registry.create_invitation(lobby_id, String::from("Lobby"), host_id, host_id, None).is_err());
+        assert!(registry.create_invitation(lobby_id, String::from("Lobby"), host_id, invitee_id, Some(0)).is_err());
+        let first = registry.create_invitation(lobby_id, String::from("Lobby"), host_id, invitee_id, None).unwrap();
+        let second = registry.create_invitation(lobby_id, String::from("Lobby"), host_id, invitee_id, None).unwrap();
+        assert!(registry.get_invitation(&first.invitation_id, &invitee_id).is_err());
+        assert!(registry.get_invitation(&second.invitation_id, &host_id).is_err());
+        assert_eq!(registry.get_invitations(&invitee_id).len(), 1);
+        registry.rm_invitation(&second.invitation_id);
+        assert!(registry.get_invitations(&invitee_id).is_empty());
+
+        let third = registry.create_invitation(lobby_id, String::from("Lobby"), host_id, invitee_id, None).unwrap();
+        registry.rm_lobby_invitations(&lobby_id);
+        assert!(registry.get_invitation(&third.invitation_id, &invitee_id).is_err());
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+use chrono::{DateTime, TimeDelta, Utc};
+use rand::{rng, Rng};
+use uuid::Uuid;
+
+use crate::common::error::AppError;
+use crate::common::time::chrono_to_prost_timestamp;
+use crate::service::proto;
+
+/// Short shareable code resolving to a lobby id.<br />
+/// Codes only save players from typing out UUIDs, lobby passwords still apply.
+#[derive(Clone, Debug)]
+pub struct LobbyInvite {
+    pub code: String,
+    pub lobby_id: Uuid,
+    pub created_by: Uuid,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl LobbyInvite {
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= Utc::now()
+    }
+}
+
+/// Invite codes are transient and don't survive restarts.
+#[derive(Debug, Default)]
+pub struct LobbyInviteRegistry {
+    invites: HashMap<String, LobbyInvite>,
+}
+
+impl LobbyInviteRegistry {
+    const CODE_LENGTH: usize = 8;
+    const CODE_ALPHABET: &'static [u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789"; // no look-alikes
+    const DEFAULT_TTL_SECONDS: u32 = 24 * 60 * 60;
+    const MAX_TTL_SECONDS: u32 = 7 * 24 * 60 * 60;
+
+    pub fn create_invite(&mut self, lobby_id: Uuid, created_by: Uuid, ttl_seconds: Option<u32>) -> Result<LobbyInvite, AppError> {
+        let ttl_seconds = ttl_seconds.unwrap_or(Self::DEFAULT_TTL_SECONDS);
+        if ttl_seconds == 0 || ttl_seconds > Self::MAX_TTL_SECONDS {
+            return Err(
+                AppError::invalid_request(
+                    format!("Invite lifetime must range from 1 to {} seconds!", Self::MAX_TTL_SECONDS)
+                )
+            );
+        }
+        self.rm_expired_invites();
+
+        let code = loop {
+            let code = Self::generate_code();
+            if !self.invites.contains_key(&code) {
+                break code;
+            }
+        };
+        let invite = LobbyInvite {
+            code: code.clone(),
+            lobby_id,
+            created_by,
+            expires_at: Utc::now() + TimeDelta::seconds(ttl_seconds.into()),
+        };
+        self.invites.insert(code, invite.clone());
+        Ok(invite)
+    }
+
+    /// Codes are case-insensitive.
+    pub fn resolve_invite(&self, code: &str) -> Result<Uuid, AppError> {
+        match self.invites.get(&code.to_uppercase()) {
+            Some(invite) if !invite.is_expired() => Ok(invite.lobby_id),
+            _ => Err(AppError::precondition_failed("Invite code invalid or expired!")),
+        }
+    }
+
+    pub fn revoke_invite(&mut self, lobby_id: &Uuid, code: &str) -> Result<(), AppError> {
+        let code = code.to_uppercase();
+        match self.invites.get(&code) {
+            Some(invite) if invite.lobby_id == *lobby_id => {
+                self.invites.remove(&code);
+                Ok(())
+            },
+            _ => Err(AppError::precondition_failed("Invite code not found for this lobby!")),
+        }
+    }
+
+    pub fn get_lobby_invites(&self, lobby_id: &Uuid) -> Vec<LobbyInvite> {
+        let mut invites: Vec<_> = self.invites
+            .values()
+            .filter(|invite| invite.lobby_id == *lobby_id && !invite.is_expired())
+            .cloned()
+            .collect();
+        invites.sort_by_key(|invite| invite.expires_at);
+        invites
+    }
+
+    fn rm_expired_invites(&mut self) {
+        self.invites.retain(|_, invite| !invite.is_expired());
+    }
+
+    fn generate_code() -> String {
+        let mut rng = rng();
+        (0..Self::CODE_LENGTH)
+            .map(|_| Self::CODE_ALPHABET[rng.random_range(0..Self::CODE_ALPHABET.len())] as char)
+            .collect()
+    }
+}
+
+impl From<LobbyInvite> for proto::LobbyInvite {
+    fn from(invite: LobbyInvite) -> Self {
+        proto::LobbyInvite {
+            code: invite.code,
+            lobby_id: invite.lobby_id.to_string(),
+            created_by: invite.created_by.to_string(),
+            expires_at: Some(chrono_to_prost_timestamp(invite.expires_at)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn resolve_invite() {
+        let mut registry = LobbyInviteRegistry::default();
+        let lobby_id = Uuid::new_v4();
+        let invite = registry.create_invite(lobby_id, Uuid::new_v4(), None).unwrap();
+        assert_eq!(invite.code.len(), LobbyInviteRegistry::CODE_LENGTH);
+        assert_eq!(registry.resolve_invite(&invite.code.to_lowercase()).unwrap(), lobby_id);
+        assert!(registry.revoke_invite(&Uuid::new_v4(), &invite.code).is_err());
+        registry.revoke_invite(&lobby_id, &invite.code).unwrap();
+        assert!(registry.resolve_invite(&invite.code).is_err());
+        registry.invites.insert(invite.code.clone(), LobbyInvite { expires_at: Utc::now(), ..invite.clone() });
+        assert!(registry.resolve_invite(&invite.code).is_err());
+        assert!(registry.create_invite(lobby_id, Uuid::new_v4(), Some(0)).is_err());
+    }
+}
@@ -1,14 +1,18 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
+use chrono::{DateTime, TimeDelta, Utc};
 use tokio::sync::{broadcast, RwLock};
 use uuid::Uuid;
 
 use crate::common::error::AppError;
-use crate::lobby::LobbySettings;
+use crate::lobby::{LobbyPassword, LobbySettings, LobbyWaitlist, MatchmakingTimeoutAction, SeatOffer};
 use crate::game::PokerPhase;
+use crate::history::HandHistoryRegistry;
 use crate::player::{Player, PlayerRegistry};
-use crate::r#match::Match;
+use crate::stats::PlayerHud;
+use crate::r#match::{Match, MatchStartPlayers};
 use crate::output::GameStateBroadcaster;
+use crate::storage::{LobbyRecord, MatchSnapshot, Storage};
 
 #[derive(Clone, Debug)]
 pub struct Lobby {
@@ -17,13 +21,20 @@ pub struct Lobby {
     pub name: String,
     pub host_player_id: Uuid,
     pub host_player_name: String, // cached for output layer, avoid fetch from registry
-    pub player_ids: HashSet<Uuid>,
+    pub seats: BTreeMap<u8, Uuid>, // seat number -> player, clockwise
+    pub dealer_seat: Option<u8>, // of the latest match
+    pub spectator_ids: HashSet<Uuid>, // not persisted
+    pub waitlist: LobbyWaitlist, // not persisted
     pub game_acceptance: Option<HashSet<Uuid>>, // per player
+    pub matchmaking_deadline: Option<DateTime<Utc>>,
     pub r#match: Option<Match>,
     pub settings: LobbySettings,
+    password: Option<LobbyPassword>,
 }
 
 impl Lobby {
+    const MAX_PASSWORD_LENGTH: usize = 64;
+
     pub fn new(
         broadcast_channel_capacity: usize,
         player_registry: Arc<RwLock<PlayerRegistry>>,
@@ -35,17 +46,69 @@ impl Lobby {
         let state_broadcaster = GameStateBroadcaster::new(
             broadcast_channel_capacity,
             player_registry);
-        let player_ids = HashSet::from([host_player_id]);
+        let seats = BTreeMap::from([(0, host_player_id)]);
         Lobby {
             lobby_id,
             state_broadcaster,
             name: lobby_name,
             host_player_id,
             host_player_name,
-            player_ids,
+            seats,
+            dealer_seat: None,
+            spectator_ids: HashSet::new(),
+            waitlist: LobbyWaitlist::default(),
             game_acceptance: None,
+            matchmaking_deadline: None,
             r#match: None,
             settings: LobbySettings::default(),
+            password: None,
+        }
+    }
+
+    /// Restores a previously persisted lobby. Restored lobbies always start out idle.
+    pub fn from_record(
+        broadcast_channel_capacity: usize,
+        player_registry: Arc<RwLock<PlayerRegistry>>,
+        record: LobbyRecord,
+    ) -> Self {
+        let state_broadcaster = GameStateBroadcaster::new(
+            broadcast_channel_capacity,
+            player_registry);
+        // Records predating seat selection only know the seated players
+        let seats = match record.seats.is_empty() {
+            true => (0..).zip(record.player_ids).collect(),
+            false => record.seats,
+        };
+        Lobby {
+            lobby_id: record.lobby_id,
+            state_broadcaster,
+            name: record.name,
+            host_player_id: record.host_player_id,
+            host_player_name: record.host_player_name,
+            seats,
+            dealer_seat: record.dealer_seat,
+            spectator_ids: HashSet::new(),
+            waitlist: LobbyWaitlist::default(),
+            game_acceptance: None,
+            matchmaking_deadline: None,
+            r#match: None,
+            settings: record.settings,
+            password: record.password_hash.or(record.password.as_deref().map(LobbyPassword::new)), // plaintext in older records
+        }
+    }
+
+    pub fn to_record(&self) -> LobbyRecord {
+        LobbyRecord {
+            lobby_id: self.lobby_id,
+            name: self.name.clone(),
+            host_player_id: self.host_player_id,
+            host_player_name: self.host_player_name.clone(),
+            player_ids: self.get_player_ids(),
+            seats: self.seats.clone(),
+            dealer_seat: self.dealer_seat,
+            settings: self.settings.clone(),
+            password_hash: self.password.clone(),
+            password: None,
         }
     }
 
@@ -59,16 +122,110 @@ impl Lobby {
         Ok(())
     }
 
-    pub fn joinable_validation(&self) -> Result<(), AppError> {
+    /// Private lobbies are only reachable by invite, an invite code still being subject to the password.
+    pub fn joinable_validation(&self, password: Option<&str>, via_invite_code: bool) -> Result<(), AppError> {
+        self.capacity_validation()?;
+        self.access_validation(password, via_invite_code)
+    }
+
+    fn access_validation(&self, password: Option<&str>, via_invite_code: bool) -> Result<(), AppError> {
+        if self.settings.is_private && !via_invite_code {
+            return Err(AppError::unauthorized("Private lobbies require an invite to join!"));
+        }
+        self.password_validation(password)
+    }
+
+    /// Password-protected lobbies still count as joinable, given the password.
+    pub fn is_joinable(&self) -> bool {
+        self.capacity_validation().is_ok()
+    }
+
+    fn capacity_validation(&self) -> Result<(), AppError> {
         self.lobby_locked_validation()?;
-        if self.player_ids.len() as u8 >= self.settings.max_players {
+        if self.seats.len() as u8 >= self.settings.max_players {
             return Err(AppError::unauthorized("Cannot join lobby. Max player capacity already reached!"));
         };
+        if !self.waitlist.is_empty() {
+            return Err(AppError::unauthorized("Cannot join lobby ahead of its waitlist!"));
+        }
         Ok(())
     }
 
-    pub fn is_joinable(&self) -> bool {
-        self.joinable_validation().is_ok()
+    fn seat_validation(&self, seat: u8) -> Result<(), AppError> {
+        if seat >= self.settings.max_players {
+            return Err(
+                AppError::invalid_request(
+                    format!("Seat numbers range from 0 to {}!", self.settings.max_players.saturating_sub(1))
+                )
+            );
+        }
+        if self.seats.contains_key(&seat) || self.is_seat_offered(seat) {
+            return Err(AppError::precondition_failed(format!("Seat {seat} already taken!")));
+        }
+        Ok(())
+    }
+
+    /// Lowest free seat, if any. Seats offered to the waitlist don't count as free.
+    fn get_free_seat(&self) -> Option<u8> {
+        (0..self.settings.max_players).find(|seat| !self.seats.contains_key(seat) && !self.is_seat_offered(*seat))
+    }
+
+    fn is_seat_offered(&self, seat: u8) -> bool {
+        self.waitlist.get_seat_offer().is_some_and(|offer| offer.seat == seat)
+    }
+
+    pub fn get_player_ids(&self) -> HashSet<Uuid> {
+        self.seats.values().copied().collect()
+    }
+
+    pub fn get_player_seat(&self, player_id: &Uuid) -> Option<u8> {
+        self.seats
+            .iter()
+            .find(|(_, seated_player_id)| *seated_player_id == player_id)
+            .map(|(seat, _)| *seat)
+    }
+
+    /// The button moves clockwise onto the next occupied seat, starting off at the lowest one.
+    fn get_next_dealer_seat(&self) -> Option<u8> {
+        let first_seat = self.seats.keys().next().copied();
+        match self.dealer_seat {
+            Some(dealer_seat) => self.seats
+                .range(dealer_seat.saturating_add(1)..)
+                .next()
+                .map(|(seat, _)| *seat)
+                .or(first_seat),
+            None => first_seat,
+        }
+    }
+
+    /// Applies to spectators as well.
+    pub fn password_validation(&self, password: Option<&str>) -> Result<(), AppError> {
+        match &self.password {
+            Some(lobby_password) if !password.is_some_and(|password| lobby_password.verify(password)) => {
+                Err(AppError::unauthorized("Invalid lobby password!"))
+            },
+            _ => Ok(()),
+        }
+    }
+
+    pub fn has_password(&self) -> bool {
+        self.password.is_some()
+    }
+
+    /// Empty passwords lift the protection.
+    pub async fn set_password(&mut self, password: Option<String>) -> Result<(), AppError> {
+        let password = password.filter(|password| !password.is_empty());
+        if password.as_ref().is_some_and(|password| password.chars().count() > Self::MAX_PASSWORD_LENGTH) {
+            return Err(
+                AppError::invalid_request(
+                    format!("Lobby passwords may not exceed {} characters!", Self::MAX_PASSWORD_LENGTH)
+                )
+            );
+        }
+        self.password = password.as_deref().map(LobbyPassword::new);
+
+        self.state_broadcaster.publish_with_lobby(self).await;
+        Ok(())
     }
 
     pub fn is_in_game(&self) -> bool {
@@ -98,36 +255,86 @@ impl Lobby {
         let Some(game_acceptance) = self.is_matchmaking_then_acceptance() else {
             return Err(AppError::precondition_failed("Lobby not currently matchmaking!"));
         };
-        if (self.player_ids.len() as u8) < self.settings.min_players  {
+        if (self.seats.len() as u8) < self.settings.min_players  {
             return Err(AppError::precondition_failed(
                 format!("Minimum number of players ({}) unmet!", self.settings.min_players)
             ));
         }
-        if *game_acceptance != self.player_ids {
+        if *game_acceptance != self.get_player_ids() {
             return Err(AppError::precondition_failed("All players need to accept matchmaking!"));
         }
         Ok(())
     }
 
-    pub async fn start_match(&mut self, players: HashSet<Player>) {
+    /// Every player needs to be able to cover the ante, for the match to be played out.
+    pub fn ante_validation(&self, players: &HashSet<Player>) -> Result<(), AppError> {
+        let short_players: Vec<&str> = players
+            .iter()
+            .filter(|player| player.total_credits < self.settings.ante_amount)
+            .map(|player| player.player_name.as_str())
+            .collect();
+        if !short_players.is_empty() {
+            return Err(AppError::precondition_failed(
+                format!("Players can't cover the ante ({}): {}", self.settings.ante_amount, short_players.join(", "))
+            ));
+        }
+        Ok(())
+    }
+
+    pub async fn start_match(
+        &mut self,
+        players: HashSet<Player>,
+        player_huds: HashMap<Uuid, PlayerHud>,
+        player_registry: Arc<RwLock<PlayerRegistry>>,
+        hand_history_registry: Arc<RwLock<HandHistoryRegistry>>,
+        storage: Arc<dyn Storage>,
+    ) {
         let (rpc_action_broadcaster, _) = broadcast::channel(
             PokerPhase::RPC_ACTION_EVENT_CHANNEL_CAPACITY,
         );
         let rpc_action_receiver = rpc_action_broadcaster.subscribe();
 
+        let dealer_seat = self.get_next_dealer_seat().unwrap_or_default();
+        self.dealer_seat = Some(dealer_seat);
         let r#match = Match::new(
             self.lobby_id,
+            self.name.clone(),
             self.state_broadcaster.clone(),
             rpc_action_broadcaster,
-            players,
-            self.settings.ante_amount,
+            storage,
+            MatchStartPlayers::new(players, &self.seats, dealer_seat, player_huds),
+            &self.settings,
         );
 
         // TODO: Make game_acceptance + match_id type-wise impossible
+        self.game_acceptance = None;
+        self.matchmaking_deadline = None;
+        self.r#match = Some(r#match);
+
+        self.r#match.as_mut().unwrap().play_poker(rpc_action_receiver, player_registry, hand_history_registry).await;
+    }
+
+    /// Returns the lobby to idle once its match got played out.
+    pub async fn finish_match(&mut self) {
+        self.r#match = None;
+
+        self.state_broadcaster.publish_with_lobby(self).await;
+    }
+
+    /// Resumes a match interrupted by a server restart.
+    pub async fn restore_match(
+        &mut self,
+        snapshot: MatchSnapshot,
+        player_registry: Arc<RwLock<PlayerRegistry>>,
+        hand_history_registry: Arc<RwLock<HandHistoryRegistry>>,
+        storage: Arc<dyn Storage>,
+    ) {
+        let r#match = Match::restore(self.state_broadcaster.clone(), storage, snapshot);
+
         self.game_acceptance = None;
         self.r#match = Some(r#match);
 
-        self.r#match.as_mut().unwrap().play_poker(rpc_action_receiver).await;
+        self.r#match.as_mut().unwrap().resume_poker(player_registry, hand_history_registry).await;
     }
 
     pub async fn start_matchmaking(&mut self) -> Result<(), AppError> {
@@ -136,7 +343,7 @@ impl Lobby {
         }
         self.lobby_locked_validation()?;
 
-        if (self.player_ids.len() as u8) < self.settings.min_players  {
+        if (self.seats.len() as u8) < self.settings.min_players  {
             return Err(AppError::precondition_failed(
                 format!("Minimum number of players ({}) unmet!", self.settings.min_players)
             ));
@@ -144,6 +351,8 @@ impl Lobby {
 
         let game_acceptance = HashSet::from([self.host_player_id]);
         self.game_acceptance = Some(game_acceptance);
+        self.matchmaking_deadline = self.settings.matchmaking.accept_timeout_seconds
+            .map(|timeout_seconds| Utc::now() + TimeDelta::seconds(timeout_seconds.into()));
 
         self.state_broadcaster.publish_with_lobby(&self).await;
         Ok(())
@@ -159,14 +368,64 @@ impl Lobby {
 
     pub async fn stop_matchmaking(&mut self) {
         self.game_acceptance = None;
+        self.matchmaking_deadline = None;
 
         self.state_broadcaster.publish_with_lobby(&self).await;
     }
 
-    pub async fn add_player(&mut self, player_id: Uuid) -> Result<(), AppError> {
-        self.joinable_validation()?;
+    /// Deals with players who haven't accepted matchmaking by the deadline.<br />
+    /// Returns the removed players, given the lobby's timeout action is to remove them.
+    pub async fn expire_matchmaking(&mut self) -> Vec<Uuid> {
+        self.matchmaking_deadline = None;
+        let Some(game_acceptance) = self.is_matchmaking_then_acceptance() else {
+            return Vec::new();
+        };
+        let unresponsive_player_ids: Vec<Uuid> = self.seats
+            .values()
+            .filter(|player_id| !game_acceptance.contains(player_id))
+            .copied()
+            .collect();
+        if unresponsive_player_ids.is_empty() {
+            return Vec::new();
+        }
+
+        let host_unresponsive = unresponsive_player_ids.contains(&self.host_player_id);
+        if self.settings.matchmaking.timeout_action == MatchmakingTimeoutAction::Cancel || host_unresponsive {
+            self.stop_matchmaking().await;
+            return Vec::new();
+        }
+        self.seats.retain(|_, player_id| !unresponsive_player_ids.contains(player_id));
+        if (self.seats.len() as u8) < self.settings.min_players {
+            self.stop_matchmaking().await;
+            return unresponsive_player_ids;
+        }
 
-        self.player_ids.insert(player_id);
+        self.state_broadcaster.publish_with_lobby(self).await;
+        unresponsive_player_ids
+    }
+
+    /// Players without a seat preference take the lowest free seat.
+    pub async fn add_player(&mut self, player_id: Uuid, password: Option<&str>, via_invite_code: bool, seat: Option<u8>) -> Result<(), AppError> {
+        self.joinable_validation(password, via_invite_code)?;
+        self.seat_player(player_id, seat).await
+    }
+
+    /// Invited players skip the password, but not the lobby's capacity limits.
+    pub async fn add_invited_player(&mut self, player_id: Uuid, seat: Option<u8>) -> Result<(), AppError> {
+        self.capacity_validation()?;
+        self.seat_player(player_id, seat).await
+    }
+
+    async fn seat_player(&mut self, player_id: Uuid, seat: Option<u8>) -> Result<(), AppError> {
+        let seat = match seat {
+            Some(seat) => {
+                self.seat_validation(seat)?;
+                seat
+            },
+            None => self.get_free_seat().ok_or(AppError::unauthorized("Cannot join lobby. No free seats left!"))?,
+        };
+
+        self.seats.insert(seat, player_id);
         self.clear_matchmaking().await; // technically can't join while matchmaking...
 
         self.state_broadcaster.publish_with_lobby(&self).await;
@@ -176,22 +435,122 @@ impl Lobby {
     pub async fn rm_player(&mut self, player_id: &Uuid) -> Result<(), AppError> {
         self.lobby_locked_validation()?;
 
-        if !self.player_ids.contains(player_id) {
+        let Some(seat) = self.get_player_seat(player_id) else {
             return Ok(());
-        }
-        self.player_ids.remove(player_id);
+        };
+        self.seats.remove(&seat);
         self.clear_matchmaking().await;
 
         self.state_broadcaster.publish_with_lobby(&self).await;
         Ok(())
     }
 
+    /// Seats may only be changed in between matches.
+    pub async fn change_seat(&mut self, player_id: &Uuid, seat: u8) -> Result<(), AppError> {
+        self.lobby_locked_validation()?;
+
+        let Some(current_seat) = self.get_player_seat(player_id) else {
+            return Err(AppError::not_found(*player_id));
+        };
+        if current_seat == seat {
+            return Ok(());
+        }
+        self.seat_validation(seat)?;
+        self.seats.remove(&current_seat);
+        self.seats.insert(seat, *player_id);
+
+        self.state_broadcaster.publish_with_lobby(self).await;
+        Ok(())
+    }
+
+    /// Only full or locked lobbies may be waitlisted for.
+    pub async fn join_waitlist(&mut self, player_id: Uuid, password: Option<&str>, via_invite_code: bool) -> Result<(), AppError> {
+        self.access_validation(password, via_invite_code)?;
+        if self.is_player(&player_id) {
+            return Err(AppError::precondition_failed("Player already seated in this lobby!"));
+        }
+        if self.capacity_validation().is_ok() {
+            return Err(AppError::precondition_failed("Lobby has free seats, join it directly instead!"));
+        }
+        self.waitlist.push(player_id)?;
+
+        self.state_broadcaster.publish_with_lobby(self).await;
+        Ok(())
+    }
+
+    pub async fn leave_waitlist(&mut self, player_id: &Uuid) -> bool {
+        if !self.waitlist.remove(player_id) {
+            return false;
+        }
+
+        self.state_broadcaster.publish_with_lobby(self).await;
+        true
+    }
+
+    /// Holds the lowest free seat for the head of the waitlist, as long as the lobby is idle.
+    pub async fn offer_free_seat(&mut self) -> Option<SeatOffer> {
+        if self.lobby_locked_validation().is_err() {
+            return None;
+        }
+        let seat = self.get_free_seat()?;
+        let offer = self.waitlist.offer_seat(seat)?;
+
+        self.state_broadcaster.publish_with_lobby(self).await;
+        Some(offer)
+    }
+
+    pub async fn accept_seat_offer(&mut self, player_id: Uuid) -> Result<(), AppError> {
+        self.lobby_locked_validation()?;
+        let offer = self.waitlist.take_seat_offer(&player_id)?;
+        self.seats.insert(offer.seat, player_id);
+        self.clear_matchmaking().await;
+
+        self.state_broadcaster.publish_with_lobby(self).await;
+        Ok(())
+    }
+
+    /// Passes an expired offer on to the next player in line, if any.
+    pub async fn expire_seat_offer(&mut self, offer: &SeatOffer) -> Option<SeatOffer> {
+        if !self.waitlist.expire_seat_offer(offer) {
+            return None;
+        }
+        self.state_broadcaster.publish_with_lobby(self).await;
+        self.offer_free_seat().await
+    }
+
+    /// Spectators may come and go regardless of lobby status, private lobbies still requiring an invite code.
+    pub async fn add_spectator(&mut self, player_id: Uuid, password: Option<&str>, via_invite_code: bool) -> Result<(), AppError> {
+        if !self.settings.spectators.allowed {
+            return Err(AppError::unauthorized("Lobby doesn't allow spectators!"));
+        }
+        self.access_validation(password, via_invite_code)?;
+        if self.is_player(&player_id) {
+            return Err(AppError::precondition_failed("Players can't spectate their own lobby!"));
+        }
+        if self.spectator_ids.len() >= self.settings.spectators.max_spectators as usize {
+            return Err(AppError::unauthorized("Cannot spectate lobby. Max spectator capacity already reached!"));
+        }
+
+        self.spectator_ids.insert(player_id);
+
+        self.state_broadcaster.publish_with_lobby(self).await;
+        Ok(())
+    }
+
+    pub async fn rm_spectator(&mut self, player_id: &Uuid) {
+        if !self.spectator_ids.remove(player_id) {
+            return;
+        }
+
+        self.state_broadcaster.publish_with_lobby(self).await;
+    }
+
     pub async fn set_match_acceptance(&mut self, player_id: Uuid, acceptance: bool) -> Result<(), AppError> {
         if self.is_in_game() {
             return Err(AppError::unauthorized("Cannot modify matchmaking acceptance while in-game!"));
         }
 
-        if !self.player_ids.contains(&player_id) {
+        if !self.is_player(&player_id) {
             return Err(AppError::not_found(player_id));
         }
 
@@ -207,11 +566,56 @@ impl Lobby {
         Ok(())
     }
 
+    pub async fn set_settings(&mut self, settings: LobbySettings) -> Result<(), AppError> {
+        self.lobby_locked_validation()?;
+
+        if self.seats.len() as u8 > settings.max_players {
+            return Err(AppError::precondition_failed(
+                format!("Lobby already has more than {} players!", settings.max_players)
+            ));
+        }
+        if self.seats.keys().next_back().is_some_and(|seat| *seat >= settings.max_players) {
+            return Err(AppError::precondition_failed(
+                format!("Players seated beyond the first {} seats need to move first!", settings.max_players)
+            ));
+        }
+        if self.spectator_ids.len() > settings.spectators.get_capacity() as usize {
+            return Err(AppError::precondition_failed(
+                format!("Lobby already has more than {} spectators!", settings.spectators.get_capacity())
+            ));
+        }
+        self.settings = settings;
+
+        self.state_broadcaster.publish_with_lobby(self).await;
+        Ok(())
+    }
+
     pub fn is_player(&self, player_id: &Uuid) -> bool {
-        self.player_ids.contains(player_id)
+        self.seats.values().any(|seated_player_id| seated_player_id == player_id)
+    }
+
+    pub fn is_spectator(&self, player_id: &Uuid) -> bool {
+        self.spectator_ids.contains(player_id)
     }
 
     pub fn is_host_player(&self, player_id: &Uuid) -> bool {
        self.host_player_id == *player_id
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::InMemoryStorage;
+    #[tokio::test]
+    async fn spectate_private_lobby() {
+        let player_registry = Arc::new(RwLock::new(PlayerRegistry::restore(Arc::new(InMemoryStorage::default()), HashMap::new(), Vec::new())));
+        let lobby_arc = Arc::new(RwLock::new(Lobby::new(1, player_registry, String::from("Table"), Uuid::new_v4(), String::from("Host"))));
+        let mut lobby = lobby_arc.write().await;
+        lobby.state_broadcaster.set_lobby(lobby_arc.clone());
+        lobby.settings.is_private = true;
+        let spectator_id = Uuid::new_v4();
+        assert!(lobby.add_spectator(spectator_id, None, false).await.is_err());
+        assert!(lobby.add_spectator(spectator_id, None, true).await.is_ok());
+    }
+}
@@ -1,7 +1,15 @@
+mod invitation;
+mod invite;
 mod lobby;
+mod password;
 mod registry;
 mod settings;
+mod waitlist;
 
+pub use invitation::{LobbyInvitation, LobbyInvitationRegistry};
+pub use invite::{LobbyInvite, LobbyInviteRegistry};
 pub use lobby::Lobby;
+pub use password::LobbyPassword;
 pub use registry::LobbyRegistry;
-pub use settings::LobbySettings;
+pub use settings::{GameMode, LobbySettings, MatchmakingSettings, MatchmakingTimeoutAction, RakeSettings};
+pub use waitlist::{LobbyWaitlist, LobbyWaitlistPosition, SeatOffer};
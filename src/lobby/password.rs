@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+/// Salted hash of a lobby password, the password itself never being kept.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LobbyPassword {
+    salt: String,
+    hash: String,
+}
+
+impl LobbyPassword {
+    pub fn new(password: &str) -> Self {
+        let salt = Uuid::new_v4().simple().to_string();
+        let hash = Self::hash(&salt, password);
+        LobbyPassword { salt, hash }
+    }
+
+    pub fn verify(&self, password: &str) -> bool {
+        self.hash == Self::hash(&self.salt, password)
+    }
+
+    /// Hex-encoded SHA-256 digest of the salted password.
+    fn hash(salt: &str, password: &str) -> String {
+        Sha256::digest(format!("{salt}{password}").as_bytes())
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn verify() {
+        let password = LobbyPassword::new("hunter2");
+        assert!(password.verify("hunter2"));
+        assert!(!password.verify("hunter3"));
+        assert_ne!(password.hash, LobbyPassword::new("hunter2").hash); // salted
+    }
+}
@@ -1,30 +1,123 @@
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::sync::RwLock as SyncRwLock;
+use tokio::sync::{broadcast, RwLock};
 use uuid::Uuid;
 
 use crate::common::error::AppError;
+use crate::output::{LobbyInfoPublic, LobbyListEvent, LobbyPage, LobbyQuery};
+use crate::storage::{self, InMemoryStorage, Storage};
 use super::Lobby;
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 pub struct LobbyRegistry {
     registry: Arc<RwLock<HashMap<Uuid, Arc<RwLock<Lobby>>>>>,
+    storage: Arc<dyn Storage>,
+    lobby_list_broadcaster: broadcast::Sender<LobbyListEvent>,
+    /// Public lobby summaries, kept up to date for lobby listing without locking every lobby.
+    lobby_index: Arc<SyncRwLock<HashMap<Uuid, LobbyInfoPublic>>>,
+}
+
+impl Default for LobbyRegistry {
+    fn default() -> Self {
+        Self::new(Arc::new(InMemoryStorage::default()))
+    }
 }
 
 impl LobbyRegistry {
+    const LOBBY_LIST_CHANNEL_CAPACITY: usize = 100;
+
+    pub fn new(storage: Arc<dyn Storage>) -> Self {
+        let (lobby_list_broadcaster, _) = broadcast::channel(Self::LOBBY_LIST_CHANNEL_CAPACITY);
+        LobbyRegistry {
+            registry: Arc::new(RwLock::new(HashMap::new())),
+            storage,
+            lobby_list_broadcaster,
+            lobby_index: Arc::default(),
+        }
+    }
+
     pub async fn add_lobby(&mut self, lobby_id: Uuid, lobby: Arc<RwLock<Lobby>>) -> Result<(), AppError> {
         let mut registry_w = self.registry.write().await;
+        let lobby_r = lobby.read().await;
+        let record = lobby_r.to_record();
+        storage::run_blocking(&self.storage, move |storage| storage.store_lobby(record)).await?;
+        self.publish(LobbyListEvent::Created(lobby_r.clone().into()));
+        drop(lobby_r);
         registry_w.insert(lobby_id, lobby);
         Ok(())
     }
 
+    /// Registers a lobby restored from storage, without persisting it again.
+    pub async fn restore_lobby(&mut self, lobby_id: Uuid, lobby: Arc<RwLock<Lobby>>) {
+        let mut registry_w = self.registry.write().await;
+        self.update_index(&LobbyListEvent::Created(lobby.read().await.clone().into()));
+        registry_w.insert(lobby_id, lobby);
+    }
+
     pub async fn rm_lobby(&mut self, lobby_id: &Uuid) -> Result<(), AppError> {
-        let Some(_) = self.registry.write().await.remove(lobby_id) else {
+        let mut registry_w = self.registry.write().await;
+        if !registry_w.contains_key(lobby_id) {
             return Err(AppError::not_found(lobby_id.clone()));
-        };
+        }
+        let removed_lobby_id = *lobby_id;
+        storage::run_blocking(&self.storage, move |storage| storage.remove_lobby(&removed_lobby_id)).await?;
+        registry_w.remove(lobby_id);
+        self.publish(LobbyListEvent::Removed(*lobby_id));
         return Ok(());
     }
 
+    /// Persists the latest state of a registered lobby.
+    pub async fn store_lobby(&self, lobby: &Lobby) -> Result<(), AppError> {
+        let record = lobby.to_record();
+        storage::run_blocking(&self.storage, move |storage| storage.store_lobby(record)).await?;
+        self.publish_lobby_update(lobby);
+        Ok(())
+    }
+
+    /// Announces lobby changes which aren't persisted, e.g. matchmaking or game start.
+    pub fn publish_lobby_update(&self, lobby: &Lobby) {
+        self.publish(LobbyListEvent::Updated(lobby.clone().into()));
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<LobbyListEvent> {
+        self.lobby_list_broadcaster.subscribe()
+    }
+
+    pub fn search_lobbies(&self, query: &LobbyQuery) -> LobbyPage {
+        match self.lobby_index.read() {
+            Ok(lobby_index_r) => query.paginate(lobby_index_r.values()),
+            Err(_) => query.paginate(std::iter::empty()),
+        }
+    }
+
+    pub fn get_lobby_infos(&self) -> Vec<LobbyInfoPublic> {
+        self.lobby_index
+            .read()
+            .map(|lobby_index_r| lobby_index_r.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    fn publish(&self, event: LobbyListEvent) {
+        self.update_index(&event);
+        // Ignore errors caused by no active receivers...
+        let _ = self.lobby_list_broadcaster.send(event);
+    }
+
+    fn update_index(&self, event: &LobbyListEvent) {
+        let Ok(mut lobby_index_w) = self.lobby_index.write() else {
+            return;
+        };
+        match event {
+            LobbyListEvent::Created(lobby) | LobbyListEvent::Updated(lobby) => {
+                lobby_index_w.insert(lobby.get_lobby_id(), lobby.clone());
+            },
+            LobbyListEvent::Removed(lobby_id) => {
+                lobby_index_w.remove(lobby_id);
+            },
+        }
+    }
+
     pub async fn get_lobby_arc(&self, lobby_id: &Uuid) -> Option<Arc<RwLock<Lobby>>> {
         let registry_r = self.registry.read().await;
         let Some(lobby) = registry_r.get(lobby_id) else {
@@ -34,30 +127,25 @@ impl LobbyRegistry {
         Some(lobby)
     }
 
-    pub async fn get_lobbies(&self) -> Vec<Lobby> {
-        let registry_r = self.registry.read().await;
-        // registry_r
-        //     .values()
-        //     .map(async move |lobby| {
-        //         let lobby = lobby.read().await;
-        //         lobby
-        //     })
-        //     .collect()
-        let futures: Vec<_> = registry_r
-            .values()
-            .map(|lobby| {
-                let lobby = lobby.clone();
-                tokio::spawn(async move {
-                    let lobby_r = lobby.read().await;
-                    lobby_r.clone()
-                })
-            })
-            .collect();
-        let mut lobbies = Vec::new();
-        for future in futures {
-            let lobby = future.await.unwrap();
-            lobbies.push(lobby);
-        }
-        lobbies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::PlayerRegistry;
+    #[tokio::test]
+    async fn rm_lobby() {
+        let storage: Arc<dyn Storage> = Arc::new(InMemoryStorage::default());
+        let player_registry = Arc::new(RwLock::new(PlayerRegistry::restore(storage.clone(), HashMap::new(), Vec::new())));
+        let lobby = Lobby::new(1, player_registry, String::from("Table"), Uuid::new_v4(), String::from("Host"));
+        let lobby_id = lobby.lobby_id;
+        let mut registry = LobbyRegistry::new(storage.clone());
+        registry.add_lobby(lobby_id, Arc::new(RwLock::new(lobby))).await.unwrap();
+        assert_eq!(storage.load().unwrap().lobbies.len(), 1);
+
+        registry.rm_lobby(&lobby_id).await.unwrap();
+        assert!(registry.get_lobby_arc(&lobby_id).await.is_none());
+        assert!(storage.load().unwrap().lobbies.is_empty()); // not restored on boot
+        assert!(registry.rm_lobby(&lobby_id).await.is_err());
     }
 }
@@ -1,19 +1,89 @@
+use serde::{Deserialize, Serialize};
+
 use crate::common::error::AppError;
 use crate::service::proto;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct LobbySettings {
     pub min_players: u8,
     pub max_players: u8,
     pub ante_amount: u64,
+    pub rake: RakeSettings,
+    #[serde(default)]
+    pub spectators: SpectatorSettings,
+    /// Private lobbies don't show up in lobby listings.
+    #[serde(default)]
+    pub is_private: bool,
+    #[serde(default)]
+    pub matchmaking: MatchmakingSettings,
+    /// Shows opponents' stats at the table.
+    #[serde(default)]
+    pub hud_enabled: bool,
+}
+
+/// Only single tables for now, tournaments to come.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GameMode {
+    #[default]
+    Single,
+}
+
+/// The house's cut of each pot.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RakeSettings {
+    pub percentage: u8,
+    pub cap: Option<u64>,
+    /// Hands ending before the drawing phase don't get raked.
+    pub no_flop_no_drop: bool,
+}
+
+/// Seatless lobby viewers, only ever shown hole cards at showdown.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SpectatorSettings {
+    pub allowed: bool,
+    pub max_spectators: u8,
+    /// Spectators get to see the game this many seconds late, to prevent ghosting.
+    pub delay_seconds: u32,
+}
+
+/// Keeps unresponsive players from stalling matchmaking.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct MatchmakingSettings {
+    /// Players get this long to accept once matchmaking starts, no deadline if unset.
+    pub accept_timeout_seconds: Option<u32>,
+    pub timeout_action: MatchmakingTimeoutAction,
+    /// Starts the match as soon as every player has accepted.
+    pub auto_start: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MatchmakingTimeoutAction {
+    #[default]
+    Cancel,
+    /// Removes players who haven't accepted in time, cancelling if too few players remain.
+    RemovePlayers,
 }
 
 impl LobbySettings {
     const MIN_PLAYERS: u8 = 2;
     const MAX_PLAYERS: u8 = 6; // 8, // TODO: card discard reshuffling
     const DEFAULT_ANTE_AMOUNT: u64 = 10;
+    const MIN_ANTE_AMOUNT: u64 = 1;
+    const MAX_ANTE_AMOUNT: u64 = 100; // a fifth of the registration credits
 
-    fn new(min_players: u8, max_players: u8, ante_amount: u64) -> Result<Self, AppError> {
+    pub fn get_game_mode(&self) -> GameMode {
+        GameMode::Single
+    }
+
+    pub fn new(
+        min_players: u8,
+        max_players: u8,
+        ante_amount: u64,
+        rake: RakeSettings,
+        spectators: SpectatorSettings,
+        is_private: bool,
+        matchmaking: MatchmakingSettings,
+    ) -> Result<Self, AppError> {
         if min_players < Self::MIN_PLAYERS {
           return Err(
               AppError::invalid_request(
@@ -28,20 +98,120 @@ impl LobbySettings {
                 )
             )
         }
+        if min_players > max_players {
+            return Err(AppError::invalid_request("Minimum number of players exceeds maximum!"))
+        }
+        // Whether seated players can cover the ante gets checked once the match starts
+        if !(Self::MIN_ANTE_AMOUNT..=Self::MAX_ANTE_AMOUNT).contains(&ante_amount) {
+            return Err(
+                AppError::invalid_request(
+                    format!("Ante amount must range from {} to {}!", Self::MIN_ANTE_AMOUNT, Self::MAX_ANTE_AMOUNT)
+                )
+            )
+        }
+        if rake.percentage > RakeSettings::MAX_PERCENTAGE {
+            return Err(
+                AppError::invalid_request(
+                    format!("Rake percentage ({}%) exceeded!", RakeSettings::MAX_PERCENTAGE)
+                )
+            )
+        }
+        if spectators.max_spectators > SpectatorSettings::MAX_SPECTATORS {
+            return Err(
+                AppError::invalid_request(
+                    format!("Maximum number of spectators ({}) exceeded!", SpectatorSettings::MAX_SPECTATORS)
+                )
+            )
+        }
+        if spectators.delay_seconds > SpectatorSettings::MAX_DELAY_SECONDS {
+            return Err(
+                AppError::invalid_request(
+                    format!("Spectator delay ({}s) exceeded!", SpectatorSettings::MAX_DELAY_SECONDS)
+                )
+            )
+        }
+        if let Some(accept_timeout_seconds) = matchmaking.accept_timeout_seconds {
+            if !(MatchmakingSettings::MIN_ACCEPT_TIMEOUT_SECONDS..=MatchmakingSettings::MAX_ACCEPT_TIMEOUT_SECONDS).contains(&accept_timeout_seconds) {
+                return Err(
+                    AppError::invalid_request(
+                        format!(
+                            "Matchmaking accept timeout must range from {} to {} seconds!",
+                            MatchmakingSettings::MIN_ACCEPT_TIMEOUT_SECONDS,
+                            MatchmakingSettings::MAX_ACCEPT_TIMEOUT_SECONDS,
+                        )
+                    )
+                )
+            }
+        }
         Ok(LobbySettings {
             min_players,
             max_players,
             ante_amount,
+            rake,
+            spectators,
+            is_private,
+            matchmaking,
+            hud_enabled: false,
         })
     }
 }
 
+impl RakeSettings {
+    const MAX_PERCENTAGE: u8 = 100;
+
+    /// Calculates the amount to be raked off a pot.
+    pub fn calculate_rake(&self, pot_credits: u64, reached_drawing: bool) -> u64 {
+        if self.no_flop_no_drop && !reached_drawing {
+            return 0;
+        }
+        let rake = (pot_credits as u128 * self.percentage as u128 / 100) as u64; // never exceeds the pot
+        match self.cap {
+            Some(cap) => rake.min(cap),
+            None => rake,
+        }
+    }
+}
+
+impl SpectatorSettings {
+    const MAX_SPECTATORS: u8 = 50;
+    const DEFAULT_MAX_SPECTATORS: u8 = 10;
+    const MAX_DELAY_SECONDS: u32 = 300;
+
+    /// Spectator seats available, if spectating is allowed at all.
+    pub fn get_capacity(&self) -> u8 {
+        match self.allowed {
+            true => self.max_spectators,
+            false => 0,
+        }
+    }
+}
+
+impl MatchmakingSettings {
+    const MIN_ACCEPT_TIMEOUT_SECONDS: u32 = 5;
+    const MAX_ACCEPT_TIMEOUT_SECONDS: u32 = 600;
+}
+
+impl Default for SpectatorSettings {
+    fn default() -> Self {
+        SpectatorSettings {
+            allowed: true,
+            max_spectators: Self::DEFAULT_MAX_SPECTATORS,
+            delay_seconds: 0,
+        }
+    }
+}
+
 impl Default for LobbySettings {
     fn default() -> Self {
         LobbySettings {
             min_players: Self::MIN_PLAYERS,
             max_players: Self::MAX_PLAYERS,
             ante_amount: Self::DEFAULT_ANTE_AMOUNT,
+            rake: RakeSettings::default(),
+            spectators: SpectatorSettings::default(),
+            is_private: false,
+            matchmaking: MatchmakingSettings::default(),
+            hud_enabled: false,
         }
     }
 }
@@ -49,10 +219,144 @@ impl Default for LobbySettings {
 impl From<LobbySettings> for proto::LobbySettings {
     fn from(settings: LobbySettings) -> Self {
         proto::LobbySettings {
-            game_mode: proto::lobby_settings::GameMode::Single as i32,
+            game_mode: proto::lobby_settings::GameMode::from(settings.get_game_mode()) as i32,
             min_players: settings.min_players.into(),
             max_players: settings.max_players.into(),
             ante_amount: settings.ante_amount,
+            rake_percentage: settings.rake.percentage.into(),
+            rake_cap: settings.rake.cap,
+            rake_no_flop_no_drop: settings.rake.no_flop_no_drop,
+            allow_spectators: settings.spectators.allowed,
+            max_spectators: settings.spectators.max_spectators.into(),
+            spectator_delay_seconds: settings.spectators.delay_seconds,
+            is_private: settings.is_private,
+            matchmaking_accept_timeout_seconds: settings.matchmaking.accept_timeout_seconds,
+            matchmaking_timeout_action: proto::lobby_settings::MatchmakingTimeoutAction::from(settings.matchmaking.timeout_action) as i32,
+            matchmaking_auto_start: settings.matchmaking.auto_start,
+            hud_enabled: settings.hud_enabled,
+        }
+    }
+}
+
+impl TryFrom<proto::LobbySettings> for LobbySettings {
+    type Error = AppError;
+
+    fn try_from(settings: proto::LobbySettings) -> Result<Self, Self::Error> {
+        let min_players = settings.min_players
+            .try_into()
+            .map_err(|_| AppError::invalid_request("Invalid LobbySettings.min_players value!"))?;
+        let max_players = settings.max_players
+            .try_into()
+            .map_err(|_| AppError::invalid_request("Invalid LobbySettings.max_players value!"))?;
+        let percentage = settings.rake_percentage
+            .try_into()
+            .map_err(|_| AppError::invalid_request("Invalid LobbySettings.rake_percentage value!"))?;
+        let rake = RakeSettings {
+            percentage,
+            cap: settings.rake_cap,
+            no_flop_no_drop: settings.rake_no_flop_no_drop,
+        };
+        let max_spectators = settings.max_spectators
+            .try_into()
+            .map_err(|_| AppError::invalid_request("Invalid LobbySettings.max_spectators value!"))?;
+        let spectators = SpectatorSettings {
+            allowed: settings.allow_spectators,
+            max_spectators,
+            delay_seconds: settings.spectator_delay_seconds,
+        };
+        let timeout_action = proto::lobby_settings::MatchmakingTimeoutAction::try_from(settings.matchmaking_timeout_action)
+            .map_err(|_| AppError::invalid_request("Invalid LobbySettings.matchmaking_timeout_action value!"))?
+            .into();
+        let matchmaking = MatchmakingSettings {
+            accept_timeout_seconds: settings.matchmaking_accept_timeout_seconds,
+            timeout_action,
+            auto_start: settings.matchmaking_auto_start,
+        };
+        let lobby_settings = LobbySettings::new(
+            min_players,
+            max_players,
+            settings.ante_amount,
+            rake,
+            spectators,
+            settings.is_private,
+            matchmaking,
+        )?;
+        Ok(LobbySettings {
+            hud_enabled: settings.hud_enabled,
+            ..lobby_settings
+        })
+    }
+}
+
+impl From<GameMode> for proto::lobby_settings::GameMode {
+    fn from(game_mode: GameMode) -> Self {
+        match game_mode {
+            GameMode::Single => proto::lobby_settings::GameMode::Single,
+        }
+    }
+}
+
+impl From<proto::lobby_settings::GameMode> for GameMode {
+    fn from(game_mode: proto::lobby_settings::GameMode) -> Self {
+        match game_mode {
+            proto::lobby_settings::GameMode::Single => GameMode::Single,
+        }
+    }
+}
+
+impl From<MatchmakingTimeoutAction> for proto::lobby_settings::MatchmakingTimeoutAction {
+    fn from(timeout_action: MatchmakingTimeoutAction) -> Self {
+        match timeout_action {
+            MatchmakingTimeoutAction::Cancel => proto::lobby_settings::MatchmakingTimeoutAction::Cancel,
+            MatchmakingTimeoutAction::RemovePlayers => proto::lobby_settings::MatchmakingTimeoutAction::RemovePlayers,
         }
     }
 }
+
+impl From<proto::lobby_settings::MatchmakingTimeoutAction> for MatchmakingTimeoutAction {
+    fn from(timeout_action: proto::lobby_settings::MatchmakingTimeoutAction) -> Self {
+        match timeout_action {
+            proto::lobby_settings::MatchmakingTimeoutAction::Cancel => MatchmakingTimeoutAction::Cancel,
+            proto::lobby_settings::MatchmakingTimeoutAction::RemovePlayers => MatchmakingTimeoutAction::RemovePlayers,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn calculate_rake() {
+        let rake = RakeSettings {
+            percentage: 5,
+            cap: Some(3),
+            no_flop_no_drop: true,
+        };
+        assert_eq!(rake.calculate_rake(40, true), 2);
+        assert_eq!(rake.calculate_rake(1000, true), 3);
+        assert_eq!(rake.calculate_rake(1000, false), 0);
+        let uncapped = RakeSettings {
+            percentage: 10,
+            cap: None,
+            no_flop_no_drop: false,
+        };
+        assert_eq!(uncapped.calculate_rake(1000, false), 100);
+        assert_eq!(uncapped.calculate_rake(u64::MAX, true), u64::MAX / 10);
+    }
+
+    #[test]
+    fn ante_bounds() {
+        let new_settings = |ante_amount| LobbySettings::new(
+            2,
+            6,
+            ante_amount,
+            RakeSettings::default(),
+            SpectatorSettings::default(),
+            false,
+            MatchmakingSettings::default(),
+        );
+        assert!(new_settings(0).is_err());
+        assert!(new_settings(LobbySettings::MAX_ANTE_AMOUNT + 1).is_err());
+        assert!(new_settings(LobbySettings::DEFAULT_ANTE_AMOUNT).is_ok());
+    }
+}
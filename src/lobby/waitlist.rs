@@ -0,0 +1,154 @@
+use std::collections::VecDeque;
+use chrono::{DateTime, TimeDelta, Utc};
+use uuid::Uuid;
+
+use crate::common::error::AppError;
+use crate::common::time::chrono_to_prost_timestamp;
+use crate::service::proto;
+
+/// A seat held for the head of the waitlist until accepted, declined or expired.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SeatOffer {
+    pub player_id: Uuid,
+    pub seat: u8,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl SeatOffer {
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= Utc::now()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct LobbyWaitlistPosition {
+    pub lobby_id: Uuid,
+    pub position: u32,
+    pub seat_offer: Option<SeatOffer>,
+}
+
+/// Queue of players waiting for a seat. Waitlists are transient and don't survive restarts.
+#[derive(Clone, Debug, Default)]
+pub struct LobbyWaitlist {
+    player_ids: VecDeque<Uuid>,
+    seat_offer: Option<SeatOffer>,
+}
+
+impl LobbyWaitlist {
+    const SEAT_OFFER_SECONDS: i64 = 30;
+
+    pub fn push(&mut self, player_id: Uuid) -> Result<(), AppError> {
+        if self.contains(&player_id) {
+            return Err(AppError::precondition_failed("Player already on the waitlist!"));
+        }
+        self.player_ids.push_back(player_id);
+        Ok(())
+    }
+
+    /// Also withdraws any seat offered to the player.
+    pub fn remove(&mut self, player_id: &Uuid) -> bool {
+        if self.seat_offer.as_ref().is_some_and(|offer| offer.player_id == *player_id) {
+            self.seat_offer = None;
+            return true;
+        }
+        let len = self.player_ids.len();
+        self.player_ids.retain(|waiting_player_id| waiting_player_id != player_id);
+        self.player_ids.len() != len
+    }
+
+    pub fn contains(&self, player_id: &Uuid) -> bool {
+        self.get_position(player_id).is_some()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.player_ids.is_empty() && self.seat_offer.is_none()
+    }
+
+    pub fn len(&self) -> usize {
+        self.player_ids.len() + usize::from(self.seat_offer.is_some())
+    }
+
+    /// 0 while holding a seat offer, otherwise 1-based.
+    pub fn get_position(&self, player_id: &Uuid) -> Option<u32> {
+        if self.seat_offer.as_ref().is_some_and(|offer| offer.player_id == *player_id) {
+            return Some(0);
+        }
+        self.player_ids
+            .iter()
+            .position(|waiting_player_id| waiting_player_id == player_id)
+            .map(|position| position as u32 + 1)
+    }
+
+    pub fn get_seat_offer(&self) -> Option<&SeatOffer> {
+        self.seat_offer.as_ref()
+    }
+
+    /// Offers the seat to the next player in line, unless an offer is already pending.
+    pub fn offer_seat(&mut self, seat: u8) -> Option<SeatOffer> {
+        if self.seat_offer.is_some() {
+            return None;
+        }
+        let player_id = self.player_ids.pop_front()?;
+        let offer = SeatOffer {
+            player_id,
+            seat,
+            expires_at: Utc::now() + TimeDelta::seconds(Self::SEAT_OFFER_SECONDS),
+        };
+        self.seat_offer = Some(offer.clone());
+        Some(offer)
+    }
+
+    /// Hands out the offered seat, provided the offer is still valid.
+    pub fn take_seat_offer(&mut self, player_id: &Uuid) -> Result<SeatOffer, AppError> {
+        match self.seat_offer.take() {
+            Some(offer) if offer.player_id == *player_id && !offer.is_expired() => Ok(offer),
+            offer => {
+                self.seat_offer = offer; // expired offers are left to expire_seat_offer()
+                Err(AppError::precondition_failed("No pending seat offer!"))
+            },
+        }
+    }
+
+    /// Players letting their offer expire drop off the waitlist.
+    pub fn expire_seat_offer(&mut self, offer: &SeatOffer) -> bool {
+        if self.seat_offer.as_ref() != Some(offer) || !offer.is_expired() {
+            return false;
+        }
+        self.seat_offer = None;
+        true
+    }
+}
+
+impl From<LobbyWaitlistPosition> for proto::LobbyWaitlistPosition {
+    fn from(position: LobbyWaitlistPosition) -> Self {
+        proto::LobbyWaitlistPosition {
+            lobby_id: position.lobby_id.to_string(),
+            position: position.position,
+            seat_offer: position.seat_offer.map(|offer| proto::lobby_waitlist_position::SeatOffer {
+                seat: offer.seat.into(),
+                expires_at: Some(chrono_to_prost_timestamp(offer.expires_at)),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn offer_seat() {
+        let mut waitlist = LobbyWaitlist::default();
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        waitlist.push(a).unwrap();
+        waitlist.push(b).unwrap();
+        assert!(waitlist.push(a).is_err());
+        let offer = waitlist.offer_seat(2).unwrap();
+        assert_eq!((offer.player_id, offer.seat), (a, 2));
+        assert!(waitlist.offer_seat(3).is_none());
+        assert_eq!((waitlist.get_position(&a), waitlist.get_position(&b)), (Some(0), Some(1)));
+        assert!(waitlist.take_seat_offer(&b).is_err());
+        assert!(!waitlist.expire_seat_offer(&offer));
+        assert_eq!(waitlist.take_seat_offer(&a).unwrap(), offer);
+        assert_eq!(waitlist.len(), 1);
+    }
+}
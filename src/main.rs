@@ -1,8 +1,19 @@
+mod achievement;
+mod chat;
 mod common;
+mod friend;
 mod game;
+mod history;
+mod leaderboard;
+mod ledger;
 mod lobby;
+mod notification;
 mod r#match;
 mod player;
+mod quickplay;
+mod rating;
+mod stats;
+mod storage;
 mod types;
 
 /// This module contains RusticPoker's top-level gRPC service implementation.
@@ -12,15 +23,30 @@ mod service;
 /// These data types contain processed output state data, not internal state data.
 pub mod output;
 
-use std::env;
+use std::{env, fs};
+use std::sync::Arc;
 use tonic::transport::Server;
+use chat::WordListProfanityFilter;
+use game::GameService;
 use service::{RusticPokerService, RusticPokerServer, FILE_DESCRIPTOR_SET};
+use storage::FileStorage;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let port = env::var("GRPC_PORT").unwrap_or(String::from("55100"));
     let address = format!("0.0.0.0:{}", port).parse().unwrap();
-    let rustic_poker_service = RusticPokerService::default();
+    let storage_path = env::var("STORAGE_PATH").unwrap_or(String::from("rustic_poker_data"));
+    let storage = Arc::new(FileStorage::open(storage_path)?);
+    let mut game_service = GameService::restore(storage).await?;
+    if let Ok(profanity_filter_path) = env::var("PROFANITY_FILTER_PATH") {
+        let words = fs::read_to_string(profanity_filter_path)?;
+        let profanity_filter = WordListProfanityFilter::new(words.lines());
+        game_service = game_service.with_profanity_filter(Arc::new(profanity_filter));
+    }
+    if let Ok(announcement) = env::var("ANNOUNCEMENT") {
+        game_service.announce(announcement);
+    }
+    let rustic_poker_service = RusticPokerService::new(game_service);
     let reflection_service = tonic_reflection::server::Builder::configure()
         .register_encoded_file_descriptor_set(FILE_DESCRIPTOR_SET)
         .build_v1()
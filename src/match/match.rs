@@ -1,13 +1,18 @@
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::sync::Arc;
-use rand::{rng, seq::SliceRandom};
-use tokio::sync::{broadcast, RwLock};
+use std::time::Duration;
+use tokio::sync::{broadcast, watch, RwLock};
 use uuid::Uuid;
 
+use crate::common::error::AppError;
 use crate::game::GamePhase;
-use crate::player::Player;
+use crate::history::{HandHistory, HandHistoryRegistry};
+use crate::lobby::LobbySettings;
+use crate::player::{Player, PlayerRegistry};
+use crate::stats::PlayerHud;
 use crate::types::hand::Hand;
 use crate::output::GameStateBroadcaster;
+use crate::storage::{self, MatchSnapshot, PendingSettlement, Storage};
 
 #[derive(Clone, Debug)]
 pub struct PlayerState {
@@ -22,27 +27,34 @@ pub(crate) struct Match {
     pub(crate) lobby_id: Uuid,
     pub(crate) player_ids: HashSet<Uuid>,
     pub(crate) phase: Arc<RwLock<GamePhase>>,
+    finished: Arc<watch::Sender<bool>>, // flipped once settled
 }
 
 impl Match {
+    const SETTLE_ATTEMPTS: u32 = 3;
+    const SETTLE_RETRY_DELAY: Duration = Duration::from_secs(1); // multiplied by the attempt
+
     pub fn new(
         lobby_id: Uuid,
+        lobby_name: String,
         state_broadcaster: GameStateBroadcaster,
         rpc_action_broadcaster: broadcast::Sender<()>,
-        players: HashSet<Player>,
-        ante_amount: u64,
+        storage: Arc<dyn Storage>,
+        players: MatchStartPlayers,
+        settings: &LobbySettings,
     ) -> Self {
         let match_id = Uuid::new_v4();
 
-        let players = MatchStartPlayers::new(players);
         let player_ids = players.player_credits.keys().cloned().collect();
 
+        let history = HandHistory::new(match_id, lobby_id, lobby_name, settings, &players);
         let phase = GamePhase::new(
-            match_id,
+            history,
             state_broadcaster,
             rpc_action_broadcaster,
+            storage,
             players,
-            ante_amount,
+            settings,
         );
 
         Match {
@@ -50,42 +62,201 @@ impl Match {
             lobby_id,
             player_ids,
             phase: Arc::new(RwLock::new(phase)),
+            finished: Arc::new(watch::Sender::new(false)),
+        }
+    }
+
+    pub fn restore(
+        state_broadcaster: GameStateBroadcaster,
+        storage: Arc<dyn Storage>,
+        snapshot: MatchSnapshot,
+    ) -> Self {
+        let match_id = snapshot.match_id;
+        let lobby_id = snapshot.lobby_id;
+        let phase = GamePhase::restore(state_broadcaster, storage, snapshot);
+        let player_ids = phase.get_table().player_ids.clone();
+
+        Match {
+            match_id,
+            lobby_id,
+            player_ids,
+            phase: Arc::new(RwLock::new(phase)),
+            finished: Arc::new(watch::Sender::new(false)),
         }
     }
 
-    pub async fn play_poker(&mut self, rpc_action_receiver: broadcast::Receiver<()>,) {
+    pub async fn play_poker(
+        &mut self,
+        rpc_action_receiver: broadcast::Receiver<()>,
+        player_registry: Arc<RwLock<PlayerRegistry>>,
+        hand_history_registry: Arc<RwLock<HandHistoryRegistry>>,
+    ) {
+        self.spawn_progress(rpc_action_receiver, player_registry, hand_history_registry, false);
+    }
+
+    /// Picks a restored match back up from its snapshot phase.
+    pub async fn resume_poker(
+        &mut self,
+        player_registry: Arc<RwLock<PlayerRegistry>>,
+        hand_history_registry: Arc<RwLock<HandHistoryRegistry>>,
+    ) {
+        let rpc_action_receiver = self.phase.read().await.subscribe_rpc_actions();
+        self.spawn_progress(rpc_action_receiver, player_registry, hand_history_registry, true);
+    }
+
+    fn spawn_progress(
+        &self,
+        rpc_action_receiver: broadcast::Receiver<()>,
+        player_registry: Arc<RwLock<PlayerRegistry>>,
+        hand_history_registry: Arc<RwLock<HandHistoryRegistry>>,
+        resumed: bool,
+    ) {
         let phase_arc = self.phase.clone();
+        let finished = self.finished.clone();
         tokio::spawn(async move {
-            GamePhase::progress(phase_arc, rpc_action_receiver).await;
+            GamePhase::progress(phase_arc.clone(), rpc_action_receiver, resumed).await;
+            Self::settle(phase_arc, player_registry, hand_history_registry).await;
+            finished.send_replace(true);
         });
     }
+
+    /// Resolves once the match has been played out and settled.
+    pub async fn wait_finished(&self) {
+        let mut finished = self.finished.subscribe();
+        let _ = finished.wait_for(|finished| *finished).await;
+    }
+
+    /// Settles the match's credit movements back into the players' bankrolls.<br />
+    /// The hand history only gets recorded for matches that got played out.<br />
+    /// Settlements that keep failing get stored as pending, to be settled on the next boot.
+    async fn settle(
+        phase_arc: Arc<RwLock<GamePhase>>,
+        player_registry: Arc<RwLock<PlayerRegistry>>,
+        hand_history_registry: Arc<RwLock<HandHistoryRegistry>>,
+    ) {
+        let (storage, match_id, transactions, mut history, played_out) = {
+            let phase_r = phase_arc.read().await;
+            let table = phase_r.get_table();
+            (phase_r.get_storage(), table.get_match_id(), table.transactions.clone(), table.history.clone(), phase_r.is_played_out())
+        };
+        let mut attempt = 1;
+        loop {
+            let result = {
+                let mut player_registry_w = player_registry.write().await;
+                player_registry_w.settle_match(match_id, transactions.clone()).await
+            };
+            match result {
+                Ok(()) => break,
+                Err(err) if attempt < Self::SETTLE_ATTEMPTS => {
+                    eprintln!("Settling match ({match_id}) failed (attempt {attempt}), retrying: {err}");
+                    tokio::time::sleep(Self::SETTLE_RETRY_DELAY * attempt).await;
+                    attempt += 1;
+                },
+                Err(err) => {
+                    eprintln!("Settling match ({match_id}) failed, leaving it pending for the next boot: {err}");
+                    history.finish();
+                    let history = played_out.then_some(history);
+                    let settlement = PendingSettlement { match_id, transactions, history };
+                    let result = storage::run_blocking(&storage, move |storage| storage.store_pending_settlement(&settlement)).await;
+                    if let Err(err) = result {
+                        eprintln!("Storing match ({match_id}) pending settlement failed, its settlement is lost: {err}");
+                    }
+                    return;
+                },
+            }
+        }
+        if !played_out {
+            return; // aborted
+        }
+        history.finish();
+        let mut hand_history_registry_w = hand_history_registry.write().await;
+        if let Err(err) = hand_history_registry_w.add_hand_history(history).await {
+            eprintln!("Recording match ({match_id}) hand history failed: {err}");
+        }
+    }
+
+    /// Settles an interrupted match that can't be resumed, refunding every pot contribution.<br />
+    /// Matches interrupted after their pots got paid out get settled as they are instead.
+    pub async fn settle_interrupted(snapshot: MatchSnapshot, player_registry: &mut PlayerRegistry) -> Result<(), AppError> {
+        let mut table = snapshot.poker_phase.get_table().clone();
+        if !snapshot.poker_phase.is_played_out() {
+            table.refund_pot_contributions();
+        }
+        player_registry.settle_match(snapshot.match_id, table.transactions).await
+    }
+
+    /// Settles a match whose settlement failed before, returning its hand history to be recorded, if played out.
+    pub async fn settle_pending(settlement: PendingSettlement, player_registry: &mut PlayerRegistry) -> Result<Option<HandHistory>, AppError> {
+        player_registry.settle_match(settlement.match_id, settlement.transactions).await?;
+        Ok(settlement.history)
+    }
 }
 
 pub(crate) struct MatchStartPlayers {
     pub(crate) ordered_player_queue: VecDeque<Uuid>, // dealer = 0
     pub(crate) player_credits: HashMap<Uuid, u64>,
+    pub(crate) player_names: HashMap<Uuid, String>,
+    pub(crate) player_seats: HashMap<Uuid, u8>,
     pub(crate) dealer_id: Uuid,
+    pub(crate) player_huds: HashMap<Uuid, PlayerHud>, // empty unless the lobby enables HUDs
 }
 
 impl MatchStartPlayers {
-    pub fn new(players: HashSet<Player>) -> Self {
-        // This ctor assumes unordered play queue.
-        // Implement new_from_game() shifting queue by 1.
+    /// Orders the table clockwise by seat, starting off at the dealer's seat.
+    pub fn new(
+        players: HashSet<Player>,
+        seats: &BTreeMap<u8, Uuid>,
+        dealer_seat: u8,
+        player_huds: HashMap<Uuid, PlayerHud>,
+    ) -> Self {
+        let player_credits = players
+            .iter()
+            .map(|player| (player.player_id, player.total_credits))
+            .collect::<HashMap<Uuid, u64>>();
+
+        let player_names = players
+            .iter()
+            .map(|player| (player.player_id, player.player_name.clone()))
+            .collect();
 
-        let (mut player_vec, player_credits): (Vec<Uuid>, HashMap<Uuid, u64>) = players
+        let player_seats: HashMap<Uuid, u8> = seats
             .iter()
-            .map(|player| (player.player_id, (player.player_id, player.total_credits)))
-            .unzip();
+            .filter(|(_, player_id)| player_credits.contains_key(player_id))
+            .map(|(seat, player_id)| (*player_id, *seat))
+            .collect();
 
-        let mut rng = rng();
-        player_vec.shuffle(&mut rng);
-        let ordered_player_queue: VecDeque<Uuid> = player_vec.into();
-        let dealer_id = ordered_player_queue.iter().next().unwrap().clone();
+        let ordered_player_queue: VecDeque<Uuid> = seats
+            .range(dealer_seat..)
+            .chain(seats.range(..dealer_seat))
+            .map(|(_, player_id)| *player_id)
+            .filter(|player_id| player_credits.contains_key(player_id))
+            .collect();
+        let dealer_id = *ordered_player_queue.front().unwrap();
 
         MatchStartPlayers {
             ordered_player_queue,
             player_credits,
+            player_names,
+            player_seats,
             dealer_id,
+            player_huds,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn seat_order() {
+        let players: Vec<Player> = (0..3).map(|_| Player::register().0).collect();
+        let seats = BTreeMap::from([(1, players[0].player_id), (4, players[1].player_id), (6, players[2].player_id)]);
+        let start_players = MatchStartPlayers::new(players.iter().cloned().collect(), &seats, 4, HashMap::new());
+        assert_eq!(start_players.dealer_id, players[1].player_id);
+        assert_eq!(
+            start_players.ordered_player_queue,
+            VecDeque::from([players[1].player_id, players[2].player_id, players[0].player_id]),
+        );
+        assert_eq!(start_players.player_seats[&players[2].player_id], 6);
+    }
+}
@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, RwLock};
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use uuid::Uuid;
+
+use super::Notification;
+
+/// A channel per subscribed player, plus one carrying announcements to everyone.<br />
+/// Notifications are transient, except for the latest announcement being repeated to new subscribers.
+#[derive(Debug)]
+pub(crate) struct NotificationHub {
+    player_broadcasters: Mutex<HashMap<Uuid, broadcast::Sender<Notification>>>,
+    announcement_broadcaster: broadcast::Sender<Notification>,
+    announcement: RwLock<Option<Notification>>,
+}
+
+impl Default for NotificationHub {
+    fn default() -> Self {
+        let (announcement_broadcaster, _) = broadcast::channel(Self::ANNOUNCEMENT_CHANNEL_CAPACITY);
+        NotificationHub {
+            player_broadcasters: Mutex::default(),
+            announcement_broadcaster,
+            announcement: RwLock::default(),
+        }
+    }
+}
+
+impl NotificationHub {
+    const PLAYER_CHANNEL_CAPACITY: usize = 100;
+    const ANNOUNCEMENT_CHANNEL_CAPACITY: usize = 10;
+
+    /// Players without any subscribers get their channel dropped.
+    pub fn publish(&self, notification: Notification) {
+        let Some(recipient_id) = notification.recipient_id else {
+            let _ = self.announcement_broadcaster.send(notification); // no subscribers is fine
+            return;
+        };
+        if let Ok(mut player_broadcasters) = self.player_broadcasters.lock() {
+            let Some(player_broadcaster) = player_broadcasters.get(&recipient_id) else {
+                return;
+            };
+            if player_broadcaster.send(notification).is_err() {
+                player_broadcasters.remove(&recipient_id);
+            }
+        }
+    }
+
+    pub fn announce(&self, text: String) {
+        let notification = Notification::announcement(text);
+        if let Ok(mut announcement) = self.announcement.write() {
+            *announcement = Some(notification.clone());
+        }
+        self.publish(notification);
+    }
+
+    pub fn get_announcement(&self) -> Option<Notification> {
+        self.announcement.read().ok().and_then(|announcement| announcement.clone())
+    }
+
+    /// Also drops the channels of any players no longer subscribed.
+    pub fn subscribe(&self, player_id: Uuid) -> NotificationReceiver {
+        let mut player_broadcasters = self.player_broadcasters.lock().unwrap();
+        player_broadcasters.retain(|_, player_broadcaster| player_broadcaster.receiver_count() > 0);
+        let player_receiver = player_broadcasters
+            .entry(player_id)
+            .or_insert_with(|| broadcast::channel(Self::PLAYER_CHANNEL_CAPACITY).0)
+            .subscribe();
+        NotificationReceiver {
+            player_receiver,
+            announcement_receiver: self.announcement_broadcaster.subscribe(),
+        }
+    }
+}
+
+/// Receives a player's own notifications along with announcements.
+#[derive(Debug)]
+pub(crate) struct NotificationReceiver {
+    player_receiver: broadcast::Receiver<Notification>,
+    announcement_receiver: broadcast::Receiver<Notification>,
+}
+
+impl NotificationReceiver {
+    /// Lagging behind on either channel gets reported the same way.
+    pub async fn recv(&mut self) -> Result<Notification, RecvError> {
+        tokio::select! {
+            notification = self.player_receiver.recv() => notification,
+            notification = self.announcement_receiver.recv() => notification,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::NotificationKind;
+    #[test]
+    fn publish() {
+        let hub = NotificationHub::default();
+        let (player_id, other_player_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut receiver = hub.subscribe(player_id);
+        let mut other_receiver = hub.subscribe(other_player_id);
+        hub.publish(Notification::new(player_id, NotificationKind::Kicked { lobby_id: Uuid::new_v4() }));
+        hub.announce(String::from("Maintenance at noon"));
+        assert!(matches!(receiver.player_receiver.try_recv().unwrap().get_kind(), NotificationKind::Kicked { .. }));
+        assert!(other_receiver.player_receiver.try_recv().is_err());
+        assert!(matches!(other_receiver.announcement_receiver.try_recv().unwrap().get_kind(), NotificationKind::Announcement(_)));
+        assert!(matches!(hub.get_announcement().unwrap().get_kind(), NotificationKind::Announcement(_)));
+
+        drop(receiver);
+        hub.publish(Notification::new(player_id, NotificationKind::Kicked { lobby_id: Uuid::new_v4() }));
+        assert!(!hub.player_broadcasters.lock().unwrap().contains_key(&player_id));
+    }
+}
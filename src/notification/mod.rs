@@ -0,0 +1,6 @@
+mod hub;
+mod notification;
+mod proto;
+
+pub(crate) use hub::NotificationHub;
+pub(crate) use notification::{Notification, NotificationKind};
@@ -0,0 +1,54 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::achievement::AchievementUnlock;
+use crate::friend::FriendPresence;
+use crate::lobby::LobbyInvitation;
+
+/// Something meant for a specific player, regardless of the lobby they're in, if any.
+#[derive(Clone, Debug)]
+pub(crate) struct Notification {
+    pub(super) recipient_id: Option<Uuid>, // none for everyone
+    pub(super) kind: NotificationKind,
+    pub(super) timestamp: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum NotificationKind {
+    LobbyInvitation(LobbyInvitation),
+    FriendPresence {
+        friend_id: Uuid,
+        presence: FriendPresence,
+    },
+    Kicked {
+        lobby_id: Uuid,
+    },
+    AchievementUnlocked(AchievementUnlock),
+    BalanceChanged {
+        balance: u64,
+        delta: i64,
+    },
+    Announcement(String),
+}
+
+impl Notification {
+    pub fn new(recipient_id: Uuid, kind: NotificationKind) -> Self {
+        Notification {
+            recipient_id: Some(recipient_id),
+            kind,
+            timestamp: Utc::now(),
+        }
+    }
+
+    pub fn announcement(text: String) -> Self {
+        Notification {
+            recipient_id: None,
+            kind: NotificationKind::Announcement(text),
+            timestamp: Utc::now(),
+        }
+    }
+
+    pub fn get_kind(&self) -> &NotificationKind {
+        &self.kind
+    }
+}
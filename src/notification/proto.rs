@@ -0,0 +1,40 @@
+use crate::common::time::chrono_to_prost_timestamp;
+use crate::service::proto;
+use super::{Notification, NotificationKind};
+
+impl From<Notification> for proto::Notification {
+    fn from(notification: Notification) -> Self {
+        let notification_proto = match notification.kind {
+            NotificationKind::LobbyInvitation(invitation) => {
+                proto::notification::Notification::LobbyInvitation(invitation.into())
+            },
+            NotificationKind::FriendPresence { friend_id, presence } => {
+                let (presence, lobby_id): (proto::friend::FriendPresence, _) = presence.into();
+                proto::notification::Notification::FriendPresence(proto::notification::FriendPresenceChanged {
+                    player_id: friend_id.to_string(),
+                    presence: presence as i32,
+                    lobby_id: lobby_id.map(|lobby_id| lobby_id.to_string()),
+                })
+            },
+            NotificationKind::Kicked { lobby_id } => {
+                proto::notification::Notification::Kicked(proto::notification::Kicked {
+                    lobby_id: lobby_id.to_string(),
+                })
+            },
+            NotificationKind::AchievementUnlocked(unlock) => {
+                proto::notification::Notification::AchievementUnlocked(unlock.into())
+            },
+            NotificationKind::BalanceChanged { balance, delta } => {
+                proto::notification::Notification::BalanceChanged(proto::notification::BalanceChanged {
+                    balance,
+                    delta,
+                })
+            },
+            NotificationKind::Announcement(text) => proto::notification::Notification::Announcement(text),
+        };
+        proto::Notification {
+            timestamp: Some(chrono_to_prost_timestamp(notification.timestamp)),
+            notification: Some(notification_proto),
+        }
+    }
+}
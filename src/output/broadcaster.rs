@@ -1,4 +1,5 @@
 use std::sync::Arc;
+use uuid::Uuid;
 use tokio::sync::{broadcast, RwLock};
 use tokio::sync::broadcast::Receiver;
 
@@ -6,25 +7,33 @@ use crate::common::error::AppError;
 use crate::lobby::Lobby;
 use crate::output::structs::{LobbyState, MatchState};
 use crate::player::PlayerRegistry;
-use super::GameState;
+use super::{GameEvent, GameState};
+use super::subscriber::{SubscriberLag, SubscriberLagTracker, SubscriberLags};
 
 #[derive(Clone, Debug)]
 pub(crate) struct GameStateBroadcaster {
     lobby_arc: Option<Arc<RwLock<Lobby>>>,
     broadcaster: broadcast::Sender<Option<GameState>>,
+    event_broadcaster: broadcast::Sender<Option<GameEvent>>,
     player_registry: Arc<RwLock<PlayerRegistry>>,
+    subscriber_lags: SubscriberLags,
 }
 
 impl GameStateBroadcaster {
+    const EVENT_CHANNEL_CAPACITY: usize = 100; // single actions may produce several events
+
     pub fn new(
         broadcast_channel_capacity: usize,
         player_registry: Arc<RwLock<PlayerRegistry>>,
     ) -> Self {
         let (broadcaster, _) = broadcast::channel(broadcast_channel_capacity);
+        let (event_broadcaster, _) = broadcast::channel(Self::EVENT_CHANNEL_CAPACITY);
         GameStateBroadcaster {
             lobby_arc: None,
             broadcaster,
+            event_broadcaster,
             player_registry,
+            subscriber_lags: SubscriberLags::default(),
         }
     }
 
@@ -36,10 +45,36 @@ impl GameStateBroadcaster {
         self.broadcaster.subscribe()
     }
 
-    pub async fn publish(&self) {
+    /// The returned tracker keeps the subscription listed until dropped.
+    pub fn track_subscriber(&self, player_id: Uuid) -> SubscriberLagTracker {
+        SubscriberLagTracker::new(player_id, self.subscriber_lags.clone())
+    }
+
+    pub fn get_subscriber_lags(&self) -> Vec<SubscriberLag> {
+        self.subscriber_lags
+            .lock()
+            .map(|subscriber_lags| subscriber_lags.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn subscribe_events(&self) -> Receiver<Option<GameEvent>> {
+        self.event_broadcaster.subscribe()
+    }
+
+    pub fn publish_event(&self, event: GameEvent) {
+        // Ignore errors caused by no active receivers...
+        let _ = self.event_broadcaster.send(Some(event));
+    }
+
+    /// Returns the published state, for the sake of replay recording.
+    pub async fn publish(&self) -> Option<GameState> {
         // TODO: drop player repository and figure out inner broadcaster error handling
-        if let Err(err) = self._publish(None).await {
-            eprintln!("{}", err);
+        match self._publish(None).await {
+            Ok(state) => Some(state),
+            Err(err) => {
+                eprintln!("{}", err);
+                None
+            },
         }
     }
 
@@ -51,17 +86,17 @@ impl GameStateBroadcaster {
         }
     }
 
-    async fn _publish(&self, lobby: Option<&Lobby>) -> Result<(), AppError> {
+    async fn _publish(&self, lobby: Option<&Lobby>) -> Result<GameState, AppError> {
         let state = self.build_state(lobby).await?;
         let _ = self.broadcaster
-            .send(Some(state));
+            .send(Some(state.clone()));
             // Ignore errors caused by no active receivers...
             // .map(|_| ())
             // .map_err(|err| {
             //     eprintln!("{}", err);
             //     AppError::internal("GameStateBroadcaster.publish() call failed!")
             // });
-        Ok(())
+        Ok(state)
     }
 
     // pub fn disconnect(&self) -> Result<(), AppError> {
@@ -0,0 +1,182 @@
+use std::collections::HashSet;
+
+use crate::service::proto;
+use proto::game_state_update::{GameStateDelta, GameStateDeltas, Update};
+use proto::game_state_update::game_state_delta::{ActivePlayersChanged, Delta, PlayerActed, PlayerCardsChanged};
+
+/// Encodes a viewer's consecutive game states as a full snapshot followed by typed deltas.<br />
+/// Anything beyond in-match progression (new match, seating changes) is sent as a fresh snapshot.
+#[derive(Debug, Default)]
+pub(crate) struct GameStateUpdateEncoder {
+    sequence: u64,
+    last_state: Option<proto::GameState>,
+}
+
+impl GameStateUpdateEncoder {
+    /// Returns None if nothing changed since the previous update.
+    pub fn encode(&mut self, mut state: proto::GameState) -> Option<proto::GameStateUpdate> {
+        normalize(&mut state);
+        let update = match self.last_state.as_ref() {
+            Some(last_state) if !requires_snapshot(last_state, &state) => {
+                let deltas = diff(last_state, &state);
+                if deltas.is_empty() {
+                    self.last_state = Some(state);
+                    return None;
+                }
+                Update::Deltas(GameStateDeltas {
+                    timestamp: state.timestamp,
+                    deltas,
+                })
+            },
+            _ => Update::Snapshot(Box::new(state.clone())),
+        };
+        self.last_state = Some(state);
+        Some(self.next_update(update))
+    }
+
+    /// Re-sends the latest state in full, if any.
+    pub fn snapshot(&mut self) -> Option<proto::GameStateUpdate> {
+        let state = self.last_state.clone()?;
+        Some(self.next_update(Update::Snapshot(Box::new(state))))
+    }
+
+    fn next_update(&mut self, update: Update) -> proto::GameStateUpdate {
+        self.sequence += 1;
+        proto::GameStateUpdate {
+            sequence: self.sequence,
+            update: Some(update),
+        }
+    }
+}
+
+/// Sorts repeated fields built out of unordered collections, so that equality checks hold up.
+fn normalize(state: &mut proto::GameState) {
+    if let Some(lobby_state) = state.lobby_state.as_mut() {
+        lobby_state.players.sort_by(|a, b| a.player_id.cmp(&b.player_id));
+    }
+    if let Some(match_state) = state.match_state.as_mut() {
+        match_state.active_player_ids.sort();
+    }
+}
+
+fn requires_snapshot(prev: &proto::GameState, next: &proto::GameState) -> bool {
+    if prev.self_player_id != next.self_player_id || prev.is_spectator != next.is_spectator {
+        return true;
+    }
+    match (prev.match_state.as_ref(), next.match_state.as_ref()) {
+        (None, None) => false,
+        (Some(prev), Some(next)) => {
+            let prev_player_ids: HashSet<&String> = prev.player_info.keys().collect();
+            let next_player_ids: HashSet<&String> = next.player_info.keys().collect();
+            prev.match_id != next.match_id
+                || prev_player_ids != next_player_ids
+                || prev.table_players_order != next.table_players_order
+        },
+        _ => true,
+    }
+}
+
+fn diff(prev: &proto::GameState, next: &proto::GameState) -> Vec<GameStateDelta> {
+    let mut deltas = Vec::new();
+    if prev.lobby_state != next.lobby_state {
+        if let Some(lobby_state) = next.lobby_state.clone() {
+            deltas.push(Delta::LobbyChanged(lobby_state));
+        }
+    }
+
+    if let (Some(prev), Some(next)) = (prev.match_state.as_ref(), next.match_state.as_ref()) {
+        for (player_id, next_info) in next.player_info.iter() {
+            let prev_info = &prev.player_info[player_id];
+            let prev_bet_amount = prev.player_bet_amounts.get(player_id);
+            let next_bet_amount = next.player_bet_amounts.get(player_id);
+            if prev_info.starting_credits != next_info.starting_credits
+                || prev_info.remaining_credits != next_info.remaining_credits
+                || prev_info.pot_credits != next_info.pot_credits
+                || prev_bet_amount != next_bet_amount {
+                deltas.push(Delta::PlayerActed(PlayerActed {
+                    player_id: player_id.clone(),
+                    starting_credits: next_info.starting_credits,
+                    remaining_credits: next_info.remaining_credits,
+                    pot_credits: next_info.pot_credits.clone(),
+                    bet_amount: next_bet_amount.cloned(),
+                }));
+            }
+            if prev_info.hand_cards != next_info.hand_cards {
+                deltas.push(Delta::PlayerCardsChanged(PlayerCardsChanged {
+                    player_id: player_id.clone(),
+                    hand_cards: next_info.hand_cards.clone(),
+                }));
+            }
+        }
+
+        for (pot_id, next_pot) in next.credit_pots.iter() {
+            if prev.credit_pots.get(pot_id) != Some(next_pot) {
+                deltas.push(Delta::PotChanged(next_pot.clone()));
+            }
+        }
+        for pot_id in prev.credit_pots.keys() {
+            if !next.credit_pots.contains_key(pot_id) {
+                deltas.push(Delta::PotRemoved(pot_id.clone()));
+            }
+        }
+
+        if prev.poker_phase != next.poker_phase {
+            if let Some(poker_phase) = next.poker_phase.clone() {
+                deltas.push(Delta::PhaseChanged(poker_phase));
+            }
+        }
+
+        if prev.active_player_ids != next.active_player_ids {
+            deltas.push(Delta::ActivePlayersChanged(ActivePlayersChanged {
+                active_player_ids: next.active_player_ids.clone(),
+            }));
+        }
+    }
+
+    deltas
+        .into_iter()
+        .map(|delta| GameStateDelta { delta: Some(delta) })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use proto::game_state::MatchState;
+    use proto::game_state::match_state::MatchStatePlayerPublicInfo;
+    fn build_state(match_id: &str, remaining_credits: u64) -> proto::GameState {
+        let player_info = MatchStatePlayerPublicInfo {
+            player_id: String::from("player"),
+            remaining_credits,
+            ..Default::default()
+        };
+        proto::GameState {
+            match_state: Some(MatchState {
+                match_id: match_id.to_string(),
+                player_info: HashMap::from([(String::from("player"), player_info)]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+    #[test]
+    fn encode() {
+        let mut encoder = GameStateUpdateEncoder::default();
+        let update = encoder.encode(build_state("first", 100)).unwrap();
+        assert!(matches!(update.update, Some(Update::Snapshot(_))));
+        assert!(encoder.encode(build_state("first", 100)).is_none());
+        let update = encoder.encode(build_state("first", 90)).unwrap();
+        assert_eq!(update.sequence, 2);
+        let Some(Update::Deltas(deltas)) = update.update else {
+            panic!("Expected deltas!");
+        };
+        assert!(matches!(
+            deltas.deltas[..],
+            [GameStateDelta { delta: Some(Delta::PlayerActed(PlayerActed { remaining_credits: 90, .. })) }]
+        ));
+        let update = encoder.encode(build_state("second", 90)).unwrap();
+        assert!(matches!(update.update, Some(Update::Snapshot(_))));
+        assert_eq!(encoder.snapshot().unwrap().sequence, 4);
+    }
+}
@@ -0,0 +1,86 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::achievement::AchievementUnlock;
+use crate::chat::ChatMessage;
+use crate::game::PokerPhaseKind;
+use crate::history::{HandHistoryAction, HandHistoryEvent};
+
+/// Something that just happened in a match, as opposed to the GameState it resulted in.
+#[derive(Clone, Debug)]
+pub(crate) struct GameEvent {
+    pub(super) match_id: Option<Uuid>, // none for lobby-wide events
+    pub(super) kind: GameEventKind,
+    pub(super) timestamp: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum GameEventKind {
+    Action(HandHistoryAction),
+    PhaseChanged(PokerPhaseKind),
+    ChatMessage(ChatMessage),
+    AchievementUnlocked(AchievementUnlock),
+}
+
+impl GameEvent {
+    pub fn from_history_event(match_id: Uuid, event: HandHistoryEvent) -> Self {
+        GameEvent {
+            match_id: Some(match_id),
+            kind: GameEventKind::Action(event.action),
+            timestamp: event.timestamp,
+        }
+    }
+
+    pub fn phase_changed(match_id: Uuid, phase: PokerPhaseKind) -> Self {
+        GameEvent {
+            match_id: Some(match_id),
+            kind: GameEventKind::PhaseChanged(phase),
+            timestamp: Utc::now(),
+        }
+    }
+
+    pub fn chat_message(message: ChatMessage) -> Self {
+        GameEvent {
+            match_id: None,
+            timestamp: message.timestamp,
+            kind: GameEventKind::ChatMessage(message),
+        }
+    }
+
+    pub fn achievement_unlocked(unlock: AchievementUnlock) -> Self {
+        GameEvent {
+            match_id: None,
+            timestamp: unlock.unlocked_at,
+            kind: GameEventKind::AchievementUnlocked(unlock),
+        }
+    }
+
+    /// Sender of a chat message event, used to honor the viewer's mute list.
+    pub fn get_chat_sender_id(&self) -> Option<Uuid> {
+        match &self.kind {
+            GameEventKind::ChatMessage(message) => Some(message.sender_id),
+            _ => None,
+        }
+    }
+
+    /// Achievement unlocks only reach the unlocking player.
+    pub fn is_visible_to(&self, viewer_id: &Uuid) -> bool {
+        match &self.kind {
+            GameEventKind::AchievementUnlocked(unlock) => unlock.player_id == *viewer_id,
+            _ => true,
+        }
+    }
+
+    /// Card lists only ever reach their owners, so spectators never get to see any.
+    pub fn as_viewer(&self, viewer_id: &Uuid) -> Self {
+        let mut event = self.clone();
+        if let GameEventKind::Action(action) = &mut event.kind {
+            action.redact(viewer_id);
+        }
+        event
+    }
+
+    pub fn get_timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+}
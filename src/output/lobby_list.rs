@@ -0,0 +1,317 @@
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use uuid::Uuid;
+
+use crate::common::error::AppError;
+use crate::lobby::GameMode;
+use super::structs::{LobbyInfoPublic, LobbyStatus};
+
+#[derive(Clone, Debug)]
+pub(crate) enum LobbyListEvent {
+    Created(LobbyInfoPublic),
+    Updated(LobbyInfoPublic),
+    Removed(Uuid),
+}
+
+/// Empty lists match anything. Private lobbies never match.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct LobbyFilter {
+    /// Case-insensitive substring.
+    pub name: Option<String>,
+    pub statuses: Vec<LobbyStatus>,
+    pub is_joinable: Option<bool>,
+    pub game_modes: Vec<GameMode>,
+    pub min_ante_amount: Option<u64>,
+    pub max_ante_amount: Option<u64>,
+    pub min_available_seats: Option<u32>,
+}
+
+impl LobbyFilter {
+    pub fn matches(&self, lobby: &LobbyInfoPublic) -> bool {
+        !lobby.settings.is_private
+            && self.name.as_ref().is_none_or(|name| lobby.name.to_lowercase().contains(&name.to_lowercase()))
+            && (self.statuses.is_empty() || self.statuses.contains(&lobby.status))
+            && self.is_joinable.is_none_or(|is_joinable| is_joinable == lobby.is_joinable)
+            && (self.game_modes.is_empty() || self.game_modes.contains(&lobby.settings.get_game_mode()))
+            && self.min_ante_amount.is_none_or(|amount| lobby.settings.ante_amount >= amount)
+            && self.max_ante_amount.is_none_or(|amount| lobby.settings.ante_amount <= amount)
+            && self.min_available_seats.is_none_or(|seats| lobby.get_available_seats() >= seats)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum LobbySortKey {
+    #[default]
+    Name,
+    PlayerCount,
+    AnteAmount,
+    AvailableSeats,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum LobbySortValue {
+    Text(String),
+    Number(u64),
+}
+
+impl LobbySortKey {
+    fn get_value(&self, lobby: &LobbyInfoPublic) -> LobbySortValue {
+        match self {
+            LobbySortKey::Name => LobbySortValue::Text(lobby.name.to_lowercase()),
+            LobbySortKey::PlayerCount => LobbySortValue::Number(lobby.player_count.into()),
+            LobbySortKey::AnteAmount => LobbySortValue::Number(lobby.settings.ante_amount),
+            LobbySortKey::AvailableSeats => LobbySortValue::Number(lobby.get_available_seats().into()),
+        }
+    }
+}
+
+/// Points right past the last lobby of a page, so pages stay consistent while lobbies come and go.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct LobbyCursor {
+    sort_key: LobbySortKey,
+    descending: bool,
+    value: LobbySortValue,
+    lobby_id: Uuid,
+}
+
+impl Display for LobbyCursor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let value = match &self.value {
+            LobbySortValue::Text(text) => text.clone(),
+            LobbySortValue::Number(number) => number.to_string(),
+        };
+        write!(f, "{}.{}.{}.{}", self.sort_key as u8, self.descending as u8, self.lobby_id, value)
+    }
+}
+
+impl TryFrom<&str> for LobbyCursor {
+    type Error = AppError;
+
+    fn try_from(cursor: &str) -> Result<Self, Self::Error> {
+        let invalid_cursor = || AppError::invalid_request("Invalid lobby cursor!");
+        let [sort_key, descending, lobby_id, value] = cursor.splitn(4, '.').collect::<Vec<_>>()[..] else {
+            return Err(invalid_cursor());
+        };
+        let sort_key = match sort_key {
+            "0" => LobbySortKey::Name,
+            "1" => LobbySortKey::PlayerCount,
+            "2" => LobbySortKey::AnteAmount,
+            "3" => LobbySortKey::AvailableSeats,
+            _ => return Err(invalid_cursor()),
+        };
+        let descending = match descending {
+            "0" => false,
+            "1" => true,
+            _ => return Err(invalid_cursor()),
+        };
+        let lobby_id = Uuid::parse_str(lobby_id).map_err(|_| invalid_cursor())?;
+        let value = match sort_key {
+            LobbySortKey::Name => LobbySortValue::Text(value.to_string()),
+            _ => LobbySortValue::Number(value.parse().map_err(|_| invalid_cursor())?),
+        };
+        Ok(LobbyCursor { sort_key, descending, value, lobby_id })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct LobbyQuery {
+    pub filter: LobbyFilter,
+    pub sort_key: LobbySortKey,
+    pub descending: bool,
+    pub cursor: Option<LobbyCursor>,
+    pub page_size: usize,
+}
+
+#[derive(Debug)]
+pub(crate) struct LobbyPage {
+    pub lobbies: Vec<LobbyInfoPublic>,
+    pub next_cursor: Option<LobbyCursor>,
+}
+
+impl LobbyQuery {
+    pub const DEFAULT_PAGE_SIZE: usize = 20;
+    pub const MAX_PAGE_SIZE: usize = 100;
+
+    pub fn new(
+        filter: LobbyFilter,
+        sort_key: LobbySortKey,
+        descending: bool,
+        cursor: Option<LobbyCursor>,
+        page_size: Option<usize>,
+    ) -> Result<Self, AppError> {
+        if let Some(cursor) = cursor.as_ref() {
+            if cursor.sort_key != sort_key || cursor.descending != descending {
+                return Err(AppError::invalid_request("Lobby cursor doesn't match the requested sort order!"));
+            }
+        }
+        let page_size = page_size.unwrap_or(Self::DEFAULT_PAGE_SIZE);
+        if page_size == 0 || page_size > Self::MAX_PAGE_SIZE {
+            return Err(
+                AppError::invalid_request(
+                    format!("Page size must range from 1 to {}!", Self::MAX_PAGE_SIZE)
+                )
+            );
+        }
+        Ok(LobbyQuery { filter, sort_key, descending, cursor, page_size })
+    }
+
+    pub fn paginate<'a>(&self, lobbies: impl Iterator<Item = &'a LobbyInfoPublic>) -> LobbyPage {
+        let mut lobbies: Vec<_> = lobbies
+            .filter(|lobby| self.filter.matches(lobby))
+            .map(|lobby| (self.sort_key.get_value(lobby), lobby))
+            .filter(|(value, lobby)| {
+                self.cursor.as_ref().is_none_or(|cursor| {
+                    self.cmp((value, &lobby.lobby_id), (&cursor.value, &cursor.lobby_id)) == Ordering::Greater
+                })
+            })
+            .collect();
+        lobbies.sort_by(|(a_value, a), (b_value, b)| self.cmp((a_value, &a.lobby_id), (b_value, &b.lobby_id)));
+
+        let has_more = lobbies.len() > self.page_size;
+        lobbies.truncate(self.page_size);
+        let next_cursor = lobbies
+            .last()
+            .filter(|_| has_more)
+            .map(|(value, lobby)| LobbyCursor {
+                sort_key: self.sort_key,
+                descending: self.descending,
+                value: value.clone(),
+                lobby_id: lobby.lobby_id,
+            });
+        LobbyPage {
+            lobbies: lobbies.into_iter().map(|(_, lobby)| lobby.clone()).collect(),
+            next_cursor,
+        }
+    }
+
+    /// Lobby ids break ties, always in ascending order.
+    fn cmp(&self, (a_value, a_id): (&LobbySortValue, &Uuid), (b_value, b_id): (&LobbySortValue, &Uuid)) -> Ordering {
+        let ordering = match self.descending {
+            true => b_value.cmp(a_value),
+            false => a_value.cmp(b_value),
+        };
+        ordering.then_with(|| a_id.cmp(b_id))
+    }
+}
+
+/// A subscriber's filtered view on the lobby list.<br />
+/// Lobbies entering the filter are announced as created, the ones leaving it as removed.
+#[derive(Debug)]
+pub(crate) struct LobbyListView {
+    filter: LobbyFilter,
+    visible_lobby_ids: HashSet<Uuid>,
+}
+
+impl LobbyListView {
+    pub fn new(filter: LobbyFilter) -> Self {
+        LobbyListView {
+            filter,
+            visible_lobby_ids: HashSet::new(),
+        }
+    }
+
+    pub fn apply(&mut self, event: LobbyListEvent) -> Option<LobbyListEvent> {
+        match event {
+            LobbyListEvent::Created(lobby) | LobbyListEvent::Updated(lobby) => self.upsert(lobby),
+            LobbyListEvent::Removed(lobby_id) => self.remove(lobby_id),
+        }
+    }
+
+    /// Brings the view in line with the complete lobby list, e.g. initially or after lagging behind.
+    pub fn resync(&mut self, lobbies: Vec<LobbyInfoPublic>) -> Vec<LobbyListEvent> {
+        let lobby_ids: HashSet<Uuid> = lobbies
+            .iter()
+            .map(|lobby| lobby.lobby_id)
+            .collect();
+        let removed_lobby_ids: Vec<Uuid> = self.visible_lobby_ids
+            .difference(&lobby_ids)
+            .copied()
+            .collect();
+        let mut events: Vec<_> = removed_lobby_ids
+            .into_iter()
+            .filter_map(|lobby_id| self.remove(lobby_id))
+            .collect();
+        events.extend(lobbies.into_iter().filter_map(|lobby| self.upsert(lobby)));
+        events
+    }
+
+    fn upsert(&mut self, lobby: LobbyInfoPublic) -> Option<LobbyListEvent> {
+        let lobby_id = lobby.lobby_id;
+        match (self.filter.matches(&lobby), self.visible_lobby_ids.contains(&lobby_id)) {
+            (true, true) => Some(LobbyListEvent::Updated(lobby)),
+            (true, false) => {
+                self.visible_lobby_ids.insert(lobby_id);
+                Some(LobbyListEvent::Created(lobby))
+            },
+            (false, true) => self.remove(lobby_id),
+            (false, false) => None,
+        }
+    }
+
+    fn remove(&mut self, lobby_id: Uuid) -> Option<LobbyListEvent> {
+        self.visible_lobby_ids
+            .remove(&lobby_id)
+            .then_some(LobbyListEvent::Removed(lobby_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lobby::LobbySettings;
+    use super::super::structs::PlayerPublicInfo;
+    fn build_lobby(lobby_id: Uuid, status: LobbyStatus) -> LobbyInfoPublic {
+        LobbyInfoPublic {
+            lobby_id,
+            name: String::from("Lobby"),
+            host_player: PlayerPublicInfo {
+                player_id: Uuid::new_v4(),
+                player_name: String::from("Host"),
+            },
+            player_count: 1,
+            status,
+            settings: LobbySettings::default(),
+            is_joinable: status == LobbyStatus::Idle,
+            has_password: false,
+        }
+    }
+    #[test]
+    fn apply() {
+        let lobby_id = Uuid::new_v4();
+        let mut view = LobbyListView::new(LobbyFilter {
+            is_joinable: Some(true),
+            ..Default::default()
+        });
+        let event = view.apply(LobbyListEvent::Created(build_lobby(lobby_id, LobbyStatus::Idle)));
+        assert!(matches!(event, Some(LobbyListEvent::Created(_))));
+        let event = view.apply(LobbyListEvent::Updated(build_lobby(lobby_id, LobbyStatus::Idle)));
+        assert!(matches!(event, Some(LobbyListEvent::Updated(_))));
+        let event = view.apply(LobbyListEvent::Updated(build_lobby(lobby_id, LobbyStatus::InGame)));
+        assert!(matches!(event, Some(LobbyListEvent::Removed(id)) if id == lobby_id));
+        assert!(view.apply(LobbyListEvent::Removed(lobby_id)).is_none());
+        let events = view.resync(vec![build_lobby(lobby_id, LobbyStatus::Idle)]);
+        assert!(matches!(events[..], [LobbyListEvent::Created(_)]));
+        assert!(matches!(view.resync(Vec::new())[..], [LobbyListEvent::Removed(_)]));
+    }
+    #[test]
+    fn paginate() {
+        let lobbies: Vec<_> = (0..5)
+            .map(|_| build_lobby(Uuid::new_v4(), LobbyStatus::Idle))
+            .collect();
+        let mut query = LobbyQuery::new(LobbyFilter::default(), LobbySortKey::Name, true, None, Some(2)).unwrap();
+        let mut lobby_ids = Vec::new();
+        loop {
+            let page = query.paginate(lobbies.iter());
+            lobby_ids.extend(page.lobbies.iter().map(|lobby| lobby.lobby_id));
+            let Some(cursor) = page.next_cursor else {
+                break;
+            };
+            query.cursor = Some(LobbyCursor::try_from(cursor.to_string().as_str()).unwrap());
+        }
+        let mut expected_ids: Vec<_> = lobbies.iter().map(|lobby| lobby.lobby_id).collect();
+        expected_ids.sort();
+        assert_eq!(lobby_ids, expected_ids);
+        assert!(LobbyQuery::new(LobbyFilter::default(), LobbySortKey::AnteAmount, true, query.cursor, None).is_err());
+    }
+}
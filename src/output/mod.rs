@@ -1,20 +1,29 @@
 mod broadcaster;
+mod delta;
+mod event;
+mod lobby_list;
 mod proto;
 mod structs;
+mod subscriber;
 
 pub(crate) use broadcaster::GameStateBroadcaster;
-pub(crate) use structs::{GameState, GameStateAsPlayer, LobbyInfoPublic, MatchStatePhaseSpecifics, MatchStatePhaseSpecificsBetting, MatchStatePhaseSpecificsDrawing, DrawingStageDiscarding, HandCard};
+pub(crate) use delta::GameStateUpdateEncoder;
+pub(crate) use event::GameEvent;
+pub(crate) use lobby_list::{LobbyFilter, LobbyListEvent, LobbyListView, LobbyPage, LobbyQuery};
+pub(crate) use structs::{GameState, GameStateAsPlayer, LobbyInfoPublic, MatchStatePhaseSpecifics, MatchStatePhaseSpecificsBetting, MatchStatePhaseSpecificsDrawing, MatchStatePhaseSpecificsShowdown, ShowdownPotDistribution, DrawingStageDiscarding, HandCard};
+pub(crate) use subscriber::SubscriberLag;
 
 use std::collections::HashMap;
 use std::sync::Arc;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
 use crate::common::error::AppError;
-use crate::lobby::Lobby;
+use crate::lobby::{Lobby, LobbySettings};
 use crate::player::{Player, PlayerRegistry};
 use crate::r#match::Match;
+use crate::stats::PlayerHud;
 use structs::*;
 
 impl GameState {
@@ -30,6 +39,15 @@ impl GameState {
     }
 
     pub fn as_player(&self, player_id: Uuid) -> Result<GameStateAsPlayer, AppError> {
+        self.as_viewer(player_id, StateViewer::Player)
+    }
+
+    /// Spectators aren't seated, so all hole cards stay hidden until showdown.
+    pub fn as_spectator(&self, spectator_id: Uuid) -> Result<GameStateAsPlayer, AppError> {
+        self.as_viewer(spectator_id, StateViewer::Spectator)
+    }
+
+    fn as_viewer(&self, viewer_id: Uuid, viewer: StateViewer) -> Result<GameStateAsPlayer, AppError> {
         let lobby_state = self.lobby_state.clone();
         let mut own_match_state = None;
         if let Some(match_state) = self.match_state.as_ref() {
@@ -37,27 +55,38 @@ impl GameState {
                 MatchStatePhaseSpecifics::Showdown(_) => true,
                 _ => false,
             };
-            own_match_state = Some(match_state.as_player(&player_id, is_showdown)?.clone())
+            let seated_player_id = match viewer {
+                StateViewer::Player => Some(&viewer_id),
+                StateViewer::Spectator => None,
+            };
+            own_match_state = Some(match_state.as_viewer(&viewer_id, seated_player_id, is_showdown)?)
         };
 
         let state = GameStateAsPlayer {
-            self_player_id: player_id,
+            self_player_id: viewer_id,
             lobby_state,
             match_state: own_match_state,
             timestamp: self.timestamp,
+            is_spectator: viewer == StateViewer::Spectator,
         };
         Ok(state)
     }
 }
 
+impl GameStateAsPlayer {
+    pub fn get_timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+}
+
 impl LobbyState {
     pub async fn from_lobby(lobby: Lobby, player_registry_arc: Arc<RwLock<PlayerRegistry>>) -> Result<LobbyState, AppError> {
         let players = Self::get_players(&lobby, player_registry_arc).await?;
         let status: LobbyStatus = (&lobby).into();
         let game_acceptance =
             lobby
-                .player_ids
-                .iter()
+                .seats
+                .values()
                 .map(|player_id| {
                     let is_accepted = lobby.game_acceptance
                         .as_ref()
@@ -65,6 +94,10 @@ impl LobbyState {
                     (*player_id, is_accepted)
                 })
                 .collect::<HashMap<_, _>>();
+        let player_seats = lobby.seats
+            .iter()
+            .map(|(seat, player_id)| (*player_id, *seat))
+            .collect();
         Ok(
             LobbyState {
                 lobby_id: lobby.lobby_id,
@@ -74,12 +107,16 @@ impl LobbyState {
                 status,
                 game_acceptance,
                 settings: lobby.settings,
+                spectator_count: lobby.spectator_ids.len() as u32,
+                player_seats,
+                waitlist_count: lobby.waitlist.len() as u32,
+                matchmaking_deadline: lobby.matchmaking_deadline,
             }
         )
     }
 
     async fn get_players(lobby: &Lobby, player_registry_arc: Arc<RwLock<PlayerRegistry>>) -> Result<Vec<PlayerPublicInfo>, AppError> {
-        let player_ids = lobby.player_ids.clone();
+        let player_ids = lobby.get_player_ids();
         let player_registry_r = player_registry_arc.read().await;
         let players = player_registry_r.get_players(&player_ids).await?;
         let players = players
@@ -91,14 +128,20 @@ impl LobbyState {
 }
 
 impl MatchState {
-    pub fn as_player(&self, player_id: &Uuid, is_showdown: bool) -> Result<MatchStateAsPlayer, AppError> {
+    /// Spectating viewers have no seat, thus no bets of their own.
+    fn as_viewer(
+        &self,
+        viewer_id: &Uuid,
+        seated_player_id: Option<&Uuid>,
+        reveal_cards: bool,
+    ) -> Result<MatchStateAsPlayer, AppError> {
         let player_info = self.player_info
             .values()
-            .map(|player_info| (player_info.player_id, player_info.as_player(player_id, !is_showdown)))
+            .map(|player_info| (player_info.player_id, player_info.as_player(viewer_id, !reveal_cards)))
             .collect();
 
         let poker_phase_specifics = self.poker_phase_specifics
-            .as_player(player_id)?;
+            .as_player(seated_player_id)?;
 
         let state = MatchStateAsPlayer {
             match_id: self.match_id,
@@ -108,21 +151,20 @@ impl MatchState {
             poker_phase_specifics,
             table_players_order: self.table_players_order.clone(),
             active_player_ids: self.active_player_ids.clone(),
+            player_seats: self.player_seats.clone(),
+            dealer_id: self.dealer_id,
         };
         Ok(state)
     }
 }
 
 impl MatchStatePhaseSpecifics {
-    fn as_player(&self, player_id: &Uuid) -> Result<MatchStatePhaseSpecificsAsPlayer, AppError> {
+    fn as_player(&self, player_id: Option<&Uuid>) -> Result<MatchStatePhaseSpecificsAsPlayer, AppError> {
         match self {
             MatchStatePhaseSpecifics::Ante => Ok(MatchStatePhaseSpecificsAsPlayer::Ante),
             MatchStatePhaseSpecifics::Dealing => Ok(MatchStatePhaseSpecificsAsPlayer::Dealing),
             MatchStatePhaseSpecifics::FirstBetting(phase) => {
-                let self_bet_amount = phase.player_bet_amounts
-                    .get(player_id)
-                    .ok_or(AppError::internal(format!("Player ({player_id}) missing")))?
-                    .clone();
+                let self_bet_amount = phase.get_self_bet_amount(player_id)?;
                 Ok(MatchStatePhaseSpecificsAsPlayer::FirstBetting(
                     MatchStatePhaseSpecificsBettingAsPlayer {
                         highest_bet_amount: phase.highest_bet_amount,
@@ -142,10 +184,7 @@ impl MatchStatePhaseSpecifics {
                 Ok(MatchStatePhaseSpecificsAsPlayer::Drawing(phase_as_player))
             },
             MatchStatePhaseSpecifics::SecondBetting(phase) => {
-                let self_bet_amount = phase.player_bet_amounts
-                    .get(player_id)
-                    .ok_or(AppError::internal(format!("Player ({player_id}) missing")))?
-                    .clone();
+                let self_bet_amount = phase.get_self_bet_amount(player_id)?;
                 Ok(MatchStatePhaseSpecificsAsPlayer::SecondBetting(
                     MatchStatePhaseSpecificsBettingAsPlayer {
                         highest_bet_amount: phase.highest_bet_amount,
@@ -166,13 +205,27 @@ impl MatchStatePhaseSpecifics {
     }
 }
 
+impl MatchStatePhaseSpecificsBetting {
+    fn get_self_bet_amount(&self, player_id: Option<&Uuid>) -> Result<Option<u64>, AppError> {
+        let Some(player_id) = player_id else {
+            return Ok(None);
+        };
+        let self_bet_amount = self.player_bet_amounts
+            .get(player_id)
+            .ok_or(AppError::internal(format!("Player ({player_id}) missing")))?;
+        Ok(Some(*self_bet_amount))
+    }
+}
+
 impl GamePlayerPublicInfo {
     pub async fn from_match(r#match: &Match) -> HashMap<Uuid, Self> {
         let mut game_phase_w = r#match.phase.write().await;
-        let player_credits = game_phase_w
-            .get_table()
-            .player_credits
-            .clone();
+        let table = game_phase_w.get_table();
+        let player_credits = table.player_credits.clone();
+        let player_huds: HashMap<Uuid, Option<PlayerHud>> = player_credits
+            .keys()
+            .map(|player_id| (*player_id, table.get_player_hud(player_id)))
+            .collect();
 
         let player_cards = &game_phase_w
             .get_player_cards();
@@ -190,6 +243,7 @@ impl GamePlayerPublicInfo {
                     player_name: "Anonymous".to_string(), // TODO
                     credits: self_credits,
                     hand_cards,
+                    hud: player_huds[&player_id].clone(),
                 };
                 (player_id, info)
             })
@@ -218,11 +272,13 @@ impl GamePlayerPublicInfo {
             }
         };
 
+        let hud = player_info.hud.filter(|_| *player_id != player_info.player_id); // opponents only
         GamePlayerPublicInfoAsPlayer {
             player_id: player_info.player_id,
             player_name: player_info.player_name,
             credits: player_info.credits,
             hand_cards,
+            hud,
         }
     }
 }
@@ -254,7 +310,10 @@ impl MatchState {
         let credit_pots = game_phase_w.get_table().credit_pots.clone();
         let player_bet_amounts = game_phase_w.get_player_bet_amounts();
         let poker_phase_specifics = game_phase_w.get_phase_specifics();
-        let table_players_order = game_phase_w.get_table_players_order();
+        let table_seats = game_phase_w.get_table().get_table_seats();
+        let table_players_order = table_seats.iter().map(|(_, player_id)| *player_id).collect();
+        let player_seats = table_seats.into_iter().map(|(seat, player_id)| (player_id, seat)).collect();
+        let dealer_id = Some(game_phase_w.get_table().get_dealer_id());
         let active_player_ids = game_phase_w.can_player_act()
             .into_iter()
             .filter(|(_, active)| *active)
@@ -269,14 +328,36 @@ impl MatchState {
             poker_phase_specifics,
             table_players_order,
             active_player_ids,
+            player_seats,
+            dealer_id,
         }
     }
 }
 
+impl LobbyInfoPublic {
+    pub fn get_lobby_id(&self) -> Uuid {
+        self.lobby_id
+    }
+
+    pub fn get_available_seats(&self) -> u32 {
+        u32::from(self.settings.max_players).saturating_sub(self.player_count)
+    }
+
+    pub fn get_settings(&self) -> &LobbySettings {
+        &self.settings
+    }
+
+    /// Joinable by anyone, without password or invite.
+    pub fn is_open_to_public(&self) -> bool {
+        self.is_joinable && !self.has_password && !self.settings.is_private
+    }
+}
+
 impl From<Lobby> for LobbyInfoPublic {
     fn from(lobby: Lobby) -> Self {
         let status = LobbyStatus::from(&lobby);
         let is_joinable = lobby.is_joinable();
+        let has_password = lobby.has_password();
         let host_player = PlayerPublicInfo {
             player_id: lobby.host_player_id,
             player_name: lobby.host_player_name,
@@ -285,10 +366,11 @@ impl From<Lobby> for LobbyInfoPublic {
             lobby_id: lobby.lobby_id,
             name: lobby.name,
             host_player,
-            player_count: lobby.player_ids.len().try_into().unwrap(),
+            player_count: lobby.seats.len().try_into().unwrap(),
             status,
             settings: lobby.settings,
             is_joinable,
+            has_password,
         }
     }
 }
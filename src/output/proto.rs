@@ -1,8 +1,18 @@
 use std::collections::HashMap;
-use chrono::{DateTime, Utc};
 
+use crate::common::error::AppError;
+use crate::common::time::chrono_to_prost_timestamp;
+use crate::game::PokerPhaseKind;
+use crate::history::HandHistoryEvent;
+use crate::lobby::GameMode;
 use crate::service::proto;
+use super::event::{GameEvent, GameEventKind};
+use super::lobby_list::{LobbyCursor, LobbyFilter, LobbyListEvent, LobbyPage, LobbyQuery, LobbySortKey};
 use super::structs::*;
+use super::subscriber::SubscriberLag;
+
+use proto::game_event::phase_changed::PokerPhaseKind as ProtoPokerPhaseKind;
+use proto::hand_history::hand_history_event::{betting_action::BettingActionKind, Event as HistoryEvent};
 
 impl From<PlayerPublicInfo> for proto::PlayerPublicInfo {
     fn from(info: PlayerPublicInfo) -> Self {
@@ -23,6 +33,16 @@ impl From<LobbyStatus> for proto::LobbyStatus {
     }
 }
 
+impl From<proto::LobbyStatus> for LobbyStatus {
+    fn from(status: proto::LobbyStatus) -> Self {
+        match status {
+            proto::LobbyStatus::Idle => LobbyStatus::Idle,
+            proto::LobbyStatus::Matchmaking => LobbyStatus::Matchmaking,
+            proto::LobbyStatus::InGame => LobbyStatus::InGame,
+        }
+    }
+}
+
 impl From<GamePlayerPublicInfoAsPlayer> for proto::game_state::match_state::MatchStatePlayerPublicInfo {
     fn from(info: GamePlayerPublicInfoAsPlayer) -> Self {
         let starting_credits = info.credits.get_starting_credits();
@@ -42,6 +62,7 @@ impl From<GamePlayerPublicInfoAsPlayer> for proto::game_state::match_state::Matc
             remaining_credits: info.credits.remaining_credits,
             pot_credits,
             hand_cards,
+            hud: info.hud.map(proto::PlayerHud::from),
         }
     }
 }
@@ -66,7 +87,12 @@ impl From<MatchStateAsPlayer> for proto::game_state::MatchState {
         let poker_phase = state.poker_phase_specifics.into();
         let table_players_order = state.table_players_order
             .into_iter()
-            .map(|player_id| player_id.into())
+            .enumerate()
+            .map(|(index, player_id)| proto::game_state::match_state::TableSeat {
+                seat: state.player_seats.get(&player_id).copied().unwrap_or(index as u8).into(),
+                player_id: player_id.to_string(),
+                is_dealer: state.dealer_id == Some(player_id),
+            })
             .collect();
         let active_player_ids = state.active_player_ids
             .into_iter()
@@ -91,6 +117,7 @@ impl From<GameStateAsPlayer> for proto::GameState {
             lobby_state: Some(state.lobby_state.into()),
             match_state: state.match_state.map(|state| state.into()),
             timestamp: Some(chrono_to_prost_timestamp(state.timestamp)),
+            is_spectator: state.is_spectator,
         }
     }
 }
@@ -114,6 +141,13 @@ impl From<LobbyState> for proto::LobbyState {
             status: status as i32,
             game_acceptance,
             settings: Some(state.settings.into()),
+            spectator_count: state.spectator_count,
+            player_seats: state.player_seats
+                .into_iter()
+                .map(|(player_id, seat)| (player_id.to_string(), seat.into()))
+                .collect(),
+            waitlist_count: state.waitlist_count,
+            matchmaking_deadline: state.matchmaking_deadline.map(chrono_to_prost_timestamp),
         }
     }
 }
@@ -128,10 +162,102 @@ impl From<LobbyInfoPublic> for proto::LobbyInfoPublic {
             status: lobby_info.status as i32,
             settings: Some(lobby_info.settings.into()),
             is_joinable: lobby_info.is_joinable,
+            has_password: lobby_info.has_password,
+        }
+    }
+}
+
+impl TryFrom<proto::WatchLobbiesRequest> for LobbyFilter {
+    type Error = AppError;
+
+    fn try_from(request: proto::WatchLobbiesRequest) -> Result<Self, Self::Error> {
+        Ok(LobbyFilter {
+            statuses: parse_lobby_statuses(request.statuses, "WatchLobbiesRequest.statuses")?,
+            is_joinable: request.is_joinable,
+            game_modes: parse_game_modes(request.game_modes, "WatchLobbiesRequest.game_modes")?,
+            ..Default::default()
+        })
+    }
+}
+
+impl TryFrom<proto::GetLobbiesRequest> for LobbyQuery {
+    type Error = AppError;
+
+    fn try_from(request: proto::GetLobbiesRequest) -> Result<Self, Self::Error> {
+        let filter = LobbyFilter {
+            name: request.name,
+            statuses: parse_lobby_statuses(request.statuses, "GetLobbiesRequest.statuses")?,
+            is_joinable: request.joinable_only.then_some(true),
+            game_modes: parse_game_modes(request.game_modes, "GetLobbiesRequest.game_modes")?,
+            min_ante_amount: request.min_ante_amount,
+            max_ante_amount: request.max_ante_amount,
+            min_available_seats: request.min_available_seats,
+        };
+        let sort_key = proto::get_lobbies_request::LobbySortKey::try_from(request.sort_key)
+            .map_err(|_| AppError::invalid_request("Invalid GetLobbiesRequest.sort_key value!"))?
+            .into();
+        let cursor = request.cursor
+            .as_deref()
+            .map(LobbyCursor::try_from)
+            .transpose()?;
+        let page_size = request.page_size.map(|page_size| page_size as usize);
+        LobbyQuery::new(filter, sort_key, request.descending, cursor, page_size)
+    }
+}
+
+impl From<proto::get_lobbies_request::LobbySortKey> for LobbySortKey {
+    fn from(sort_key: proto::get_lobbies_request::LobbySortKey) -> Self {
+        match sort_key {
+            proto::get_lobbies_request::LobbySortKey::Name => LobbySortKey::Name,
+            proto::get_lobbies_request::LobbySortKey::PlayerCount => LobbySortKey::PlayerCount,
+            proto::get_lobbies_request::LobbySortKey::AnteAmount => LobbySortKey::AnteAmount,
+            proto::get_lobbies_request::LobbySortKey::AvailableSeats => LobbySortKey::AvailableSeats,
+        }
+    }
+}
+
+impl From<LobbyPage> for proto::GetLobbiesResponse {
+    fn from(page: LobbyPage) -> Self {
+        proto::GetLobbiesResponse {
+            lobbies: page.lobbies.into_iter().map(|lobby| lobby.into()).collect(),
+            next_cursor: page.next_cursor.map(|cursor| cursor.to_string()),
         }
     }
 }
 
+fn parse_lobby_statuses(statuses: Vec<i32>, field: &str) -> Result<Vec<LobbyStatus>, AppError> {
+    statuses
+        .into_iter()
+        .map(|status| {
+            proto::LobbyStatus::try_from(status)
+                .map(LobbyStatus::from)
+                .map_err(|_| AppError::invalid_request(format!("Invalid {field} value!")))
+        })
+        .collect()
+}
+
+fn parse_game_modes(game_modes: Vec<i32>, field: &str) -> Result<Vec<GameMode>, AppError> {
+    game_modes
+        .into_iter()
+        .map(|game_mode| {
+            proto::lobby_settings::GameMode::try_from(game_mode)
+                .map(GameMode::from)
+                .map_err(|_| AppError::invalid_request(format!("Invalid {field} value!")))
+        })
+        .collect()
+}
+
+impl From<LobbyListEvent> for proto::LobbyListEvent {
+    fn from(event: LobbyListEvent) -> Self {
+        let event = match event {
+            LobbyListEvent::Created(lobby_info) => proto::lobby_list_event::Event::Created(lobby_info.into()),
+            LobbyListEvent::Updated(lobby_info) => proto::lobby_list_event::Event::Updated(lobby_info.into()),
+            LobbyListEvent::Removed(lobby_id) => proto::lobby_list_event::Event::Removed(lobby_id.to_string()),
+        };
+        proto::LobbyListEvent { event: Some(event) }
+    }
+}
+
 impl From<MatchStatePhaseSpecificsAsPlayer> for proto::game_state::PokerPhase {
     fn from(phase: MatchStatePhaseSpecificsAsPlayer) -> Self {
         let phase = match phase {
@@ -141,7 +267,7 @@ impl From<MatchStatePhaseSpecificsAsPlayer> for proto::game_state::PokerPhase {
                 proto::game_state::poker_phase::Phase::FirstBetting(
                     proto::game_state::poker_phase::PokerPhaseBetting {
                         highest_bet_amount: Some(phase.highest_bet_amount),
-                        self_bet_amount: Some(phase.self_bet_amount),
+                        self_bet_amount: phase.self_bet_amount,
                     }
                 )
             }
@@ -156,7 +282,7 @@ impl From<MatchStatePhaseSpecificsAsPlayer> for proto::game_state::PokerPhase {
                 proto::game_state::poker_phase::Phase::SecondBetting(
                     proto::game_state::poker_phase::PokerPhaseBetting {
                         highest_bet_amount: Some(phase.highest_bet_amount),
-                        self_bet_amount: Some(phase.self_bet_amount),
+                        self_bet_amount: phase.self_bet_amount,
                     }
                 )
             }
@@ -195,6 +321,7 @@ impl From<ShowdownPotDistribution> for proto::game_state::poker_phase::poker_pha
             player_ids: distribution.player_ids.into_iter().map(|player_id| player_id.into()).collect(),
             total_credits: distribution.total_credits,
             credits_per_winner: distribution.credits_per_winner,
+            raked_credits: distribution.raked_credits,
         }
     }
 }
@@ -233,9 +360,81 @@ impl From<HandCard> for proto::game_state::match_state::match_state_player_publi
     }
 }
 
-fn chrono_to_prost_timestamp(dt: DateTime<Utc>) -> prost_types::Timestamp {
-    prost_types::Timestamp {
-        seconds: dt.timestamp(),
-        nanos: dt.timestamp_subsec_nanos() as i32,
+impl From<GameEvent> for proto::GameEvent {
+    fn from(event: GameEvent) -> Self {
+        let event_proto = match event.kind {
+            GameEventKind::Action(action) => {
+                // Actions share their payloads with hand history events
+                let history_event: proto::hand_history::HandHistoryEvent = HandHistoryEvent {
+                    action,
+                    timestamp: event.timestamp,
+                }.into();
+                history_event.event.map(|history_event| match history_event {
+                    HistoryEvent::Ante(ante) => proto::game_event::Event::PlayerAnted(ante),
+                    HistoryEvent::BettingAction(action) if action.kind() == BettingActionKind::Fold => {
+                        proto::game_event::Event::PlayerFolded(proto::game_event::PlayerFolded {
+                            player_id: action.player_id,
+                        })
+                    },
+                    HistoryEvent::BettingAction(action) => {
+                        proto::game_event::Event::PlayerBet(proto::game_event::PlayerBet {
+                            player_id: action.player_id,
+                            kind: action.kind,
+                            bet_amount: action.bet_amount,
+                            added_credits: action.added_credits,
+                        })
+                    },
+                    HistoryEvent::CardsDealt(cards) => proto::game_event::Event::CardsDealt(cards),
+                    HistoryEvent::CardsDiscarded(cards) => proto::game_event::Event::CardsDiscarded(cards),
+                    HistoryEvent::CardsDrawn(cards) => proto::game_event::Event::CardsDrawn(cards),
+                    HistoryEvent::HandShown(hand) => proto::game_event::Event::HandShown(hand),
+                    HistoryEvent::PotAwarded(award) => proto::game_event::Event::PotAwarded(award),
+                    HistoryEvent::UncalledBetReturned(award) => proto::game_event::Event::UncalledBetReturned(award),
+                    HistoryEvent::Rake(amount) => proto::game_event::Event::Rake(amount),
+                })
+            },
+            GameEventKind::PhaseChanged(phase) => {
+                let phase: ProtoPokerPhaseKind = phase.into();
+                Some(proto::game_event::Event::PhaseChanged(proto::game_event::PhaseChanged {
+                    phase: phase as i32,
+                }))
+            },
+            GameEventKind::ChatMessage(message) => {
+                Some(proto::game_event::Event::ChatMessage(message.into()))
+            },
+            GameEventKind::AchievementUnlocked(unlock) => {
+                Some(proto::game_event::Event::AchievementUnlocked(unlock.into()))
+            },
+        };
+        proto::GameEvent {
+            match_id: event.match_id.map(|match_id| match_id.to_string()),
+            timestamp: Some(chrono_to_prost_timestamp(event.timestamp)),
+            event: event_proto,
+        }
+    }
+}
+
+impl From<PokerPhaseKind> for ProtoPokerPhaseKind {
+    fn from(phase: PokerPhaseKind) -> Self {
+        match phase {
+            PokerPhaseKind::Ante => ProtoPokerPhaseKind::Ante,
+            PokerPhaseKind::Dealing => ProtoPokerPhaseKind::Dealing,
+            PokerPhaseKind::FirstBetting => ProtoPokerPhaseKind::FirstBetting,
+            PokerPhaseKind::DrawingDiscarding => ProtoPokerPhaseKind::DrawingDiscarding,
+            PokerPhaseKind::DrawingDealing => ProtoPokerPhaseKind::DrawingDealing,
+            PokerPhaseKind::SecondBetting => ProtoPokerPhaseKind::SecondBetting,
+            PokerPhaseKind::Showdown => ProtoPokerPhaseKind::Showdown,
+        }
+    }
+}
+
+impl From<SubscriberLag> for proto::get_state_subscribers_response::StateSubscriber {
+    fn from(lag: SubscriberLag) -> Self {
+        proto::get_state_subscribers_response::StateSubscriber {
+            subscription_id: lag.subscription_id.to_string(),
+            player_id: lag.player_id.to_string(),
+            lag_count: lag.lag_count,
+            skipped_states: lag.skipped_states,
+        }
     }
 }
@@ -1,4 +1,5 @@
 use std::collections::{HashMap, HashSet, VecDeque};
+use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
@@ -7,8 +8,9 @@ use crate::types::hand::HandRank;
 use crate::game::table::{CalculatedPlayerCredits, CreditPot};
 use crate::types::card::Card;
 use crate::types::stateful::StatefulCard;
+use crate::stats::PlayerHud;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(crate) struct GameState {
     pub(super) lobby_state: LobbyState,
     pub(super) match_state: Option<MatchState>,
@@ -21,22 +23,29 @@ pub(crate) struct GameStateAsPlayer {
     pub(super) lobby_state: LobbyState,
     pub(super) match_state: Option<MatchStateAsPlayer>,
     pub(super) timestamp: DateTime<Utc>,
+    pub(super) is_spectator: bool,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum StateViewer {
+    Player,
+    Spectator,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(super) struct PlayerPublicInfo {
     pub(super) player_id: Uuid,
     pub(super) player_name: String,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) enum LobbyStatus {
     Idle,
     Matchmaking,
     InGame,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(super) struct LobbyState {
     pub(super) lobby_id: Uuid,
     pub(super) name: String,
@@ -45,17 +54,29 @@ pub(super) struct LobbyState {
     pub(super) status: LobbyStatus,
     pub(super) game_acceptance: HashMap<Uuid, bool>,
     pub(super) settings: LobbySettings,
+    #[serde(default)]
+    pub(super) spectator_count: u32,
+    #[serde(default)]
+    pub(super) player_seats: HashMap<Uuid, u8>,
+    #[serde(default)]
+    pub(super) waitlist_count: u32,
+    #[serde(default)]
+    pub(super) matchmaking_deadline: Option<DateTime<Utc>>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(super) struct MatchState {
     pub(super) match_id: Uuid,
     pub(super) player_info: HashMap<Uuid, GamePlayerPublicInfo>,
     pub(super) credit_pots: HashMap<Uuid, CreditPot>,
     pub(super) player_bet_amounts: Option<HashMap<Uuid, u64>>,
     pub(super) poker_phase_specifics: MatchStatePhaseSpecifics,
-    pub(super) table_players_order: VecDeque<Uuid>,
+    pub(super) table_players_order: VecDeque<Uuid>, // by seat
     pub(super) active_player_ids: HashSet<Uuid>,
+    #[serde(default)]
+    pub(super) player_seats: HashMap<Uuid, u8>,
+    #[serde(default)]
+    pub(super) dealer_id: Option<Uuid>,
 }
 
 #[derive(Clone, Debug)]
@@ -65,16 +86,20 @@ pub(super) struct MatchStateAsPlayer {
     pub(super) credit_pots: HashMap<Uuid, CreditPot>,
     pub(super) player_bet_amounts: Option<HashMap<Uuid, u64>>,
     pub(super) poker_phase_specifics: MatchStatePhaseSpecificsAsPlayer,
-    pub(super) table_players_order: VecDeque<Uuid>,
+    pub(super) table_players_order: VecDeque<Uuid>, // by seat
     pub(super) active_player_ids: HashSet<Uuid>,
+    pub(super) player_seats: HashMap<Uuid, u8>,
+    pub(super) dealer_id: Option<Uuid>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(super) struct GamePlayerPublicInfo {
     pub(super) player_id: Uuid,
     pub(super) player_name: String,
     pub(super) credits: CalculatedPlayerCredits,
     pub(super) hand_cards: Option<Vec<StatefulCard>>,
+    #[serde(default)]
+    pub(super) hud: Option<PlayerHud>,
 }
 
 #[derive(Clone, Debug)]
@@ -83,8 +108,10 @@ pub(super) struct GamePlayerPublicInfoAsPlayer {
     pub(super) player_name: String,
     pub(super) credits: CalculatedPlayerCredits,
     pub(super) hand_cards: Option<Vec<HandCard>>,
+    pub(super) hud: Option<PlayerHud>,
 }
 
+#[derive(Clone, Debug)]
 pub(crate) struct LobbyInfoPublic {
     pub(super) lobby_id: Uuid,
     pub(super) name: String,
@@ -93,9 +120,10 @@ pub(crate) struct LobbyInfoPublic {
     pub(super) status: LobbyStatus,
     pub(super) settings: LobbySettings,
     pub(super) is_joinable: bool,
+    pub(super) has_password: bool,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(crate) enum MatchStatePhaseSpecifics {
     Ante,
     Dealing,
@@ -105,19 +133,19 @@ pub(crate) enum MatchStatePhaseSpecifics {
     Showdown(MatchStatePhaseSpecificsShowdown),
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(crate) struct MatchStatePhaseSpecificsBetting {
     pub(crate) highest_bet_amount: u64,
     pub(crate) player_bet_amounts: HashMap<Uuid, u64>, // I don't really need this. part of parent struct. could be convenient for mapping tho
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(crate) enum MatchStatePhaseSpecificsDrawing {
     Discarding(DrawingStageDiscarding),
     Dealing,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(crate) struct MatchStatePhaseSpecificsShowdown {
     pub(crate) winning_rank: HandRank,
     pub(crate) winner_ids: HashSet<Uuid>,
@@ -137,7 +165,7 @@ pub(super) enum MatchStatePhaseSpecificsAsPlayer {
 #[derive(Clone, Debug)]
 pub(super) struct MatchStatePhaseSpecificsBettingAsPlayer {
     pub(super) highest_bet_amount: u64,
-    pub(super) self_bet_amount: u64,
+    pub(super) self_bet_amount: Option<u64>, // spectators don't bet
 }
 
 #[derive(Clone, Debug)]
@@ -153,17 +181,18 @@ pub(crate) struct MatchStatePhaseSpecificsShowdownAsPlayer {
     pub(crate) pot_distribution: HashMap<Uuid, ShowdownPotDistribution>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct DrawingStageDiscarding {
     pub(crate) player_discard_count: HashMap<Uuid, u8>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(crate) struct ShowdownPotDistribution {
     pub(crate) pot_id: Uuid,
     pub(crate) player_ids: HashSet<Uuid>,
     pub(crate) total_credits: u64,
     pub(crate) credits_per_winner: u64,
+    pub(crate) raked_credits: u64,
 }
 
 #[derive(Clone, Debug)]
@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+/// Lag bookkeeping of a single WatchState subscription.
+#[derive(Clone, Debug)]
+pub(crate) struct SubscriberLag {
+    pub subscription_id: Uuid,
+    pub player_id: Uuid,
+    pub lag_count: u64,
+    pub skipped_states: u64,
+}
+
+pub(crate) type SubscriberLags = Arc<Mutex<HashMap<Uuid, SubscriberLag>>>;
+
+/// Registers a subscription for as long as it's alive, deregistering it once dropped.
+#[derive(Debug)]
+pub(crate) struct SubscriberLagTracker {
+    subscription_id: Uuid,
+    subscriber_lags: SubscriberLags,
+}
+
+impl SubscriberLagTracker {
+    pub fn new(player_id: Uuid, subscriber_lags: SubscriberLags) -> Self {
+        let subscription_id = Uuid::new_v4();
+        let lag = SubscriberLag {
+            subscription_id,
+            player_id,
+            lag_count: 0,
+            skipped_states: 0,
+        };
+        if let Ok(mut subscriber_lags) = subscriber_lags.lock() {
+            subscriber_lags.insert(subscription_id, lag);
+        }
+        SubscriberLagTracker {
+            subscription_id,
+            subscriber_lags,
+        }
+    }
+
+    /// Returns how many times the subscriber has lagged behind so far.
+    pub fn record_lag(&self, skipped_states: u64) -> u64 {
+        let Ok(mut subscriber_lags) = self.subscriber_lags.lock() else {
+            return 0;
+        };
+        match subscriber_lags.get_mut(&self.subscription_id) {
+            Some(lag) => {
+                lag.lag_count += 1;
+                lag.skipped_states += skipped_states;
+                lag.lag_count
+            },
+            None => 0,
+        }
+    }
+}
+
+impl Drop for SubscriberLagTracker {
+    fn drop(&mut self) {
+        if let Ok(mut subscriber_lags) = self.subscriber_lags.lock() {
+            subscriber_lags.remove(&self.subscription_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn record_lag() {
+        let subscriber_lags = SubscriberLags::default();
+        let tracker = SubscriberLagTracker::new(Uuid::new_v4(), subscriber_lags.clone());
+        assert_eq!(tracker.record_lag(3), 1);
+        assert_eq!(tracker.record_lag(2), 2);
+        let lag = subscriber_lags.lock().unwrap()[&tracker.subscription_id].clone();
+        assert_eq!((lag.lag_count, lag.skipped_states), (2, 5));
+        drop(tracker);
+        assert!(subscriber_lags.lock().unwrap().is_empty());
+    }
+}
@@ -1,5 +1,8 @@
 mod player;
+mod profile;
+mod proto;
 mod registry;
 
 pub use player::Player;
+pub(crate) use profile::PlayerProfile;
 pub use registry::PlayerRegistry;
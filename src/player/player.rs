@@ -1,11 +1,15 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 #[derive(Eq, PartialEq, Hash)] // object-level equality
 pub struct Player {
     pub player_id: Uuid,
     pub player_name: String,
     pub total_credits: u64,
+    /// Hash of the secret allowing clients to resume a persisted player account.
+    pub access_token_hash: String,
     // events => calculate after table actions
     // pub available_credits: u64,
     // pub reserved_credits: u64
@@ -14,11 +18,27 @@ pub struct Player {
 impl Player {
     const REGISTRATION_CREDITS: u64 = 500;
 
-    pub fn register() -> Self {
-        Player {
+    /// Returns the new player along with its access token, of which only the hash gets kept.
+    pub fn register() -> (Self, String) {
+        let access_token = Uuid::new_v4().simple().to_string();
+        let player = Player {
             player_id: Uuid::new_v4(),
             player_name: "Anonymous".to_string(), // TODO
             total_credits: Self::REGISTRATION_CREDITS,
-        }
+            access_token_hash: Self::hash_access_token(&access_token),
+        };
+        (player, access_token)
+    }
+
+    pub fn verify_access_token(&self, access_token: &str) -> bool {
+        self.access_token_hash == Self::hash_access_token(access_token)
+    }
+
+    /// Hex-encoded SHA-256 digest, tokens being random enough to go without salting.
+    pub fn hash_access_token(access_token: &str) -> String {
+        Sha256::digest(access_token.as_bytes())
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
     }
 }
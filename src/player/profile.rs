@@ -0,0 +1,12 @@
+use uuid::Uuid;
+
+use crate::lobby::GameMode;
+use crate::rating::PlayerRating;
+
+/// Publicly visible player details.
+#[derive(Clone, Debug)]
+pub(crate) struct PlayerProfile {
+    pub player_id: Uuid,
+    pub player_name: String,
+    pub ratings: Vec<(GameMode, PlayerRating)>,
+}
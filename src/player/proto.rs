@@ -0,0 +1,15 @@
+use crate::service::proto;
+use super::PlayerProfile;
+
+impl From<PlayerProfile> for proto::PlayerProfile {
+    fn from(profile: PlayerProfile) -> Self {
+        proto::PlayerProfile {
+            player_id: profile.player_id.to_string(),
+            player_name: profile.player_name,
+            ratings: profile.ratings
+                .into_iter()
+                .map(proto::PlayerRating::from)
+                .collect(),
+        }
+    }
+}
@@ -4,20 +4,63 @@ use tokio::sync::RwLock;
 use uuid::Uuid;
 
 use crate::common::error::AppError;
+use crate::ledger::{CreditLedger, LedgerTransaction, LedgerTransactionKind, PlayerBalance};
+use crate::storage::{self, InMemoryStorage, Storage};
 use super::Player;
 
 // TODO: Track Player/Lobby mapping in PlayerRegistry (outside Player struct)
 
-// TODO: keep player credits up-to-date with events
-
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 pub struct PlayerRegistry {
     registry: Arc<RwLock<HashMap<Uuid, Arc<RwLock<Player>>>>>,
+    ledger: Arc<RwLock<CreditLedger>>,
+    storage: Arc<dyn Storage>,
+}
+
+impl Default for PlayerRegistry {
+    fn default() -> Self {
+        Self::restore(Arc::new(InMemoryStorage::default()), HashMap::new(), Vec::new())
+    }
 }
 
 impl PlayerRegistry {
+    /// Rebuilds the registry from persisted players and their ledger history.<br />
+    /// Player credits are always derived from the ledger.
+    pub fn restore(
+        storage: Arc<dyn Storage>,
+        players: HashMap<Uuid, Player>,
+        transactions: Vec<LedgerTransaction>,
+    ) -> Self {
+        let mut ledger = CreditLedger::default();
+        for transaction in transactions {
+            ledger.record(transaction);
+        }
+        let registry = players
+            .into_iter()
+            .map(|(player_id, mut player)| {
+                player.total_credits = ledger.get_balance(&player_id);
+                (player_id, Arc::new(RwLock::new(player)))
+            })
+            .collect();
+        PlayerRegistry {
+            registry: Arc::new(RwLock::new(registry)),
+            ledger: Arc::new(RwLock::new(ledger)),
+            storage,
+        }
+    }
+
     pub async fn add_player(&mut self, player: Player) -> Result<(), AppError> {
         let mut registry_w = self.registry.write().await;
+        let mut ledger_w = self.ledger.write().await;
+        let buy_in = LedgerTransaction::new(
+            player.player_id,
+            None,
+            LedgerTransactionKind::BuyIn,
+            player.total_credits,
+        );
+        let (stored_player, stored_buy_in) = (player.clone(), buy_in.clone());
+        storage::run_blocking(&self.storage, move |storage| storage.store_player(&stored_player, &[stored_buy_in])).await?;
+        ledger_w.record(buy_in);
         registry_w.insert(player.player_id, Arc::new(RwLock::new(player.clone())));
         Ok(())
     }
@@ -67,3 +110,40 @@ impl PlayerRegistry {
         }
     }
 }
+
+impl PlayerRegistry {
+    /// Records a match's ledger transactions and updates the affected player balances.<br />
+    /// Either every transaction gets applied or none of them do.
+    pub async fn settle_match(&mut self, match_id: Uuid, transactions: Vec<LedgerTransaction>) -> Result<(), AppError> {
+        let registry_r = self.registry.read().await;
+        let mut ledger_w = self.ledger.write().await;
+
+        let balances = ledger_w.preview_balances(&transactions)?;
+        let mut players = Vec::with_capacity(balances.len());
+        for player_id in balances.keys().filter(|player_id| **player_id != CreditLedger::HOUSE_ACCOUNT_ID) {
+            let player = registry_r
+                .get(player_id)
+                .ok_or(AppError::not_found(*player_id))?;
+            players.push(player.clone());
+        }
+
+        let stored_transactions = transactions.clone();
+        storage::run_blocking(&self.storage, move |storage| storage.store_settlement(&match_id, &stored_transactions)).await?;
+        for transaction in transactions {
+            ledger_w.record(transaction);
+        }
+        for player in players {
+            let mut player_w = player.write().await;
+            player_w.total_credits = balances[&player_w.player_id];
+        }
+        Ok(())
+    }
+
+    pub async fn get_player_balance(&self, player_id: &Uuid) -> Result<PlayerBalance, AppError> {
+        if !self.registry.read().await.contains_key(player_id) {
+            return Err(AppError::not_found(*player_id));
+        }
+        let ledger_r = self.ledger.read().await;
+        Ok(ledger_r.get_player_balance(player_id))
+    }
+}
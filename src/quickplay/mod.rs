@@ -0,0 +1,4 @@
+mod proto;
+mod queue;
+
+pub(crate) use queue::{QuickPlayCriteria, QuickPlayQueue, QuickPlayStatus, QuickPlayTicket};
@@ -0,0 +1,51 @@
+use chrono::Utc;
+
+use crate::common::error::AppError;
+use crate::common::time::chrono_to_prost_timestamp;
+use crate::lobby::GameMode;
+use crate::service::proto;
+use super::{QuickPlayCriteria, QuickPlayStatus};
+
+impl TryFrom<proto::JoinQuickPlayRequest> for QuickPlayCriteria {
+    type Error = AppError;
+
+    fn try_from(request: proto::JoinQuickPlayRequest) -> Result<Self, Self::Error> {
+        let game_mode = proto::lobby_settings::GameMode::try_from(request.game_mode)
+            .map(GameMode::from)
+            .map_err(|_| AppError::invalid_request("Invalid JoinQuickPlayRequest.game_mode value!"))?;
+        let table_size = request.table_size
+            .map(u8::try_from)
+            .transpose()
+            .map_err(|_| AppError::invalid_request("Invalid JoinQuickPlayRequest.table_size value!"))?;
+        let criteria = QuickPlayCriteria {
+            game_mode,
+            ante_amount: request.ante_amount,
+            table_size,
+        };
+        criteria.get_lobby_settings(table_size)?;
+        Ok(criteria)
+    }
+}
+
+impl From<QuickPlayStatus> for proto::QuickPlayStatus {
+    fn from(status: QuickPlayStatus) -> Self {
+        let status = match status {
+            QuickPlayStatus::Queued { ticket, position } => {
+                let now = Utc::now();
+                let ante_range = ticket.get_ante_range(now);
+                let rating_range = ticket.get_rating_range(now);
+                proto::quick_play_status::Status::Queued(proto::quick_play_status::Queued {
+                    position,
+                    queued_at: Some(chrono_to_prost_timestamp(ticket.queued_at)),
+                    min_ante_amount: *ante_range.start(),
+                    max_ante_amount: *ante_range.end(),
+                    table_size: ticket.get_table_size(now).map(u32::from),
+                    min_rating: *rating_range.start(),
+                    max_rating: *rating_range.end(),
+                })
+            },
+            QuickPlayStatus::Seated(lobby_id) => proto::quick_play_status::Status::LobbyId(lobby_id.to_string()),
+        };
+        proto::QuickPlayStatus { status: Some(status) }
+    }
+}
@@ -0,0 +1,199 @@
+use std::ops::RangeInclusive;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::common::error::AppError;
+use crate::lobby::{GameMode, LobbySettings, MatchmakingSettings, MatchmakingTimeoutAction};
+
+/// The kind of table a player is looking for.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct QuickPlayCriteria {
+    pub game_mode: GameMode,
+    pub ante_amount: u64,
+    /// Preferred max players, any table size if unset.
+    pub table_size: Option<u8>,
+}
+
+impl QuickPlayCriteria {
+    const ACCEPT_TIMEOUT_SECONDS: u32 = 30;
+
+    /// Settings of tables opened for the criteria, which every player gets to accept before auto-starting.
+    pub fn get_lobby_settings(&self, table_size: Option<u8>) -> Result<LobbySettings, AppError> {
+        let default_settings = LobbySettings::default();
+        let matchmaking = MatchmakingSettings {
+            accept_timeout_seconds: Some(Self::ACCEPT_TIMEOUT_SECONDS),
+            timeout_action: MatchmakingTimeoutAction::RemovePlayers,
+            auto_start: true,
+        };
+        LobbySettings::new(
+            default_settings.min_players,
+            table_size.unwrap_or(default_settings.max_players),
+            self.ante_amount,
+            default_settings.rake,
+            default_settings.spectators,
+            false,
+            matchmaking,
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct QuickPlayTicket {
+    pub player_id: Uuid,
+    pub criteria: QuickPlayCriteria,
+    /// For the game mode in question, as of queueing.
+    pub rating: f64,
+    pub queued_at: DateTime<Utc>,
+}
+
+impl QuickPlayTicket {
+    pub const WIDEN_INTERVAL_SECONDS: i64 = 15;
+    const MAX_WIDEN_LEVEL: i64 = 3;
+    const TABLE_SIZE_WIDEN_LEVEL: u32 = 2;
+    const RATING_WINDOW: f64 = 100.0;
+
+    pub fn new(player_id: Uuid, criteria: QuickPlayCriteria, rating: f64) -> Self {
+        QuickPlayTicket {
+            player_id,
+            criteria,
+            rating,
+            queued_at: Utc::now(),
+        }
+    }
+
+    /// Criteria widen every interval spent in the queue, up to a limit.
+    pub fn get_widen_level(&self, now: DateTime<Utc>) -> u32 {
+        let waited_seconds = (now - self.queued_at).num_seconds();
+        (waited_seconds / Self::WIDEN_INTERVAL_SECONDS).clamp(0, Self::MAX_WIDEN_LEVEL) as u32
+    }
+
+    /// Doubles in both directions with each widening.
+    pub fn get_ante_range(&self, now: DateTime<Utc>) -> RangeInclusive<u64> {
+        let factor = 1u64 << self.get_widen_level(now);
+        let ante_amount = self.criteria.ante_amount;
+        ante_amount / factor..=ante_amount.saturating_mul(factor)
+    }
+
+    /// Doubles with each widening as well.
+    pub fn get_rating_range(&self, now: DateTime<Utc>) -> RangeInclusive<f64> {
+        let rating_window = Self::RATING_WINDOW * f64::from(1u32 << self.get_widen_level(now));
+        self.rating - rating_window..=self.rating + rating_window
+    }
+
+    /// The table size preference gets dropped after a couple of widenings.
+    pub fn get_table_size(&self, now: DateTime<Utc>) -> Option<u8> {
+        match self.get_widen_level(now) < Self::TABLE_SIZE_WIDEN_LEVEL {
+            true => self.criteria.table_size,
+            false => None,
+        }
+    }
+
+    pub fn accepts(&self, settings: &LobbySettings, now: DateTime<Utc>) -> bool {
+        settings.get_game_mode() == self.criteria.game_mode
+            && self.get_ante_range(now).contains(&settings.ante_amount)
+            && self.get_table_size(now).is_none_or(|table_size| table_size == settings.max_players)
+    }
+
+    pub fn accepts_rating(&self, rating: f64, now: DateTime<Utc>) -> bool {
+        self.get_rating_range(now).contains(&rating)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum QuickPlayStatus {
+    Queued {
+        ticket: QuickPlayTicket,
+        position: u32, // 1-based
+    },
+    Seated(Uuid), // lobby
+}
+
+/// Players waiting for a table, longest waiting first. Not persisted.
+#[derive(Debug, Default)]
+pub(crate) struct QuickPlayQueue {
+    tickets: Vec<QuickPlayTicket>,
+}
+
+impl QuickPlayQueue {
+    pub fn push(&mut self, ticket: QuickPlayTicket) -> Result<(), AppError> {
+        if self.get_ticket(&ticket.player_id).is_some() {
+            return Err(AppError::precondition_failed("Player already queued for quick play!"));
+        }
+        self.tickets.push(ticket);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, player_id: &Uuid) -> Option<QuickPlayTicket> {
+        let index = self.tickets.iter().position(|ticket| ticket.player_id == *player_id)?;
+        Some(self.tickets.remove(index))
+    }
+
+    pub fn get_ticket(&self, player_id: &Uuid) -> Option<&QuickPlayTicket> {
+        self.tickets.iter().find(|ticket| ticket.player_id == *player_id)
+    }
+
+    pub fn get_status(&self, player_id: &Uuid) -> Option<QuickPlayStatus> {
+        let index = self.tickets.iter().position(|ticket| ticket.player_id == *player_id)?;
+        Some(QuickPlayStatus::Queued {
+            ticket: self.tickets[index].clone(),
+            position: index as u32 + 1,
+        })
+    }
+
+    pub fn get_tickets(&self) -> &[QuickPlayTicket] {
+        &self.tickets
+    }
+
+    /// Takes enough mutually acceptable tickets of similarly rated players to open a new table with, if any.<br />
+    /// The longest waiting player's criteria decide on the table's settings.
+    pub fn take_table_group(&mut self, now: DateTime<Utc>) -> Option<(LobbySettings, Vec<QuickPlayTicket>)> {
+        for ticket in &self.tickets {
+            let Ok(settings) = ticket.criteria.get_lobby_settings(ticket.get_table_size(now)) else {
+                continue;
+            };
+            let player_ids: Vec<Uuid> = self.tickets
+                .iter()
+                .filter(|other_ticket| {
+                    other_ticket.accepts(&settings, now)
+                        && other_ticket.accepts_rating(ticket.rating, now)
+                        && ticket.accepts_rating(other_ticket.rating, now)
+                })
+                .take(settings.max_players.into())
+                .map(|other_ticket| other_ticket.player_id)
+                .collect();
+            if player_ids.len() < settings.min_players.into() {
+                continue;
+            }
+
+            let group = player_ids
+                .iter()
+                .filter_map(|player_id| self.remove(player_id))
+                .collect();
+            return Some((settings, group));
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeDelta;
+    #[test]
+    fn take_table_group() {
+        let criteria = |ante_amount, table_size| QuickPlayCriteria { game_mode: GameMode::Single, ante_amount, table_size };
+        let mut queue = QuickPlayQueue::default();
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        queue.push(QuickPlayTicket::new(a, criteria(10, Some(4)), 1500.0)).unwrap();
+        queue.push(QuickPlayTicket::new(b, criteria(30, None), 1600.0)).unwrap();
+        queue.push(QuickPlayTicket::new(c, criteria(10, None), 2500.0)).unwrap();
+        assert!(queue.push(QuickPlayTicket::new(a, criteria(10, None), 1500.0)).is_err());
+        let now = Utc::now();
+        assert!(queue.take_table_group(now).is_none());
+        let later = now + TimeDelta::seconds(2 * QuickPlayTicket::WIDEN_INTERVAL_SECONDS);
+        assert_eq!(queue.get_ticket(&a).unwrap().get_ante_range(later), 2..=40);
+        let (settings, group) = queue.take_table_group(later).unwrap();
+        assert_eq!((settings.ante_amount, settings.max_players, group.len()), (10, 6, 2));
+        assert_eq!(queue.get_tickets().len(), 1);
+    }
+}
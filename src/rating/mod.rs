@@ -0,0 +1,6 @@
+mod proto;
+mod rating;
+mod registry;
+
+pub(crate) use rating::PlayerRating;
+pub(crate) use registry::RatingRegistry;
@@ -0,0 +1,14 @@
+use crate::lobby::GameMode;
+use crate::service::proto;
+use super::PlayerRating;
+
+impl From<(GameMode, PlayerRating)> for proto::PlayerRating {
+    fn from((game_mode, rating): (GameMode, PlayerRating)) -> Self {
+        proto::PlayerRating {
+            game_mode: proto::lobby_settings::GameMode::from(game_mode) as i32,
+            rating: rating.rating,
+            deviation: rating.deviation,
+            matches_played: rating.matches_played,
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use std::cmp::Ordering;
+
+/// Elo-scaled skill estimate for a single game mode.<br />
+/// Its deviation expresses the uncertainty, shrinking as more matches get played.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct PlayerRating {
+    pub rating: f64,
+    pub deviation: f64,
+    pub matches_played: u32,
+}
+
+impl Default for PlayerRating {
+    fn default() -> Self {
+        PlayerRating {
+            rating: Self::DEFAULT_RATING,
+            deviation: Self::DEFAULT_DEVIATION,
+            matches_played: 0,
+        }
+    }
+}
+
+impl PlayerRating {
+    const DEFAULT_RATING: f64 = 1500.0;
+    const DEFAULT_DEVIATION: f64 = 350.0;
+    const MIN_DEVIATION: f64 = 50.0;
+    const DEVIATION_DECAY: f64 = 0.9;
+    const MIN_K_FACTOR: f64 = 16.0;
+    const MAX_K_FACTOR: f64 = 64.0;
+
+    /// Rates a match given each player's placement, lower placements being better and ties sharing one.<br />
+    /// Every player plays a virtual duel against each opponent, the outcomes getting averaged out.
+    pub fn rate_match(placements: &[(PlayerRating, u32)]) -> Vec<PlayerRating> {
+        let opponent_count = placements.len().saturating_sub(1) as f64;
+        placements
+            .iter()
+            .enumerate()
+            .map(|(index, (player_rating, placement))| {
+                if opponent_count == 0.0 {
+                    return *player_rating;
+                }
+                let score_delta: f64 = placements
+                    .iter()
+                    .enumerate()
+                    .filter(|(opponent_index, _)| *opponent_index != index)
+                    .map(|(_, (opponent_rating, opponent_placement))| {
+                        let score = match placement.cmp(opponent_placement) {
+                            Ordering::Less => 1.0,
+                            Ordering::Equal => 0.5,
+                            Ordering::Greater => 0.0,
+                        };
+                        score - player_rating.get_expected_score(opponent_rating)
+                    })
+                    .sum();
+                PlayerRating {
+                    rating: player_rating.rating + player_rating.get_k_factor() * score_delta / opponent_count,
+                    deviation: (player_rating.deviation * Self::DEVIATION_DECAY).max(Self::MIN_DEVIATION),
+                    matches_played: player_rating.matches_played + 1,
+                }
+            })
+            .collect()
+    }
+
+    fn get_expected_score(&self, opponent_rating: &PlayerRating) -> f64 {
+        1.0 / (1.0 + 10f64.powf((opponent_rating.rating - self.rating) / 400.0))
+    }
+
+    /// Uncertain ratings move faster.
+    fn get_k_factor(&self) -> f64 {
+        let certainty = (Self::DEFAULT_DEVIATION - self.deviation) / (Self::DEFAULT_DEVIATION - Self::MIN_DEVIATION);
+        Self::MAX_K_FACTOR - (Self::MAX_K_FACTOR - Self::MIN_K_FACTOR) * certainty.clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn rate_match() {
+        let veteran = PlayerRating { rating: 1500.0, deviation: PlayerRating::MIN_DEVIATION, matches_played: 100 };
+        let rated = PlayerRating::rate_match(&[(PlayerRating::default(), 1), (PlayerRating::default(), 2), (veteran, 2)]);
+        assert!(rated[0].rating > 1500.0 && rated[1].rating < 1500.0);
+        assert!(rated[1].rating < rated[2].rating && rated[2].rating < 1500.0);
+        assert!(rated[0].deviation < PlayerRating::DEFAULT_DEVIATION);
+        assert_eq!(rated[2].deviation, PlayerRating::MIN_DEVIATION);
+        assert_eq!(rated[2].matches_played, 101);
+    }
+}
@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::history::HandHistory;
+use crate::lobby::GameMode;
+use super::PlayerRating;
+
+/// Ratings of every player per game mode.<br />
+/// Derived from the recorded hand histories rather than persisted on their own.
+#[derive(Debug, Default)]
+pub(crate) struct RatingRegistry {
+    ratings: HashMap<GameMode, HashMap<Uuid, PlayerRating>>,
+}
+
+impl RatingRegistry {
+    pub fn restore(hand_histories: &[HandHistory]) -> Self {
+        let mut hand_histories: Vec<&HandHistory> = hand_histories.iter().collect();
+        hand_histories.sort_by_key(|history| history.started_at);
+        let mut registry = RatingRegistry::default();
+        for history in hand_histories {
+            registry.record_hand(history);
+        }
+        registry
+    }
+
+    /// Places players by the credits they won or lost over the hand.
+    pub fn record_hand(&mut self, history: &HandHistory) {
+        let net_results = history.get_net_results();
+        let mode_ratings = self.ratings.entry(history.game_mode).or_default();
+        let (player_ids, placements): (Vec<Uuid>, Vec<(PlayerRating, u32)>) = net_results
+            .iter()
+            .map(|(player_id, net_result)| {
+                let placement = 1 + net_results.values().filter(|other_result| *other_result > net_result).count();
+                let rating = mode_ratings.get(player_id).copied().unwrap_or_default();
+                (*player_id, (rating, placement as u32))
+            })
+            .unzip();
+        for (player_id, rating) in player_ids.into_iter().zip(PlayerRating::rate_match(&placements)) {
+            mode_ratings.insert(player_id, rating);
+        }
+    }
+
+    /// Unrated players start out at the default rating.
+    pub fn get_rating(&self, player_id: &Uuid, game_mode: GameMode) -> PlayerRating {
+        self.ratings
+            .get(&game_mode)
+            .and_then(|mode_ratings| mode_ratings.get(player_id))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Only game modes the player got rated in.
+    pub fn get_player_ratings(&self, player_id: &Uuid) -> Vec<(GameMode, PlayerRating)> {
+        self.ratings
+            .iter()
+            .filter_map(|(game_mode, mode_ratings)| {
+                mode_ratings.get(player_id).map(|rating| (*game_mode, *rating))
+            })
+            .collect()
+    }
+}
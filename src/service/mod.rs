@@ -8,17 +8,20 @@ pub(crate) use proto::{
 };
 
 use std::sync::Arc;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::ops::Deref;
 use std::pin::Pin;
 use futures::Stream;
 use tokio::sync::RwLock;
-use tonic::{Request, Response, Status};
+use tonic::{Request, Response, Status, Streaming};
 use uuid::Uuid;
-use futures::stream::TryStreamExt;
+use futures::stream::{StreamExt, TryStreamExt};
 
 use crate::common::error::AppError;
 use crate::game::{DiscardedCards, GameService};
+use crate::leaderboard::LeaderboardQuery;
+use crate::output::{GameStateUpdateEncoder, LobbyFilter, LobbyQuery};
+use crate::quickplay::QuickPlayCriteria;
 
 #[derive(Default)]
 pub struct RusticPokerService {
@@ -26,6 +29,26 @@ pub struct RusticPokerService {
     player_connections: Arc<RwLock<HashMap<PeerAddress, Uuid>>>,
 }
 
+impl RusticPokerService {
+    pub fn new(game_service: GameService) -> Self {
+        RusticPokerService {
+            game_service,
+            player_connections: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Invite codes take precedence over lobby ids.
+    async fn resolve_lobby_id(&self, lobby_id: String, invite_code: Option<String>) -> Result<Uuid, Status> {
+        if let Some(invite_code) = invite_code {
+            return Ok(self.game_service.resolve_lobby_invite(&invite_code).await?);
+        }
+        Uuid::parse_str(&lobby_id)
+            .map_err(|_|
+                Status::invalid_argument("JoinLobbyRequest.lobby_id should be a UUID (v4)!")
+            )
+    }
+}
+
 macro_rules! extract_client_address {
     ($request:expr) => {
         {
@@ -62,18 +85,40 @@ macro_rules! get_player_id {
 #[tonic::async_trait]
 impl proto::rustic_poker_server::RusticPoker for RusticPokerService {
     type WatchStateStream = Pin<Box<dyn Stream<Item=Result<proto::GameState, Status>> + Send>>;
-
-    async fn connect(&self, request: Request<proto::ConnectRequest>) -> Result<Response<()>, Status> {
+    type WatchEventsStream = Pin<Box<dyn Stream<Item=Result<proto::GameEvent, Status>> + Send>>;
+    type WatchStateUpdatesStream = Pin<Box<dyn Stream<Item=Result<proto::GameStateUpdate, Status>> + Send>>;
+    type WatchLobbiesStream = Pin<Box<dyn Stream<Item=Result<proto::LobbyListEvent, Status>> + Send>>;
+    type WatchLobbyInvitationsStream = Pin<Box<dyn Stream<Item=Result<proto::LobbyInvitation, Status>> + Send>>;
+    type WatchNotificationsStream = Pin<Box<dyn Stream<Item=Result<proto::Notification, Status>> + Send>>;
+    type ReplayHandStream = Pin<Box<dyn Stream<Item=Result<proto::GameState, Status>> + Send>>;
+
+    async fn connect(&self, request: Request<proto::ConnectRequest>) -> Result<Response<proto::ConnectResponse>, Status> {
         let peer_address = extract_client_address!(request)?;
+        let request = request.into_inner();
+        let credentials = match (request.player_id, request.access_token) {
+            (Some(player_id), Some(access_token)) => {
+                let player_id = Uuid::parse_str(&player_id)
+                    .map_err(|_| Status::invalid_argument("Invalid player_id!"))?;
+                Some((player_id, access_token))
+            },
+            (None, None) => None,
+            _ => return Err(Status::invalid_argument("Both player_id and access_token are required to resume a player!")),
+        };
 
         let mut player_connections_w = self.player_connections.write().await;
-        if player_connections_w.contains_key(&peer_address) {
-            return Ok(Response::new(()));
+        let (player, access_token) = match (player_connections_w.get(&peer_address), credentials) {
+            (Some(player_id), None) => (self.game_service.get_player_rpc(player_id).await?, String::new()), // only stored hashed
+            (_, credentials) => self.game_service.connect_rpc(credentials).await?,
+        };
+        let was_online = player_connections_w.values().any(|player_id| *player_id == player.player_id);
+        player_connections_w.insert(peer_address, player.player_id);
+        if !was_online {
+            self.game_service.set_player_online_rpc(player.player_id, true).await;
         }
-
-        let player_id = self.game_service.connect_rpc().await?;
-        player_connections_w.insert(peer_address, player_id);
-        Ok(Response::new(()))
+        Ok(Response::new(proto::ConnectResponse {
+            player_id: player.player_id.to_string(),
+            access_token,
+        }))
     }
 
     async fn disconnect(&self, request: Request<()>) -> Result<Response<()>, Status> {
@@ -83,19 +128,140 @@ impl proto::rustic_poker_server::RusticPoker for RusticPokerService {
         let Some(player_id) = player_connections_r.get(&peer_address) else {
             return Err(Status::aborted("No active client connections!"));
         };
+        let is_online = player_connections_r
+            .iter()
+            .any(|(connection_address, connected_player_id)| connected_player_id == player_id && *connection_address != peer_address);
+        if !is_online {
+            self.game_service.set_player_online_rpc(*player_id, false).await;
+        }
 
         self.game_service.disconnect_rpc(player_id).await?;
         Ok(Response::new(()))
     }
 
-    async fn get_lobbies(&self, _: Request<()>) -> Result<Response<proto::GetLobbiesResponse>, Status> {
-        let lobbies = self.game_service
-            .get_lobbies_rpc()
+    async fn get_player_profile(&self, request: Request<proto::GetPlayerProfileRequest>) -> Result<Response<proto::PlayerProfile>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+        let profile_player_id = request.into_inner().player_id
+            .map(|profile_player_id| Uuid::parse_str(&profile_player_id))
+            .transpose()
+            .map_err(|_| Status::invalid_argument("Invalid player_id!"))?
+            .unwrap_or(player_id);
+
+        let profile = self.game_service.get_player_profile_rpc(&profile_player_id).await?;
+        Ok(Response::new(profile.into()))
+    }
+
+    async fn get_player_stats(&self, request: Request<proto::GetPlayerStatsRequest>) -> Result<Response<proto::PlayerStats>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+        let stats_player_id = request.into_inner().player_id
+            .map(|stats_player_id| Uuid::parse_str(&stats_player_id))
+            .transpose()
+            .map_err(|_| Status::invalid_argument("Invalid player_id!"))?
+            .unwrap_or(player_id);
+
+        let stats = self.game_service.get_player_stats_rpc(&stats_player_id).await?;
+        Ok(Response::new((stats_player_id, stats).into()))
+    }
+
+    async fn get_leaderboard(&self, request: Request<proto::GetLeaderboardRequest>) -> Result<Response<proto::GetLeaderboardResponse>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+        let query = LeaderboardQuery::try_from(request.into_inner())?;
+
+        let page = self.game_service.get_leaderboard_rpc(query, &player_id).await;
+        Ok(Response::new(page.into()))
+    }
+
+    async fn get_player_achievements(&self, request: Request<proto::GetPlayerAchievementsRequest>) -> Result<Response<proto::GetPlayerAchievementsResponse>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+        let achievements_player_id = request.into_inner().player_id
+            .map(|achievements_player_id| Uuid::parse_str(&achievements_player_id))
+            .transpose()
+            .map_err(|_| Status::invalid_argument("Invalid player_id!"))?
+            .unwrap_or(player_id);
+
+        let achievements = self.game_service.get_player_achievements_rpc(&achievements_player_id).await?;
+        Ok(Response::new(proto::GetPlayerAchievementsResponse {
+            player_id: achievements_player_id.to_string(),
+            achievements: achievements.into_iter().map(proto::AchievementProgress::from).collect(),
+        }))
+    }
+
+    async fn send_friend_request(&self, request: Request<proto::FriendPlayerRequest>) -> Result<Response<()>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+        let addressee_id = Uuid::parse_str(&request.into_inner().player_id)
+            .map_err(|_| Status::invalid_argument("Invalid player_id!"))?;
+
+        self.game_service.send_friend_request_rpc(player_id, addressee_id).await?;
+        Ok(Response::new(()))
+    }
+
+    async fn accept_friend_request(&self, request: Request<proto::FriendPlayerRequest>) -> Result<Response<()>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+        let requester_id = Uuid::parse_str(&request.into_inner().player_id)
+            .map_err(|_| Status::invalid_argument("Invalid player_id!"))?;
+
+        self.game_service.accept_friend_request_rpc(player_id, requester_id).await?;
+        Ok(Response::new(()))
+    }
+
+    async fn remove_friend(&self, request: Request<proto::FriendPlayerRequest>) -> Result<Response<()>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+        let other_player_id = Uuid::parse_str(&request.into_inner().player_id)
+            .map_err(|_| Status::invalid_argument("Invalid player_id!"))?;
+
+        self.game_service.remove_friend_rpc(player_id, other_player_id).await?;
+        Ok(Response::new(()))
+    }
+
+    async fn get_friends(&self, request: Request<()>) -> Result<Response<proto::GetFriendsResponse>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+        let online_player_ids: HashSet<Uuid> = self.player_connections.read().await.values().copied().collect();
+
+        let friends = self.game_service.get_friends_rpc(player_id, &online_player_ids).await?;
+        Ok(Response::new(proto::GetFriendsResponse {
+            friends: friends.into_iter().map(proto::Friend::from).collect(),
+        }))
+    }
+
+    async fn join_friend_lobby(&self, request: Request<proto::JoinFriendLobbyRequest>) -> Result<Response<()>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+        let proto::JoinFriendLobbyRequest { player_id: friend_id, password, seat } = request.into_inner();
+        let friend_id = Uuid::parse_str(&friend_id)
+            .map_err(|_| Status::invalid_argument("Invalid player_id!"))?;
+        let seat = seat
+            .map(u8::try_from)
+            .transpose()
+            .map_err(|_| Status::invalid_argument("Invalid seat!"))?;
+
+        self.game_service.join_friend_lobby_rpc(player_id, friend_id, password, seat).await?;
+        Ok(Response::new(()))
+    }
+
+    async fn get_lobbies(&self, request: Request<proto::GetLobbiesRequest>) -> Result<Response<proto::GetLobbiesResponse>, Status> {
+        let query = LobbyQuery::try_from(request.into_inner())?;
+
+        let page = self.game_service.get_lobbies_rpc(query).await;
+        Ok(Response::new(page.into()))
+    }
+
+    async fn watch_lobbies(&self, request: Request<proto::WatchLobbiesRequest>) -> Result<Response<Self::WatchLobbiesStream>, Status> {
+        let filter = LobbyFilter::try_from(request.into_inner())?;
+
+        let stream = self.game_service
+            .watch_lobbies_rpc(filter)
             .await
-            .into_iter()
-            .map(|lobby| lobby.into())
-            .collect();
-        Ok(Response::new(proto::GetLobbiesResponse{ lobbies }))
+            .map_ok(proto::LobbyListEvent::from)
+            .map_err(|err| err.into());
+        Ok(Response::new(Box::pin(stream) as Self::WatchLobbiesStream))
     }
 
     // TODO: return LobbyInfoPrivate instead
@@ -111,13 +277,26 @@ impl proto::rustic_poker_server::RusticPoker for RusticPokerService {
     async fn join_lobby(&self, request: Request<proto::JoinLobbyRequest>) -> Result<Response<()>, Status> {
         let peer_address = extract_client_address!(request)?;
         let player_id = get_player_id!(self, &peer_address)?;
-        let proto::JoinLobbyRequest { lobby_id } = request.into_inner();
+        let proto::JoinLobbyRequest { lobby_id, password, invite_code, seat } = request.into_inner();
+        let seat = seat
+            .map(u8::try_from)
+            .transpose()
+            .map_err(|_| Status::invalid_argument("Invalid seat!"))?;
+
+        let via_invite_code = invite_code.is_some();
+        let lobby_id = self.resolve_lobby_id(lobby_id, invite_code).await?;
+        self.game_service.join_lobby_rpc(lobby_id, player_id, password, via_invite_code, seat).await?;
+        Ok(Response::new(()))
+    }
 
-        let lobby_id = Uuid::parse_str(&lobby_id)
-            .map_err(|_|
-                Status::invalid_argument("JoinLobbyRequest.lobby_id should be a UUID (v4)!")
-            )?;
-        self.game_service.join_lobby_rpc(lobby_id, player_id).await?;
+    async fn spectate_lobby(&self, request: Request<proto::JoinLobbyRequest>) -> Result<Response<()>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+        let proto::JoinLobbyRequest { lobby_id, password, invite_code, .. } = request.into_inner();
+
+        let via_invite_code = invite_code.is_some();
+        let lobby_id = self.resolve_lobby_id(lobby_id, invite_code).await?;
+        self.game_service.spectate_lobby_rpc(lobby_id, player_id, password, via_invite_code).await?;
         Ok(Response::new(()))
     }
 
@@ -129,12 +308,97 @@ impl proto::rustic_poker_server::RusticPoker for RusticPokerService {
         Ok(Response::new(()))
     }
 
-    // async fn kick_lobby_player(&self, request: Request<KickLobbyPlayerRequest>) -> Result<Response<()>, Status> {
-    //     let peer_address = extract_client_address!(request)?;
-    //     let KickLobbyPlayerRequest { user_id } = request.into_inner();
-    //     self.server.lock().unwrap().kick_lobby_player(&peer_address, &user_id)?;
-    //     Ok(Response::new(()))
-    // }
+    async fn change_lobby_seat(&self, request: Request<proto::ChangeLobbySeatRequest>) -> Result<Response<()>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+        let seat = u8::try_from(request.into_inner().seat)
+            .map_err(|_| Status::invalid_argument("Invalid seat!"))?;
+
+        self.game_service.change_lobby_seat_rpc(player_id, seat).await?;
+        Ok(Response::new(()))
+    }
+
+    async fn join_lobby_waitlist(&self, request: Request<proto::JoinLobbyRequest>) -> Result<Response<proto::LobbyWaitlistPosition>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+        let proto::JoinLobbyRequest { lobby_id, password, invite_code, .. } = request.into_inner();
+
+        let via_invite_code = invite_code.is_some();
+        let lobby_id = self.resolve_lobby_id(lobby_id, invite_code).await?;
+        let position = self.game_service.join_lobby_waitlist_rpc(lobby_id, player_id, password, via_invite_code).await?;
+        Ok(Response::new(position.into()))
+    }
+
+    async fn leave_lobby_waitlist(&self, request: Request<()>) -> Result<Response<()>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+
+        self.game_service.leave_lobby_waitlist_rpc(player_id).await?;
+        Ok(Response::new(()))
+    }
+
+    async fn get_lobby_waitlist_position(&self, request: Request<()>) -> Result<Response<proto::LobbyWaitlistPosition>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+
+        let position = self.game_service.get_lobby_waitlist_position_rpc(player_id).await?;
+        Ok(Response::new(position.into()))
+    }
+
+    async fn respond_seat_offer(&self, request: Request<proto::RespondSeatOfferRequest>) -> Result<Response<()>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+        let proto::RespondSeatOfferRequest { accept } = request.into_inner();
+
+        self.game_service.respond_seat_offer_rpc(player_id, accept).await?;
+        Ok(Response::new(()))
+    }
+
+    async fn join_quick_play(&self, request: Request<proto::JoinQuickPlayRequest>) -> Result<Response<proto::QuickPlayStatus>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+        let criteria = QuickPlayCriteria::try_from(request.into_inner())?;
+
+        let status = self.game_service.join_quick_play_rpc(player_id, criteria).await?;
+        Ok(Response::new(status.into()))
+    }
+
+    async fn leave_quick_play(&self, request: Request<()>) -> Result<Response<()>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+
+        self.game_service.leave_quick_play_rpc(player_id).await?;
+        Ok(Response::new(()))
+    }
+
+    async fn get_quick_play_status(&self, request: Request<()>) -> Result<Response<proto::QuickPlayStatus>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+
+        let status = self.game_service.get_quick_play_status_rpc(player_id).await?;
+        Ok(Response::new(status.into()))
+    }
+
+    async fn kick_lobby_player(&self, request: Request<proto::KickLobbyPlayerRequest>) -> Result<Response<()>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+        let kicked_player_id = Uuid::parse_str(&request.into_inner().player_id)
+            .map_err(|_| Status::invalid_argument("Invalid player_id!"))?;
+
+        self.game_service.kick_lobby_player_rpc(player_id, kicked_player_id).await?;
+        Ok(Response::new(()))
+    }
+
+    async fn mute_lobby_player(&self, request: Request<proto::MuteLobbyPlayerRequest>) -> Result<Response<()>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+        let proto::MuteLobbyPlayerRequest { player_id: muted_player_id, muted } = request.into_inner();
+        let muted_player_id = Uuid::parse_str(&muted_player_id)
+            .map_err(|_| Status::invalid_argument("Invalid player_id!"))?;
+
+        self.game_service.mute_lobby_player_rpc(player_id, muted_player_id, muted).await?;
+        Ok(Response::new(()))
+    }
 
     async fn set_lobby_matchmaking_status(&self, request: Request<proto::SetLobbyMatchmakingStatusRequest>) -> Result<Response<()>, Status> {
         let peer_address = extract_client_address!(request)?;
@@ -148,6 +412,125 @@ impl proto::rustic_poker_server::RusticPoker for RusticPokerService {
         Ok(Response::new(()))
     }
 
+    async fn set_lobby_settings(&self, request: Request<proto::LobbySettings>) -> Result<Response<()>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+        let settings = request.into_inner().try_into()?;
+
+        self.game_service.set_lobby_settings_rpc(player_id, settings).await?;
+        Ok(Response::new(()))
+    }
+
+    async fn set_lobby_password(&self, request: Request<proto::SetLobbyPasswordRequest>) -> Result<Response<()>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+        let proto::SetLobbyPasswordRequest { password } = request.into_inner();
+
+        self.game_service.set_lobby_password_rpc(player_id, password).await?;
+        Ok(Response::new(()))
+    }
+
+    async fn create_lobby_invite(&self, request: Request<proto::CreateLobbyInviteRequest>) -> Result<Response<proto::LobbyInvite>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+        let proto::CreateLobbyInviteRequest { ttl_seconds } = request.into_inner();
+
+        let invite = self.game_service.create_lobby_invite_rpc(player_id, ttl_seconds).await?;
+        Ok(Response::new(invite.into()))
+    }
+
+    async fn revoke_lobby_invite(&self, request: Request<proto::RevokeLobbyInviteRequest>) -> Result<Response<()>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+        let proto::RevokeLobbyInviteRequest { code } = request.into_inner();
+
+        self.game_service.revoke_lobby_invite_rpc(player_id, code).await?;
+        Ok(Response::new(()))
+    }
+
+    async fn get_lobby_invites(&self, request: Request<()>) -> Result<Response<proto::GetLobbyInvitesResponse>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+
+        let invites = self.game_service
+            .get_lobby_invites_rpc(player_id)
+            .await?
+            .into_iter()
+            .map(|invite| invite.into())
+            .collect();
+        Ok(Response::new(proto::GetLobbyInvitesResponse { invites }))
+    }
+
+    async fn resolve_lobby_invite(&self, request: Request<proto::ResolveLobbyInviteRequest>) -> Result<Response<proto::LobbyInfoPublic>, Status> {
+        let proto::ResolveLobbyInviteRequest { code } = request.into_inner();
+
+        let lobby = self.game_service.resolve_lobby_invite_rpc(code).await?;
+        Ok(Response::new(lobby.into()))
+    }
+
+    async fn invite_lobby_player(&self, request: Request<proto::InviteLobbyPlayerRequest>) -> Result<Response<proto::LobbyInvitation>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+        let proto::InviteLobbyPlayerRequest { player_id: invitee_id, ttl_seconds } = request.into_inner();
+        let invitee_id = Uuid::parse_str(&invitee_id)
+            .map_err(|_| Status::invalid_argument("Invalid player_id!"))?;
+
+        let invitation = self.game_service.invite_lobby_player_rpc(player_id, invitee_id, ttl_seconds).await?;
+        Ok(Response::new(invitation.into()))
+    }
+
+    async fn respond_lobby_invitation(&self, request: Request<proto::RespondLobbyInvitationRequest>) -> Result<Response<()>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+        let proto::RespondLobbyInvitationRequest { invitation_id, accept, seat } = request.into_inner();
+        let invitation_id = Uuid::parse_str(&invitation_id)
+            .map_err(|_| Status::invalid_argument("Invalid invitation_id!"))?;
+        let seat = seat
+            .map(u8::try_from)
+            .transpose()
+            .map_err(|_| Status::invalid_argument("Invalid seat!"))?;
+
+        self.game_service.respond_lobby_invitation_rpc(player_id, invitation_id, accept, seat).await?;
+        Ok(Response::new(()))
+    }
+
+    async fn get_lobby_invitations(&self, request: Request<()>) -> Result<Response<proto::GetLobbyInvitationsResponse>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+
+        let invitations = self.game_service
+            .get_lobby_invitations_rpc(player_id)
+            .await
+            .into_iter()
+            .map(|invitation| invitation.into())
+            .collect();
+        Ok(Response::new(proto::GetLobbyInvitationsResponse { invitations }))
+    }
+
+    async fn watch_lobby_invitations(&self, request: Request<()>) -> Result<Response<Self::WatchLobbyInvitationsStream>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+
+        let stream = self.game_service
+            .watch_lobby_invitations_rpc(player_id)
+            .await
+            .map_ok(proto::LobbyInvitation::from)
+            .map_err(|err| err.into());
+        Ok(Response::new(Box::pin(stream) as Self::WatchLobbyInvitationsStream))
+    }
+
+    async fn watch_notifications(&self, request: Request<()>) -> Result<Response<Self::WatchNotificationsStream>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+
+        let stream = self.game_service
+            .watch_notifications_rpc(player_id)
+            .await
+            .map_ok(proto::Notification::from)
+            .map_err(|err| err.into());
+        Ok(Response::new(Box::pin(stream) as Self::WatchNotificationsStream))
+    }
+
     async fn respond_lobby_matchmaking(&self, request: Request<proto::RespondLobbyMatchmakingRequest>) -> Result<Response<()>, Status> {
         let peer_address = extract_client_address!(request)?;
         let player_id = get_player_id!(self, &peer_address)?;
@@ -168,6 +551,26 @@ impl proto::rustic_poker_server::RusticPoker for RusticPokerService {
         Ok(Response::new(()))
     }
 
+    async fn send_chat_message(&self, request: Request<proto::SendChatMessageRequest>) -> Result<Response<proto::ChatMessage>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+        let proto::SendChatMessageRequest { text } = request.into_inner();
+
+        let message = self.game_service.send_chat_message_rpc(player_id, text).await?;
+        Ok(Response::new(message.into()))
+    }
+
+    async fn mute_chat_player(&self, request: Request<proto::MuteChatPlayerRequest>) -> Result<Response<()>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+        let proto::MuteChatPlayerRequest { player_id: muted_player_id, muted } = request.into_inner();
+        let muted_player_id = Uuid::parse_str(&muted_player_id)
+            .map_err(|_| Status::invalid_argument("Invalid player_id!"))?;
+
+        self.game_service.mute_chat_player_rpc(player_id, muted_player_id, muted).await?;
+        Ok(Response::new(()))
+    }
+
     async fn respond_betting_phase(&self, request: Request<proto::RespondBettingPhaseRequest>) -> Result<Response<()>, Status> {
         let peer_address = extract_client_address!(request)?;
         let player_id = get_player_id!(self, &peer_address)?;
@@ -189,6 +592,111 @@ impl proto::rustic_poker_server::RusticPoker for RusticPokerService {
         Ok(Response::new(()))
     }
 
+    async fn get_balance(&self, request: Request<()>) -> Result<Response<proto::GetBalanceResponse>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+
+        let balance = self.game_service.get_balance_rpc(player_id).await?;
+        Ok(Response::new(balance.into()))
+    }
+
+    async fn get_hand_histories(&self, request: Request<proto::GetHandHistoriesRequest>) -> Result<Response<proto::GetHandHistoriesResponse>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+        let limit = request.into_inner().limit.map(|limit| limit as usize);
+
+        let hands = self.game_service
+            .get_hand_histories_rpc(player_id, limit)
+            .await
+            .into_iter()
+            .map(|history| history.into())
+            .collect();
+        Ok(Response::new(proto::GetHandHistoriesResponse { hands }))
+    }
+
+    async fn export_hand_histories(&self, request: Request<proto::GetHandHistoriesRequest>) -> Result<Response<proto::ExportHandHistoriesResponse>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+        let limit = request.into_inner().limit.map(|limit| limit as usize);
+
+        let text = self.game_service.export_hand_histories_rpc(player_id, limit).await;
+        Ok(Response::new(proto::ExportHandHistoriesResponse { text }))
+    }
+
+    /// Same as watch_state(), except for sending deltas between snapshots.<br />
+    /// Clients may request a fresh snapshot at any time, eg: upon detecting a sequence gap.
+    async fn watch_state_updates(&self, request: Request<Streaming<proto::WatchStateUpdatesRequest>>) -> Result<Response<Self::WatchStateUpdatesStream>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+        let mut update_requests = request.into_inner();
+
+        let mut states = Box::pin(self.game_service.watch_state_rpc(player_id).await?);
+        let stream = async_stream::try_stream! {
+            let mut encoder = GameStateUpdateEncoder::default();
+            let mut is_requesting = true;
+            loop {
+                let update = tokio::select! {
+                    state = states.next() => match state {
+                        Some(state) => state.map(|state| encoder.encode(state.into())),
+                        None => break,
+                    },
+                    request = update_requests.next(), if is_requesting => Ok(match request {
+                        Some(Ok(request)) if request.snapshot => encoder.snapshot(),
+                        Some(Ok(_)) => None,
+                        _ => {
+                            is_requesting = false;
+                            None
+                        },
+                    }),
+                };
+                if let Some(update) = update? {
+                    yield update;
+                }
+            }
+        };
+        Ok(Response::new(Box::pin(stream) as Self::WatchStateUpdatesStream))
+    }
+
+    async fn watch_events(&self, request: Request<()>) -> Result<Response<Self::WatchEventsStream>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+
+        let stream = self.game_service
+            .watch_events_rpc(player_id)
+            .await?
+            .map_ok(proto::GameEvent::from)
+            .map_err(|err| err.into());
+        Ok(Response::new(Box::pin(stream) as Self::WatchEventsStream))
+    }
+
+    async fn get_state_subscribers(&self, request: Request<()>) -> Result<Response<proto::GetStateSubscribersResponse>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+
+        let subscribers = self.game_service
+            .get_state_subscribers_rpc(player_id)
+            .await?
+            .into_iter()
+            .map(|lag| lag.into())
+            .collect();
+        Ok(Response::new(proto::GetStateSubscribersResponse { subscribers }))
+    }
+
+    async fn replay_hand(&self, request: Request<proto::ReplayHandRequest>) -> Result<Response<Self::ReplayHandStream>, Status> {
+        let peer_address = extract_client_address!(request)?;
+        let player_id = get_player_id!(self, &peer_address)?;
+        let request = request.into_inner();
+        let match_id = Uuid::parse_str(&request.match_id)
+            .map_err(|_| Status::invalid_argument("Invalid match_id!"))?;
+
+        let stream = self.game_service
+            .replay_hand_rpc(player_id, match_id, request.speed.unwrap_or(1.0))
+            .await?
+            .map_ok(proto::GameState::from)
+            .map_err(|err| err.into());
+        Ok(Response::new(Box::pin(stream) as Self::ReplayHandStream))
+    }
+
     async fn watch_state(&self, request: Request<()>) -> Result<Response<Self::WatchStateStream>, Status> {
         let peer_address = extract_client_address!(request)?;
         let player_id = get_player_id!(self, &peer_address)?;
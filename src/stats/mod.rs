@@ -0,0 +1,6 @@
+mod proto;
+mod registry;
+mod stats;
+
+pub(crate) use registry::StatsRegistry;
+pub(crate) use stats::{PlayerHud, PlayerStats};
@@ -0,0 +1,31 @@
+use uuid::Uuid;
+
+use crate::service::proto;
+use super::{PlayerHud, PlayerStats};
+
+impl From<(Uuid, PlayerStats)> for proto::PlayerStats {
+    fn from((player_id, stats): (Uuid, PlayerStats)) -> Self {
+        proto::PlayerStats {
+            player_id: player_id.to_string(),
+            hands_played: stats.hands_played,
+            vpip: stats.get_vpip(),
+            pfr: stats.get_pfr(),
+            aggression_factor: stats.get_aggression_factor(),
+            went_to_showdown: stats.get_went_to_showdown(),
+            won_at_showdown: stats.get_won_at_showdown(),
+            average_pot_won: stats.get_average_pot_won(),
+            biggest_pot_won: stats.biggest_pot,
+        }
+    }
+}
+
+impl From<PlayerHud> for proto::PlayerHud {
+    fn from(hud: PlayerHud) -> Self {
+        proto::PlayerHud {
+            hands_played: hud.hands_played,
+            vpip: hud.vpip,
+            pfr: hud.pfr,
+            aggression_factor: hud.aggression_factor,
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+use crate::history::HandHistory;
+use super::{PlayerHud, PlayerStats};
+
+/// Stats of every player, derived from the recorded hand histories rather than persisted on their own.
+#[derive(Debug, Default)]
+pub(crate) struct StatsRegistry {
+    player_stats: HashMap<Uuid, PlayerStats>,
+}
+
+impl StatsRegistry {
+    pub fn restore(hand_histories: &[HandHistory]) -> Self {
+        let mut registry = StatsRegistry::default();
+        for history in hand_histories {
+            registry.record_hand(history);
+        }
+        registry
+    }
+
+    pub fn record_hand(&mut self, history: &HandHistory) {
+        for seat in &history.seats {
+            self.player_stats
+                .entry(seat.player_id)
+                .or_default()
+                .record_hand(&seat.player_id, history);
+        }
+    }
+
+    pub fn get_stats(&self, player_id: &Uuid) -> PlayerStats {
+        self.player_stats.get(player_id).cloned().unwrap_or_default()
+    }
+
+    pub fn get_huds(&self, player_ids: &HashSet<Uuid>) -> HashMap<Uuid, PlayerHud> {
+        player_ids
+            .iter()
+            .map(|player_id| (*player_id, self.get_stats(player_id).get_hud()))
+            .collect()
+    }
+}
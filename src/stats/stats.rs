@@ -0,0 +1,165 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::game::BettingRoundAction;
+use crate::history::{HandHistory, HandHistoryAction};
+
+/// Long-term counters of a player's betting behaviour and results.<br />
+/// "Pre-draw" covers the first betting round, i.e. anything before cards get discarded.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct PlayerStats {
+    pub hands_played: u32,
+    /// Voluntarily put credits into the pot pre-draw, antes aside.
+    pub vpip_hands: u32,
+    /// Bet or raised pre-draw.
+    pub pfr_hands: u32,
+    pub aggressive_actions: u32, // bets and raises
+    pub calls: u32, // checks aside
+    pub drawing_hands: u32, // still in the hand once drawing started
+    pub showdowns: u32,
+    pub showdowns_won: u32,
+    pub pots_won: u32, // hands winning at least one pot
+    pub total_pot_winnings: u64,
+    pub biggest_pot: u64,
+}
+
+/// Compact stats shown next to opponents at the table.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct PlayerHud {
+    pub hands_played: u32,
+    pub vpip: f64,
+    pub pfr: f64,
+    pub aggression_factor: Option<f64>,
+}
+
+impl PlayerStats {
+    pub fn record_hand(&mut self, player_id: &Uuid, history: &HandHistory) {
+        let (mut vpip, mut pfr, mut reached_drawing, mut showdown) = (false, false, false, false);
+        let mut drawing_started = false;
+        let mut pot_winnings = 0;
+        for event in &history.events {
+            match &event.action {
+                HandHistoryAction::CardsDiscarded { player_id: discarding_player_id, .. }
+                | HandHistoryAction::CardsDrawn { player_id: discarding_player_id, .. } => {
+                    drawing_started = true;
+                    reached_drawing |= discarding_player_id == player_id;
+                },
+                HandHistoryAction::BettingAction { player_id: acting_player_id, action, added_credits, .. }
+                    if acting_player_id == player_id => {
+                    let is_aggressive = matches!(action, BettingRoundAction::Bet(_) | BettingRoundAction::Raise(_));
+                    if is_aggressive {
+                        self.aggressive_actions += 1;
+                    } else if matches!(action, BettingRoundAction::Call) && *added_credits > 0 {
+                        self.calls += 1;
+                    }
+                    if !drawing_started {
+                        vpip |= *added_credits > 0;
+                        pfr |= is_aggressive;
+                    }
+                },
+                HandHistoryAction::HandShown { player_id: showing_player_id, .. } => {
+                    showdown |= showing_player_id == player_id;
+                },
+                HandHistoryAction::PotAwarded { player_id: winner_id, amount } if winner_id == player_id => {
+                    pot_winnings += amount;
+                },
+                _ => {},
+            }
+        }
+
+        self.hands_played += 1;
+        self.vpip_hands += u32::from(vpip);
+        self.pfr_hands += u32::from(pfr);
+        self.drawing_hands += u32::from(reached_drawing);
+        self.showdowns += u32::from(showdown);
+        self.showdowns_won += u32::from(showdown && pot_winnings > 0);
+        if pot_winnings > 0 {
+            self.pots_won += 1;
+            self.total_pot_winnings += pot_winnings;
+            self.biggest_pot = self.biggest_pot.max(pot_winnings);
+        }
+    }
+
+    pub fn get_vpip(&self) -> f64 {
+        percentage(self.vpip_hands, self.hands_played)
+    }
+
+    pub fn get_pfr(&self) -> f64 {
+        percentage(self.pfr_hands, self.hands_played)
+    }
+
+    /// Bets and raises per call, undefined for players who never called.
+    pub fn get_aggression_factor(&self) -> Option<f64> {
+        (self.calls > 0).then(|| f64::from(self.aggressive_actions) / f64::from(self.calls))
+    }
+
+    /// Out of the hands still played once drawing started.
+    pub fn get_went_to_showdown(&self) -> f64 {
+        percentage(self.showdowns, self.drawing_hands)
+    }
+
+    pub fn get_won_at_showdown(&self) -> f64 {
+        percentage(self.showdowns_won, self.showdowns)
+    }
+
+    pub fn get_average_pot_won(&self) -> f64 {
+        match self.pots_won {
+            0 => 0.0,
+            pots_won => self.total_pot_winnings as f64 / f64::from(pots_won),
+        }
+    }
+
+    pub fn get_hud(&self) -> PlayerHud {
+        PlayerHud {
+            hands_played: self.hands_played,
+            vpip: self.get_vpip(),
+            pfr: self.get_pfr(),
+            aggression_factor: self.get_aggression_factor(),
+        }
+    }
+}
+
+fn percentage(count: u32, total: u32) -> f64 {
+    match total {
+        0 => 0.0,
+        total => 100.0 * f64::from(count) / f64::from(total),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use crate::lobby::GameMode;
+    #[test]
+    fn record_hand() {
+        let (player_id, opponent_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut history = HandHistory {
+            match_id: Uuid::new_v4(),
+            lobby_id: Uuid::new_v4(),
+            table_name: String::from("Table"),
+            game_mode: GameMode::Single,
+            max_seats: 6,
+            ante_amount: 10,
+            button_player_id: player_id,
+            seats: Vec::new(),
+            events: Vec::new(),
+            started_at: Utc::now(),
+            ended_at: None,
+            frames: Vec::new(),
+        };
+        let bet = |player_id, action, added_credits| HandHistoryAction::BettingAction { player_id, action, bet_amount: 0, added_credits };
+        history.record(HandHistoryAction::Ante { player_id, amount: 10 });
+        history.record(bet(player_id, BettingRoundAction::Call, 0));
+        history.record(bet(opponent_id, BettingRoundAction::Bet(20), 20));
+        history.record(bet(player_id, BettingRoundAction::Call, 20));
+        history.record(HandHistoryAction::CardsDiscarded { player_id, card_count: 0, cards: None });
+        history.record(bet(player_id, BettingRoundAction::Raise(40), 40));
+        history.record(HandHistoryAction::PotAwarded { player_id, amount: 140 });
+        let mut stats = PlayerStats::default();
+        stats.record_hand(&player_id, &history);
+        assert_eq!((stats.get_vpip(), stats.get_pfr()), (100.0, 0.0));
+        assert_eq!(stats.get_aggression_factor(), Some(1.0));
+        assert_eq!((stats.get_went_to_showdown(), stats.get_average_pot_won()), (0.0, 140.0));
+    }
+}
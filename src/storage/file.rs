@@ -0,0 +1,288 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use uuid::Uuid;
+
+use crate::common::error::AppError;
+use crate::friend::Friendship;
+use crate::history::HandHistory;
+use crate::ledger::LedgerTransaction;
+use crate::lobby::LobbyPassword;
+use crate::player::Player;
+use super::{LobbyRecord, MatchSnapshot, PendingSettlement, Storage, StorageRecords};
+
+/// File-based storage backend keeping every record in a JSON file of its own, within the storage directory.<br />
+/// Ledger transactions get appended to a log instead, one batch per line.<br />
+/// Record writes go through a temporary file that atomically replaces the previous one.
+#[derive(Debug)]
+pub(crate) struct FileStorage {
+    path: PathBuf,
+    transaction_log: Mutex<File>,
+}
+
+impl FileStorage {
+    const PLAYERS_DIR: &'static str = "players";
+    const LOBBIES_DIR: &'static str = "lobbies";
+    const MATCH_SNAPSHOTS_DIR: &'static str = "match_snapshots";
+    const HAND_HISTORIES_DIR: &'static str = "hand_histories";
+    const FRIENDSHIPS_DIR: &'static str = "friendships";
+    const PENDING_SETTLEMENTS_DIR: &'static str = "pending_settlements";
+    const TRANSACTION_LOG: &'static str = "transactions.jsonl";
+
+    /// A single JSON document left behind by previous versions gets migrated into a storage directory of the same path.
+    pub fn open<P: Into<PathBuf>>(path: P) -> Result<Self, AppError> {
+        let path = path.into();
+        let legacy_records = match path.is_file() {
+            true => Some(Self::take_legacy_records(&path)?),
+            false => None,
+        };
+        for dir in [Self::PLAYERS_DIR, Self::LOBBIES_DIR, Self::MATCH_SNAPSHOTS_DIR, Self::HAND_HISTORIES_DIR, Self::FRIENDSHIPS_DIR, Self::PENDING_SETTLEMENTS_DIR] {
+            fs::create_dir_all(path.join(dir)).map_err(AppError::internal)?;
+        }
+        let transaction_log_path = path.join(Self::TRANSACTION_LOG);
+        Self::drop_torn_transactions(&transaction_log_path)?;
+        let transaction_log = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(transaction_log_path)
+            .map_err(AppError::internal)?;
+        let storage = FileStorage {
+            path,
+            transaction_log: Mutex::new(transaction_log),
+        };
+        if let Some(records) = legacy_records {
+            storage.store_records(records)?;
+        }
+        Ok(storage)
+    }
+
+    /// Moves the legacy document aside, hashing any plaintext access tokens and lobby passwords along the way.
+    fn take_legacy_records(path: &Path) -> Result<StorageRecords, AppError> {
+        let contents = fs::read(path).map_err(AppError::internal)?;
+        let mut document: serde_json::Value = serde_json::from_slice(&contents).map_err(AppError::internal)?;
+        if let Some(players) = document.get_mut("players").and_then(|players| players.as_object_mut()) {
+            for player in players.values_mut().filter_map(|player| player.as_object_mut()) {
+                if let Some(serde_json::Value::String(access_token)) = player.remove("access_token") {
+                    player.insert(String::from("access_token_hash"), Player::hash_access_token(&access_token).into());
+                }
+            }
+        }
+        if let Some(lobbies) = document.get_mut("lobbies").and_then(|lobbies| lobbies.as_object_mut()) {
+            for lobby in lobbies.values_mut().filter_map(|lobby| lobby.as_object_mut()) {
+                if let Some(serde_json::Value::String(password)) = lobby.remove("password") {
+                    let password_hash = serde_json::to_value(LobbyPassword::new(&password)).map_err(AppError::internal)?;
+                    lobby.insert(String::from("password_hash"), password_hash);
+                }
+            }
+        }
+        let records = serde_json::from_value(document).map_err(AppError::internal)?;
+        let mut legacy_path = path.as_os_str().to_owned();
+        legacy_path.push(".legacy");
+        fs::rename(path, legacy_path).map_err(AppError::internal)?;
+        Ok(records)
+    }
+
+    fn store_records(&self, records: StorageRecords) -> Result<(), AppError> {
+        for player in records.players.values() {
+            self.write_record(Self::PLAYERS_DIR, &player.player_id.to_string(), player)?;
+        }
+        self.append_transactions(&records.transactions)?;
+        for lobby in records.lobbies.into_values() {
+            self.store_lobby(lobby)?;
+        }
+        for snapshot in records.match_snapshots.into_values() {
+            self.store_match_snapshot(snapshot)?;
+        }
+        for history in &records.hand_histories {
+            self.store_hand_history(history)?;
+        }
+        for friendship in &records.friendships {
+            self.store_friendship(friendship)?;
+        }
+        for settlement in records.pending_settlements.values() {
+            self.store_pending_settlement(settlement)?;
+        }
+        Ok(())
+    }
+
+    fn get_record_path(&self, dir: &str, key: &str) -> PathBuf {
+        self.path.join(dir).join(format!("{key}.json"))
+    }
+
+    fn write_record<T: Serialize>(&self, dir: &str, key: &str, record: &T) -> Result<(), AppError> {
+        let contents = serde_json::to_vec(record).map_err(AppError::internal)?;
+        let path = self.get_record_path(dir, key);
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, contents).map_err(AppError::internal)?;
+        fs::rename(&tmp_path, &path).map_err(AppError::internal)
+    }
+
+    fn remove_record(&self, dir: &str, key: &str) -> Result<(), AppError> {
+        match fs::remove_file(self.get_record_path(dir, key)) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(AppError::internal(err)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Leftover temporary files of interrupted writes get skipped.
+    fn read_records<T: DeserializeOwned>(&self, dir: &str) -> Result<Vec<T>, AppError> {
+        let mut records = Vec::new();
+        for entry in fs::read_dir(self.path.join(dir)).map_err(AppError::internal)? {
+            let path = entry.map_err(AppError::internal)?.path();
+            if path.extension().is_some_and(|extension| extension == "json") {
+                let contents = fs::read(&path).map_err(AppError::internal)?;
+                records.push(serde_json::from_slice(&contents).map_err(AppError::internal)?);
+            }
+        }
+        Ok(records)
+    }
+
+    /// Batches are written as a single line, so they're either recorded as a whole or not at all.
+    fn append_transactions(&self, transactions: &[LedgerTransaction]) -> Result<(), AppError> {
+        if transactions.is_empty() {
+            return Ok(());
+        }
+        let mut line = serde_json::to_vec(transactions).map_err(AppError::internal)?;
+        line.push(b'\n');
+        let mut transaction_log = self.transaction_log.lock().unwrap();
+        transaction_log.write_all(&line).map_err(AppError::internal)?;
+        transaction_log.sync_data().map_err(AppError::internal)
+    }
+
+    /// A batch torn by a crash can only ever be the unterminated last line, which then gets cut off.
+    fn drop_torn_transactions(transaction_log_path: &Path) -> Result<(), AppError> {
+        let Ok(contents) = fs::read(transaction_log_path) else {
+            return Ok(()); // not created yet
+        };
+        if contents.last().is_none_or(|byte| *byte == b'\n') {
+            return Ok(());
+        }
+        let length = contents.iter().rposition(|byte| *byte == b'\n').map_or(0, |index| index + 1);
+        let transaction_log = OpenOptions::new().write(true).open(transaction_log_path).map_err(AppError::internal)?;
+        transaction_log.set_len(length as u64).map_err(AppError::internal)
+    }
+
+    fn read_transactions(&self) -> Result<Vec<LedgerTransaction>, AppError> {
+        let transaction_log = File::open(self.path.join(Self::TRANSACTION_LOG)).map_err(AppError::internal)?;
+        let mut transactions = Vec::new();
+        for line in BufReader::new(transaction_log).lines() {
+            let line = line.map_err(AppError::internal)?;
+            let batch: Vec<LedgerTransaction> = serde_json::from_str(&line).map_err(AppError::internal)?;
+            transactions.extend(batch);
+        }
+        Ok(transactions)
+    }
+
+    fn get_friendship_key((player_id, other_player_id): (Uuid, Uuid)) -> String {
+        format!("{player_id}_{other_player_id}")
+    }
+}
+
+impl Storage for FileStorage {
+    /// Snapshots and pending settlements of matches that got settled right before a crash are left out.
+    fn load(&self) -> Result<StorageRecords, AppError> {
+        let transactions = self.read_transactions()?;
+        let mut hand_histories: Vec<HandHistory> = self.read_records(Self::HAND_HISTORIES_DIR)?;
+        hand_histories.sort_by_key(|history| (history.ended_at, history.started_at));
+        let mut records = StorageRecords {
+            players: self.read_records::<Player>(Self::PLAYERS_DIR)?
+                .into_iter()
+                .map(|player| (player.player_id, player))
+                .collect(),
+            transactions: Vec::new(),
+            lobbies: self.read_records::<LobbyRecord>(Self::LOBBIES_DIR)?
+                .into_iter()
+                .map(|lobby| (lobby.lobby_id, lobby))
+                .collect(),
+            match_snapshots: self.read_records::<MatchSnapshot>(Self::MATCH_SNAPSHOTS_DIR)?
+                .into_iter()
+                .map(|snapshot| (snapshot.match_id, snapshot))
+                .collect(),
+            hand_histories,
+            friendships: self.read_records(Self::FRIENDSHIPS_DIR)?,
+            pending_settlements: self.read_records::<PendingSettlement>(Self::PENDING_SETTLEMENTS_DIR)?
+                .into_iter()
+                .map(|settlement| (settlement.match_id, settlement))
+                .collect(),
+        };
+        for transaction in &transactions {
+            if let Some(match_id) = &transaction.match_id {
+                records.match_snapshots.remove(match_id);
+                records.pending_settlements.remove(match_id);
+            }
+        }
+        for match_id in records.pending_settlements.keys() {
+            records.match_snapshots.remove(match_id); // left behind by a crash in between
+        }
+        records.transactions = transactions;
+        Ok(records)
+    }
+
+    fn store_player(&self, player: &Player, transactions: &[LedgerTransaction]) -> Result<(), AppError> {
+        self.write_record(Self::PLAYERS_DIR, &player.player_id.to_string(), player)?;
+        self.append_transactions(transactions)
+    }
+
+    fn store_settlement(&self, match_id: &Uuid, transactions: &[LedgerTransaction]) -> Result<(), AppError> {
+        self.append_transactions(transactions)?;
+        self.remove_record(Self::MATCH_SNAPSHOTS_DIR, &match_id.to_string())?;
+        self.remove_record(Self::PENDING_SETTLEMENTS_DIR, &match_id.to_string())
+    }
+
+    fn store_pending_settlement(&self, settlement: &PendingSettlement) -> Result<(), AppError> {
+        let match_id = settlement.match_id.to_string();
+        self.write_record(Self::PENDING_SETTLEMENTS_DIR, &match_id, settlement)?;
+        self.remove_record(Self::MATCH_SNAPSHOTS_DIR, &match_id)
+    }
+
+    fn store_lobby(&self, lobby: LobbyRecord) -> Result<(), AppError> {
+        self.write_record(Self::LOBBIES_DIR, &lobby.lobby_id.to_string(), &lobby)
+    }
+
+    fn remove_lobby(&self, lobby_id: &Uuid) -> Result<(), AppError> {
+        self.remove_record(Self::LOBBIES_DIR, &lobby_id.to_string())
+    }
+
+    fn store_match_snapshot(&self, snapshot: MatchSnapshot) -> Result<(), AppError> {
+        self.write_record(Self::MATCH_SNAPSHOTS_DIR, &snapshot.match_id.to_string(), &snapshot)
+    }
+
+    fn store_hand_history(&self, history: &HandHistory) -> Result<(), AppError> {
+        self.write_record(Self::HAND_HISTORIES_DIR, &history.match_id.to_string(), history)
+    }
+
+    fn store_friendship(&self, friendship: &Friendship) -> Result<(), AppError> {
+        self.write_record(Self::FRIENDSHIPS_DIR, &Self::get_friendship_key(friendship.get_key()), friendship)
+    }
+
+    fn remove_friendship(&self, key: (Uuid, Uuid)) -> Result<(), AppError> {
+        self.remove_record(Self::FRIENDSHIPS_DIR, &Self::get_friendship_key(key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ledger::LedgerTransactionKind;
+    #[test]
+    fn survives_reopening() {
+        let path = std::env::temp_dir().join(format!("rustic_poker_{}", Uuid::new_v4()));
+        let (player, _) = Player::register();
+        let buy_in = LedgerTransaction::new(player.player_id, None, LedgerTransactionKind::BuyIn, 500);
+        let ante = LedgerTransaction::new(player.player_id, Some(Uuid::new_v4()), LedgerTransactionKind::Ante, 10);
+        {
+            let storage = FileStorage::open(&path).unwrap();
+            storage.store_player(&player, &[buy_in]).unwrap();
+            storage.store_settlement(&Uuid::new_v4(), &[ante]).unwrap();
+        }
+        OpenOptions::new().append(true).open(path.join(FileStorage::TRANSACTION_LOG)).unwrap().write_all(b"[{\"torn").unwrap();
+        let records = FileStorage::open(&path).unwrap().load().unwrap();
+        fs::remove_dir_all(&path).unwrap();
+        assert_eq!(records.players[&player.player_id], player);
+        assert_eq!(records.transactions.len(), 2);
+        assert_eq!(records.transactions[1].kind, LedgerTransactionKind::Ante);
+    }
+}
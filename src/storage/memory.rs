@@ -0,0 +1,66 @@
+use std::sync::Mutex;
+use uuid::Uuid;
+
+use crate::common::error::AppError;
+use crate::friend::Friendship;
+use crate::history::HandHistory;
+use crate::ledger::LedgerTransaction;
+use crate::player::Player;
+use super::{LobbyRecord, MatchSnapshot, PendingSettlement, Storage, StorageRecords};
+
+/// Volatile storage backend, mainly meant for testing.
+#[derive(Debug, Default)]
+pub(crate) struct InMemoryStorage {
+    records: Mutex<StorageRecords>,
+}
+
+impl Storage for InMemoryStorage {
+    fn load(&self) -> Result<StorageRecords, AppError> {
+        Ok(self.records.lock().unwrap().clone())
+    }
+
+    fn store_player(&self, player: &Player, transactions: &[LedgerTransaction]) -> Result<(), AppError> {
+        self.records.lock().unwrap().insert_player(player, transactions);
+        Ok(())
+    }
+
+    fn store_settlement(&self, match_id: &Uuid, transactions: &[LedgerTransaction]) -> Result<(), AppError> {
+        self.records.lock().unwrap().insert_settlement(match_id, transactions);
+        Ok(())
+    }
+
+    fn store_pending_settlement(&self, settlement: &PendingSettlement) -> Result<(), AppError> {
+        self.records.lock().unwrap().insert_pending_settlement(settlement);
+        Ok(())
+    }
+
+    fn store_lobby(&self, lobby: LobbyRecord) -> Result<(), AppError> {
+        self.records.lock().unwrap().insert_lobby(lobby);
+        Ok(())
+    }
+
+    fn remove_lobby(&self, lobby_id: &Uuid) -> Result<(), AppError> {
+        self.records.lock().unwrap().lobbies.remove(lobby_id);
+        Ok(())
+    }
+
+    fn store_match_snapshot(&self, snapshot: MatchSnapshot) -> Result<(), AppError> {
+        self.records.lock().unwrap().insert_match_snapshot(snapshot);
+        Ok(())
+    }
+
+    fn store_hand_history(&self, history: &HandHistory) -> Result<(), AppError> {
+        self.records.lock().unwrap().insert_hand_history(history);
+        Ok(())
+    }
+
+    fn store_friendship(&self, friendship: &Friendship) -> Result<(), AppError> {
+        self.records.lock().unwrap().insert_friendship(friendship);
+        Ok(())
+    }
+
+    fn remove_friendship(&self, key: (Uuid, Uuid)) -> Result<(), AppError> {
+        self.records.lock().unwrap().remove_friendship(key);
+        Ok(())
+    }
+}
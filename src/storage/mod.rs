@@ -0,0 +1,58 @@
+mod file;
+mod memory;
+mod records;
+
+pub(crate) use file::FileStorage;
+pub(crate) use memory::InMemoryStorage;
+pub(crate) use records::{LobbyRecord, MatchSnapshot, PendingSettlement, StorageRecords};
+
+use std::fmt::Debug;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::common::error::AppError;
+use crate::friend::Friendship;
+use crate::history::HandHistory;
+use crate::ledger::LedgerTransaction;
+use crate::player::Player;
+
+/// Persistence backend for any state that needs to survive server restarts.<br />
+/// Every call is expected to be durable by the time it returns.
+pub(crate) trait Storage: Debug + Send + Sync {
+    fn load(&self) -> Result<StorageRecords, AppError>;
+
+    /// Stores a player account along with its initial ledger transactions.
+    fn store_player(&self, player: &Player, transactions: &[LedgerTransaction]) -> Result<(), AppError>;
+
+    /// Stores a match's settled transactions, discarding its snapshot or pending settlement.
+    fn store_settlement(&self, match_id: &Uuid, transactions: &[LedgerTransaction]) -> Result<(), AppError>;
+
+    /// Stores a played out match's transactions for settling them on the next boot, discarding its snapshot.
+    fn store_pending_settlement(&self, settlement: &PendingSettlement) -> Result<(), AppError>;
+
+    fn store_lobby(&self, lobby: LobbyRecord) -> Result<(), AppError>;
+
+    fn remove_lobby(&self, lobby_id: &Uuid) -> Result<(), AppError>;
+
+    fn store_match_snapshot(&self, snapshot: MatchSnapshot) -> Result<(), AppError>;
+
+    fn store_hand_history(&self, history: &HandHistory) -> Result<(), AppError>;
+
+    /// Stores a friend request, replacing any previous state of the friendship.
+    fn store_friendship(&self, friendship: &Friendship) -> Result<(), AppError>;
+
+    /// Removes a friendship or friend request by its players' key.
+    fn remove_friendship(&self, key: (Uuid, Uuid)) -> Result<(), AppError>;
+}
+
+/// Runs a storage call on tokio's blocking thread pool, keeping disk I/O off the async runtime.
+pub(crate) async fn run_blocking<T, F>(storage: &Arc<dyn Storage>, call: F) -> Result<T, AppError>
+where
+    T: Send + 'static,
+    F: FnOnce(&dyn Storage) -> Result<T, AppError> + Send + 'static,
+{
+    let storage = storage.clone();
+    tokio::task::spawn_blocking(move || call(&*storage))
+        .await
+        .map_err(AppError::internal)?
+}
@@ -0,0 +1,106 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::friend::Friendship;
+use crate::game::PokerPhase;
+use crate::history::HandHistory;
+use crate::ledger::LedgerTransaction;
+use crate::lobby::{LobbyPassword, LobbySettings};
+use crate::player::Player;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub(crate) struct StorageRecords {
+    pub(crate) players: HashMap<Uuid, Player>,
+    pub(crate) transactions: Vec<LedgerTransaction>,
+    pub(crate) lobbies: HashMap<Uuid, LobbyRecord>,
+    #[serde(default)]
+    pub(crate) match_snapshots: HashMap<Uuid, MatchSnapshot>,
+    #[serde(default)]
+    pub(crate) hand_histories: Vec<HandHistory>,
+    #[serde(default)]
+    pub(crate) friendships: Vec<Friendship>, // pending requests included
+    #[serde(default)]
+    pub(crate) pending_settlements: HashMap<Uuid, PendingSettlement>,
+}
+
+/// Persistent subset of a lobby's state.<br />
+/// Matchmaking and in-game state are transient and don't get restored.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct LobbyRecord {
+    pub(crate) lobby_id: Uuid,
+    pub(crate) name: String,
+    pub(crate) host_player_id: Uuid,
+    pub(crate) host_player_name: String,
+    pub(crate) player_ids: HashSet<Uuid>,
+    #[serde(default)]
+    pub(crate) seats: BTreeMap<u8, Uuid>,
+    #[serde(default)]
+    pub(crate) dealer_seat: Option<u8>,
+    pub(crate) settings: LobbySettings,
+    #[serde(default)]
+    pub(crate) password_hash: Option<LobbyPassword>,
+    /// Plaintext password of records predating hashing, only ever read.
+    #[serde(default, skip_serializing)]
+    pub(crate) password: Option<String>,
+}
+
+/// Latest state of an in-progress match, taken after each phase action.<br />
+/// Leaves out the hand history's replay frames.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct MatchSnapshot {
+    pub(crate) match_id: Uuid,
+    pub(crate) lobby_id: Uuid,
+    pub(crate) poker_phase: PokerPhase,
+    pub(crate) timestamp: DateTime<Utc>,
+}
+
+/// Transactions of a played out match whose settlement failed, to be settled on the next boot.<br />
+/// Takes the place of the match's snapshot, as the match mustn't be played out again.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct PendingSettlement {
+    pub(crate) match_id: Uuid,
+    pub(crate) transactions: Vec<LedgerTransaction>,
+    pub(crate) history: Option<HandHistory>, // none for aborted matches
+}
+
+impl StorageRecords {
+    pub fn insert_player(&mut self, player: &Player, transactions: &[LedgerTransaction]) {
+        self.players.insert(player.player_id, player.clone());
+        self.transactions.extend_from_slice(transactions);
+    }
+
+    /// Settled matches no longer need to be resumed.
+    pub fn insert_settlement(&mut self, match_id: &Uuid, transactions: &[LedgerTransaction]) {
+        self.transactions.extend_from_slice(transactions);
+        self.match_snapshots.remove(match_id);
+        self.pending_settlements.remove(match_id);
+    }
+
+    pub fn insert_pending_settlement(&mut self, settlement: &PendingSettlement) {
+        self.match_snapshots.remove(&settlement.match_id);
+        self.pending_settlements.insert(settlement.match_id, settlement.clone());
+    }
+
+    pub fn insert_lobby(&mut self, lobby: LobbyRecord) {
+        self.lobbies.insert(lobby.lobby_id, lobby);
+    }
+
+    pub fn insert_hand_history(&mut self, history: &HandHistory) {
+        self.hand_histories.push(history.clone());
+    }
+
+    pub fn insert_friendship(&mut self, friendship: &Friendship) {
+        self.remove_friendship(friendship.get_key());
+        self.friendships.push(friendship.clone());
+    }
+
+    pub fn remove_friendship(&mut self, key: (Uuid, Uuid)) {
+        self.friendships.retain(|friendship| friendship.get_key() != key);
+    }
+
+    pub fn insert_match_snapshot(&mut self, snapshot: MatchSnapshot) {
+        self.match_snapshots.insert(snapshot.match_id, snapshot);
+    }
+}
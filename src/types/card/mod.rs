@@ -8,11 +8,12 @@ pub(crate) use suit::*;
 use std::cmp::Ordering;
 use std::convert::TryInto;
 use std::fmt::{Display, Formatter};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::common::error::AppError;
 
-#[derive(Eq, Clone, Debug, Hash)]
+#[derive(Eq, Clone, Debug, Hash, Serialize, Deserialize)]
 pub(crate) struct Card {
     pub rank: CardRank,
     pub suit: CardSuit,
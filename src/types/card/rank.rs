@@ -1,9 +1,10 @@
 use std::cmp::Ordering;
 use std::fmt::{Display, Formatter};
+use serde::{Deserialize, Serialize};
 
 use crate::types::card::CardParseError;
 
-#[derive(Eq, PartialEq, Clone, Debug, Hash)]
+#[derive(Eq, PartialEq, Clone, Debug, Hash, Serialize, Deserialize)]
 pub(crate) enum CardRank {
     Ace,
     King,
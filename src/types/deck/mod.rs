@@ -1,9 +1,10 @@
 use std::collections::HashSet;
+use serde::{Deserialize, Serialize};
 use rand::{rng, seq::SliceRandom};
 
 use super::card::{Card, CardRank, CardSuit};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CardDeck {
     deck: Vec<Card>,
     discard_pile: HashSet<Card>,
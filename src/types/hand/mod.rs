@@ -5,6 +5,7 @@ pub(super) mod tie_breakers;
 pub(crate) use rank::*;
 
 use std::cmp::Ordering;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use itertools::Itertools;
 use crate::types::{
@@ -12,7 +13,7 @@ use crate::types::{
     hand::tie_breakers::TieBreakers,
 };
 
-#[derive(Eq, Clone, Debug, Hash)]
+#[derive(Eq, Clone, Debug, Hash, Serialize, Deserialize)]
 pub(crate) struct Hand {
     pub(crate) raw_hand_str: String, // eg: "AD KD QD JD 10D"
     pub(crate) cards: [Card; 5],
@@ -1,12 +1,13 @@
 use std::cmp::Ordering;
 use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
 use itertools::Itertools;
 use crate::types::{
     card::{Card, CardRank, CardSuit, ShiftAce},
     hand::HandParseError,
 };
 
-#[derive(Eq, PartialEq, Clone, Debug, Hash)]
+#[derive(Eq, PartialEq, Clone, Debug, Hash, Serialize, Deserialize)]
 pub(crate) enum HandRank {
     RoyalFlush,
     StraightFlush,
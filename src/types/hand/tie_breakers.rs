@@ -1,9 +1,10 @@
 use std::cmp::Ordering;
+use serde::{Deserialize, Serialize};
 use crate::types::card::CardRank;
 
 /// Tuple structs containing tie-breaker card ranks and vectors of card ranks.
 /// Vector elements are sorted in descending order of priority.
-#[derive(Eq, PartialEq, Clone, Debug, Hash)]
+#[derive(Eq, PartialEq, Clone, Debug, Hash, Serialize, Deserialize)]
 pub(crate) enum TieBreakers {
     // Kickers, Pairs and Sets for each HandRank
     StraightFlush(CardRank),     // top card
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::game::DiscardedCards;
@@ -15,7 +16,7 @@ use crate::types::hand::Hand;
 //     pub(crate) tie_breakers: Option<TieBreakers>,
 // }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(crate) struct StatefulCard {
     pub(crate) card: Card,
     pub(crate) discarded: bool,